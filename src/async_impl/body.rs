@@ -2,20 +2,30 @@ use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use bytes::Bytes;
 use futures_core::Stream;
 use http_body::Body as HttpBody;
+use hyper::client::connect::CaptureConnection;
 use pin_project_lite::pin_project;
 use tokio::time::Sleep;
 
+use crate::connect::expect_continue::Wait;
+
 /// An asynchronous request body.
 pub struct Body {
     inner: Inner,
 }
 
 // The `Stream` trait isn't stable, so the impl isn't public.
-pub(crate) struct ImplStream(Body);
+pub(crate) struct ImplStream {
+    body: Body,
+    // Holds back the first chunk until the server says to go ahead.
+    expect_continue: Option<Wait>,
+    // Sent once the body is done.
+    trailers: Option<http::HeaderMap>,
+}
 
 enum Inner {
     Reusable(Bytes),
@@ -40,10 +50,46 @@ pin_project! {
 
 struct WrapHyper(hyper::Body);
 
+#[cfg(feature = "stream")]
+struct SizedBody {
+    inner: Pin<
+        Box<
+            dyn HttpBody<Data = Bytes, Error = Box<dyn std::error::Error + Send + Sync>>
+                + Send
+                + Sync,
+        >,
+    >,
+    remaining: u64,
+    // The final bytes are held back until the stream ends, so that a stream
+    // longer than declared fails before the server sees a complete body.
+    last: Option<Bytes>,
+    ended: bool,
+}
+
 impl Body {
     /// Returns a reference to the internal data of the `Body`.
     ///
     /// `None` is returned, if the underlying data is a stream.
+    ///
+    /// Bodies set from bytes or strings, or by `RequestBuilder::json` and
+    /// `RequestBuilder::form`, are kept in memory, so these are exactly the
+    /// bytes that will be sent. This allows computing a digest or
+    /// signature of a built `Request` before sending it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn run() -> Result<(), reqwest::Error> {
+    /// let req = reqwest::Client::new()
+    ///     .post("https://example.com/upload")
+    ///     .body("payload")
+    ///     .build()?;
+    ///
+    /// let bytes = req.body().and_then(|body| body.as_bytes());
+    /// assert_eq!(bytes, Some(&b"payload"[..]));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn as_bytes(&self) -> Option<&[u8]> {
         match &self.inner {
             Inner::Reusable(bytes) => Some(bytes.as_ref()),
@@ -84,6 +130,56 @@ impl Body {
         Body::stream(stream)
     }
 
+    /// Wrap a futures `Stream` of a known length in a box inside `Body`.
+    ///
+    /// The request is sent with a `Content-Length` of `len` instead of being
+    /// chunked, while the body is still streamed. If the stream yields more
+    /// or fewer bytes than `len`, sending the request fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use reqwest::Body;
+    /// # use futures_util;
+    /// # fn main() {
+    /// let chunks: Vec<Result<_, ::std::io::Error>> = vec![
+    ///     Ok("hello"),
+    ///     Ok(" "),
+    ///     Ok("world"),
+    /// ];
+    ///
+    /// let stream = futures_util::stream::iter(chunks);
+    ///
+    /// let body = Body::sized_stream(stream, 11);
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    pub fn sized_stream<S>(stream: S, len: u64) -> Body
+    where
+        S: futures_core::stream::TryStream + Send + Sync + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        match Body::stream(stream).inner {
+            Inner::Streaming { body, timeout } => Body {
+                inner: Inner::Streaming {
+                    body: Box::pin(SizedBody {
+                        inner: body,
+                        remaining: len,
+                        last: None,
+                        ended: false,
+                    }),
+                    timeout,
+                },
+            },
+            Inner::Reusable(_) => unreachable!("Body::stream is always streaming"),
+        }
+    }
+
     pub(crate) fn stream<S>(stream: S) -> Body
     where
         S: futures_core::stream::TryStream + Send + Sync + 'static,
@@ -103,6 +199,20 @@ impl Body {
         }
     }
 
+    /// Compresses the body with Brotli, as a stream of unknown length.
+    #[cfg(feature = "brotli")]
+    pub(crate) fn brotli(self) -> Body {
+        use async_compression::tokio::bufread::BrotliEncoder;
+        use futures_util::TryStreamExt;
+        use tokio_util::io::{ReaderStream, StreamReader};
+
+        let reader = StreamReader::new(TryStreamExt::map_err(
+            self.into_stream(),
+            crate::error::into_io,
+        ));
+        Body::stream(ReaderStream::new(BrotliEncoder::new(reader)))
+    }
+
     pub(crate) fn response(body: hyper::Body, timeout: Option<Pin<Box<Sleep>>>) -> Body {
         Body {
             inner: Inner::Streaming {
@@ -149,7 +259,11 @@ impl Body {
     }
 
     pub(crate) fn into_stream(self) -> ImplStream {
-        ImplStream(self)
+        ImplStream {
+            body: self,
+            expect_continue: None,
+            trailers: None,
+        }
     }
 
     #[cfg(feature = "multipart")]
@@ -216,6 +330,20 @@ impl fmt::Debug for Body {
 
 // ===== impl ImplStream =====
 
+impl ImplStream {
+    /// Waits for a `100 Continue` on `connection` before sending any of the
+    /// body, for up to `timeout`.
+    pub(crate) fn expect_continue(&mut self, timeout: Duration, connection: CaptureConnection) {
+        self.expect_continue = Some(Wait::new(timeout, connection));
+    }
+
+    /// Sends `trailers` after the body.
+    pub(crate) fn trailers(mut self, trailers: http::HeaderMap) -> ImplStream {
+        self.trailers = Some(trailers);
+        self
+    }
+}
+
 impl HttpBody for ImplStream {
     type Data = Bytes;
     type Error = crate::Error;
@@ -224,7 +352,12 @@ impl HttpBody for ImplStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
-        let opt_try_chunk = match self.0.inner {
+        if let Some(wait) = self.expect_continue.as_mut() {
+            futures_core::ready!(wait.poll(cx));
+            self.expect_continue = None;
+        }
+
+        let opt_try_chunk = match self.body.inner {
             Inner::Streaming {
                 ref mut body,
                 ref mut timeout,
@@ -250,21 +383,24 @@ impl HttpBody for ImplStream {
     }
 
     fn poll_trailers(
-        self: Pin<&mut Self>,
+        mut self: Pin<&mut Self>,
         _cx: &mut Context,
     ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
-        Poll::Ready(Ok(None))
+        Poll::Ready(Ok(self.trailers.take()))
     }
 
     fn is_end_stream(&self) -> bool {
-        match self.0.inner {
+        if self.trailers.is_some() {
+            return false;
+        }
+        match self.body.inner {
             Inner::Streaming { ref body, .. } => body.is_end_stream(),
             Inner::Reusable(ref bytes) => bytes.is_empty(),
         }
     }
 
     fn size_hint(&self) -> http_body::SizeHint {
-        match self.0.inner {
+        match self.body.inner {
             Inner::Streaming { ref body, .. } => body.size_hint(),
             Inner::Reusable(ref bytes) => {
                 let mut hint = http_body::SizeHint::default();
@@ -311,6 +447,64 @@ where
     }
 }
 
+// ===== impl SizedBody =====
+
+#[cfg(feature = "stream")]
+impl HttpBody for SizedBody {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        if self.ended {
+            return Poll::Ready(None);
+        }
+        loop {
+            match futures_core::ready!(self.inner.as_mut().poll_data(cx)) {
+                Some(Ok(chunk)) => {
+                    let len = chunk.len() as u64;
+                    if len > self.remaining {
+                        return Poll::Ready(Some(Err(
+                            "body stream is longer than its declared length".into(),
+                        )));
+                    }
+                    self.remaining -= len;
+                    if self.remaining > 0 {
+                        return Poll::Ready(Some(Ok(chunk)));
+                    }
+                    if !chunk.is_empty() {
+                        self.last = Some(chunk);
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None if self.remaining > 0 => {
+                    return Poll::Ready(Some(Err(
+                        "body stream is shorter than its declared length".into(),
+                    )));
+                }
+                None => {
+                    self.ended = true;
+                    return Poll::Ready(self.last.take().map(Ok));
+                }
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let last = self.last.as_ref().map_or(0, |last| last.len() as u64);
+        http_body::SizeHint::with_exact(self.remaining + last)
+    }
+}
+
 // ===== impl WrapHyper =====
 
 impl HttpBody for WrapHyper {
@@ -1,18 +1,22 @@
 #[cfg(any(feature = "native-tls", feature = "__rustls",))]
 use std::any::Any;
+#[cfg(feature = "__rustls")]
+use std::collections::HashSet;
 use std::net::IpAddr;
-use std::sync::Arc;
-use std::time::Duration;
-use std::{collections::HashMap, convert::TryInto, net::SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::{collections::HashMap, convert::TryFrom, convert::TryInto, net::SocketAddr};
 use std::{fmt, str};
 
 use bytes::Bytes;
 use http::header::{
-    Entry, HeaderMap, HeaderValue, ACCEPT, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH,
-    CONTENT_TYPE, LOCATION, PROXY_AUTHORIZATION, RANGE, REFERER, TRANSFER_ENCODING, USER_AGENT,
+    Entry, HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_ENCODING, CONNECTION, CONTENT_ENCODING,
+    CONTENT_LENGTH, CONTENT_TYPE, EXPECT, LOCATION, PROXY_AUTHORIZATION, RANGE, REFERER,
+    RETRY_AFTER, TRANSFER_ENCODING, USER_AGENT,
 };
 use http::uri::Scheme;
 use http::Uri;
+use hyper::client::connect::CaptureConnection;
 use hyper::client::ResponseFuture;
 #[cfg(feature = "native-tls-crate")]
 use native_tls_crate::TlsConnector;
@@ -22,27 +26,34 @@ use rustls::RootCertStore;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::time::Sleep;
 
 use log::debug;
 
+use super::body::ImplStream;
 use super::decoder::Accepts;
+use super::decoder::Encoding;
 use super::request::{Request, RequestBuilder};
-use super::response::Response;
+use super::response::{RequestDetails, Response};
 use super::Body;
-use crate::connect::{Connector, HttpConnector};
+use crate::connect::{self, Connector, HttpConnector};
 #[cfg(feature = "cookies")]
 use crate::cookie;
 use crate::error;
 use crate::into_url::{expect_uri, try_uri};
 use crate::redirect::{self, remove_sensitive_headers};
+#[cfg(all(feature = "__tls", feature = "pinning"))]
+use crate::tls::PinnedKeys;
 #[cfg(feature = "__tls")]
 use crate::tls::TlsBackend;
+#[cfg(feature = "__rustls")]
+use crate::tls::{CertVerifierFn, CertificateChain};
 #[cfg(feature = "__tls")]
 use crate::Certificate;
 #[cfg(any(feature = "native-tls", feature = "__rustls"))]
 use crate::Identity;
-use crate::{IntoUrl, Method, Proxy, StatusCode, Url};
+use crate::{IntoUrl, Method, Proxy, StatusCode, Url, Version};
 
 /// An asynchronous `Client` to make Requests with.
 ///
@@ -68,6 +79,7 @@ pub struct ClientBuilder {
     config: Config,
 }
 
+#[derive(Clone, Copy)]
 enum HttpVersionPref {
     Http1,
     Http2,
@@ -77,6 +89,9 @@ enum HttpVersionPref {
 struct Config {
     // NOTE: When adding a new field, update `fmt::Debug for ClientBuilder`
     accepts: Accepts,
+    #[cfg(feature = "gzip")]
+    gzip_skip_content_types: Vec<String>,
+    range_identity_encoding: bool,
     headers: HeaderMap,
     #[cfg(feature = "native-tls")]
     hostname_verification: bool,
@@ -86,34 +101,62 @@ struct Config {
     connection_verbose: bool,
     pool_idle_timeout: Option<Duration>,
     pool_max_idle_per_host: usize,
+    max_connections_per_host: Option<usize>,
+    provided_stream: Option<connect::provided::Provided>,
     tcp_keepalive: Option<Duration>,
     #[cfg(any(feature = "native-tls", feature = "__rustls"))]
     identity: Option<Identity>,
+    #[cfg(feature = "__rustls")]
+    host_identities: HashMap<String, Identity>,
+    #[cfg(feature = "__rustls")]
+    insecure_hosts: HashSet<String>,
     proxies: Vec<Proxy>,
     auto_sys_proxy: bool,
     redirect_policy: redirect::Policy,
-    referer: bool,
+    record_redirects: bool,
+    retry_statuses: Vec<StatusCode>,
+    max_retries: usize,
+    retry_backoff: RetryBackoff,
+    referer: redirect::RefererPolicy,
+    request_id: Option<RequestIdGenerator>,
+    request_interceptor: Option<RequestInterceptor>,
+    normalize_headers: bool,
+    default_content_type: Option<HeaderValue>,
     timeout: Option<Duration>,
+    total_timeout: Option<Duration>,
     #[cfg(feature = "__tls")]
     root_certs: Vec<Certificate>,
+    #[cfg(all(feature = "__tls", feature = "pinning"))]
+    pinned_keys: PinnedKeys,
+    #[cfg(feature = "__rustls")]
+    cert_verifier: Option<CertVerifierFn>,
     #[cfg(feature = "__tls")]
     tls_built_in_root_certs: bool,
     #[cfg(feature = "__tls")]
+    tls_handshake_timeout: Option<Duration>,
+    #[cfg(feature = "__tls")]
     tls: TlsBackend,
     http_version_pref: HttpVersionPref,
     http1_title_case_headers: bool,
+    http1_read_buf_exact_size: Option<usize>,
+    http1_max_buf_size: Option<usize>,
+    expect_continue_timeout: Option<Duration>,
     http2_initial_stream_window_size: Option<u32>,
     http2_initial_connection_window_size: Option<u32>,
     http2_adaptive_window: bool,
     http2_max_frame_size: Option<u32>,
     local_address: Option<IpAddr>,
     nodelay: bool,
+    reuse_address: bool,
     #[cfg(feature = "cookies")]
     cookie_store: Option<Arc<dyn cookie::CookieStore>>,
+    #[cfg(feature = "cookies")]
+    cookie_change: Option<CookieChangeFn>,
     trust_dns: bool,
     error: Option<crate::Error>,
     https_only: bool,
-    dns_overrides: HashMap<String, SocketAddr>,
+    dns_overrides: HashMap<String, Vec<SocketAddr>>,
+    connect_attempts: Option<usize>,
 }
 
 impl Default for ClientBuilder {
@@ -134,6 +177,9 @@ impl ClientBuilder {
             config: Config {
                 error: None,
                 accepts: Accepts::default(),
+                #[cfg(feature = "gzip")]
+                gzip_skip_content_types: Vec::new(),
+                range_identity_encoding: false,
                 headers,
                 #[cfg(feature = "native-tls")]
                 hostname_verification: true,
@@ -143,35 +189,67 @@ impl ClientBuilder {
                 connection_verbose: false,
                 pool_idle_timeout: Some(Duration::from_secs(90)),
                 pool_max_idle_per_host: std::usize::MAX,
+                max_connections_per_host: None,
+                provided_stream: None,
                 // TODO: Re-enable default duration once hyper's HttpConnector is fixed
                 // to no longer error when an option fails.
                 tcp_keepalive: None, //Some(Duration::from_secs(60)),
                 proxies: Vec::new(),
                 auto_sys_proxy: true,
                 redirect_policy: redirect::Policy::default(),
-                referer: true,
+                record_redirects: false,
+                retry_statuses: Vec::new(),
+                max_retries: 0,
+                retry_backoff: RetryBackoff {
+                    base: Duration::from_millis(100),
+                    max: Duration::from_secs(10),
+                    jitter: false,
+                },
+                referer: redirect::RefererPolicy::NoReferrerWhenDowngrade,
+                request_id: None,
+                request_interceptor: None,
+                normalize_headers: false,
+                default_content_type: None,
                 timeout: None,
+                total_timeout: None,
                 #[cfg(feature = "__tls")]
                 root_certs: Vec::new(),
+                #[cfg(all(feature = "__tls", feature = "pinning"))]
+                pinned_keys: PinnedKeys::default(),
+                #[cfg(feature = "__rustls")]
+                cert_verifier: None,
                 #[cfg(feature = "__tls")]
                 tls_built_in_root_certs: true,
+                #[cfg(feature = "__tls")]
+                tls_handshake_timeout: None,
                 #[cfg(any(feature = "native-tls", feature = "__rustls"))]
                 identity: None,
+                #[cfg(feature = "__rustls")]
+                host_identities: HashMap::new(),
+                #[cfg(feature = "__rustls")]
+                insecure_hosts: HashSet::new(),
                 #[cfg(feature = "__tls")]
                 tls: TlsBackend::default(),
                 http_version_pref: HttpVersionPref::All,
                 http1_title_case_headers: false,
+                http1_read_buf_exact_size: None,
+                http1_max_buf_size: None,
+                expect_continue_timeout: Some(Duration::from_secs(1)),
                 http2_initial_stream_window_size: None,
                 http2_initial_connection_window_size: None,
                 http2_adaptive_window: false,
                 http2_max_frame_size: None,
                 local_address: None,
                 nodelay: true,
+                reuse_address: false,
                 trust_dns: cfg!(feature = "trust-dns"),
                 #[cfg(feature = "cookies")]
                 cookie_store: None,
+                #[cfg(feature = "cookies")]
+                cookie_change: None,
                 https_only: false,
                 dns_overrides: HashMap::new(),
+                connect_attempts: None,
             },
         }
     }
@@ -203,24 +281,51 @@ impl ClientBuilder {
 
             let http = match config.trust_dns {
                 false => {
-                    if config.dns_overrides.is_empty() {
+                    if config.dns_overrides.is_empty() && config.connect_attempts.is_none() {
                         HttpConnector::new_gai()
                     } else {
-                        HttpConnector::new_gai_with_overrides(config.dns_overrides)
+                        HttpConnector::new_gai_with_overrides(
+                            config.dns_overrides,
+                            config.connect_attempts,
+                        )
                     }
                 }
                 #[cfg(feature = "trust-dns")]
                 true => {
-                    if config.dns_overrides.is_empty() {
+                    if config.dns_overrides.is_empty() && config.connect_attempts.is_none() {
                         HttpConnector::new_trust_dns()?
                     } else {
-                        HttpConnector::new_trust_dns_with_overrides(config.dns_overrides)?
+                        HttpConnector::new_trust_dns_with_overrides(
+                            config.dns_overrides,
+                            config.connect_attempts,
+                        )?
                     }
                 }
                 #[cfg(not(feature = "trust-dns"))]
                 true => unreachable!("trust-dns shouldn't be enabled unless the feature is"),
             };
 
+            #[cfg(feature = "__rustls")]
+            if config.cert_verifier.is_some() && !matches!(config.tls, TlsBackend::Rustls) {
+                return Err(crate::error::builder(
+                    "custom_cert_verifier requires the rustls TLS backend",
+                ));
+            }
+
+            #[cfg(feature = "__rustls")]
+            if !config.host_identities.is_empty() && !matches!(config.tls, TlsBackend::Rustls) {
+                return Err(crate::error::builder(
+                    "identity_for_host requires the rustls TLS backend",
+                ));
+            }
+
+            #[cfg(feature = "__rustls")]
+            if !config.insecure_hosts.is_empty() && !matches!(config.tls, TlsBackend::Rustls) {
+                return Err(crate::error::builder(
+                    "danger_accept_invalid_certs_for requires the rustls TLS backend",
+                ));
+            }
+
             #[cfg(feature = "__tls")]
             match config.tls {
                 #[cfg(feature = "default-tls")]
@@ -291,7 +396,9 @@ impl ClientBuilder {
                 ),
                 #[cfg(feature = "__rustls")]
                 TlsBackend::Rustls => {
-                    use crate::tls::NoVerifier;
+                    use crate::tls::{
+                        CustomVerifier, NoVerifier, PerHostVerifier, VerificationRecorder,
+                    };
 
                     let mut tls = rustls::ClientConfig::new();
                     match config.http_version_pref {
@@ -316,31 +423,56 @@ impl ClientBuilder {
                         tls.root_store.roots.extend_from_slice(roots_slice);
                     }
 
-                    if !config.certs_verification {
-                        tls.dangerous()
-                            .set_certificate_verifier(Arc::new(NoVerifier));
-                    }
+                    let verifier: Arc<dyn rustls::ServerCertVerifier> =
+                        if !config.certs_verification {
+                            Arc::new(NoVerifier)
+                        } else {
+                            let verifier = match config.cert_verifier {
+                                Some(verifier) => Arc::new(CustomVerifier(verifier)) as _,
+                                None => Arc::new(rustls::WebPKIVerifier::new()) as _,
+                            };
+                            if !config.insecure_hosts.is_empty() {
+                                Arc::new(PerHostVerifier {
+                                    insecure_hosts: config.insecure_hosts,
+                                    inner: verifier,
+                                })
+                            } else {
+                                verifier
+                            }
+                        };
+                    tls.dangerous()
+                        .set_certificate_verifier(Arc::new(VerificationRecorder(verifier)));
 
                     for cert in config.root_certs {
                         cert.add_to_rustls(&mut tls)?;
                     }
 
+                    let mut host_configs = HashMap::new();
+                    for (host, id) in config.host_identities {
+                        let mut host_tls = tls.clone();
+                        id.add_to_rustls(&mut host_tls)?;
+                        host_configs.insert(host, host_tls);
+                    }
+
                     if let Some(id) = config.identity {
                         id.add_to_rustls(&mut tls)?;
                     }
 
-                    Connector::new_rustls_tls(
+                    let mut connector = Connector::new_rustls_tls(
                         http,
                         tls,
                         proxies.clone(),
                         user_agent(&config.headers),
                         config.local_address,
                         config.nodelay,
-                    )
+                    );
+                    connector.set_rustls_host_configs(host_configs);
+                    connector.set_records_tls_verification();
+                    connector
                 }
                 #[cfg(any(feature = "native-tls", feature = "__rustls",))]
                 TlsBackend::UnknownPreconfigured => {
-                    return Err(crate::error::builder(
+                    return Err(crate::error::tls_backend(
                         "Unknown TLS backend passed to `use_preconfigured_tls`",
                     ));
                 }
@@ -352,6 +484,16 @@ impl ClientBuilder {
 
         connector.set_timeout(config.connect_timeout);
         connector.set_verbose(config.connection_verbose);
+        connector.set_max_connections_per_host(config.max_connections_per_host);
+        #[cfg(all(feature = "__tls", feature = "pinning"))]
+        connector.set_pinned_keys(config.pinned_keys);
+        #[cfg(feature = "__tls")]
+        connector.set_tls_handshake_timeout(config.tls_handshake_timeout);
+        connector.set_provided_stream(config.provided_stream);
+        connector.set_watches_continue(
+            config.expect_continue_timeout.is_some()
+                && !matches!(config.http_version_pref, HttpVersionPref::Http2),
+        );
 
         let mut builder = hyper::Client::builder();
         if matches!(config.http_version_pref, HttpVersionPref::Http2) {
@@ -376,25 +518,58 @@ impl ClientBuilder {
         builder.pool_idle_timeout(config.pool_idle_timeout);
         builder.pool_max_idle_per_host(config.pool_max_idle_per_host);
         connector.set_keepalive(config.tcp_keepalive);
+        connector.set_reuse_address(config.reuse_address);
 
         if config.http1_title_case_headers {
             builder.http1_title_case_headers(true);
         }
+        if let Some(sz) = config.http1_read_buf_exact_size {
+            builder.http1_read_buf_exact_size(sz);
+        }
+        if let Some(max) = config.http1_max_buf_size {
+            // hyper panics below its minimum buffer size.
+            if max < HTTP1_MIN_BUF_SIZE {
+                return Err(crate::error::builder(format!(
+                    "http1_max_buf_size must be at least {}",
+                    HTTP1_MIN_BUF_SIZE
+                )));
+            }
+            builder.http1_max_buf_size(max);
+        }
 
-        let hyper_client = builder.build(connector);
+        let hyper_client = builder.build(connector.clone());
 
         let proxies_maybe_http_auth = proxies.iter().any(|p| p.maybe_has_http_auth());
 
         Ok(Client {
             inner: Arc::new(ClientRef {
                 accepts: config.accepts,
+                #[cfg(feature = "gzip")]
+                gzip_skip_content_types: config.gzip_skip_content_types,
+                range_identity_encoding: config.range_identity_encoding,
                 #[cfg(feature = "cookies")]
                 cookie_store: config.cookie_store,
+                #[cfg(feature = "cookies")]
+                cookie_change: config.cookie_change,
                 hyper: hyper_client,
+                hyper_builder: builder,
+                proxy_clients: Mutex::new(HashMap::new()),
+                connector,
                 headers: config.headers,
                 redirect_policy: config.redirect_policy,
+                record_redirects: config.record_redirects,
+                retry_statuses: config.retry_statuses,
+                max_retries: config.max_retries,
+                retry_backoff: config.retry_backoff,
                 referer: config.referer,
+                request_id: config.request_id,
+                request_interceptor: config.request_interceptor,
+                normalize_headers: config.normalize_headers,
+                default_content_type: config.default_content_type,
+                http_version_pref: config.http_version_pref,
                 request_timeout: config.timeout,
+                expect_continue_timeout: config.expect_continue_timeout,
+                total_timeout: config.total_timeout,
                 proxies,
                 proxies_maybe_http_auth,
                 https_only: config.https_only,
@@ -490,6 +665,139 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets a header that is filled with a freshly generated value on every request.
+    ///
+    /// `generate` is called once per request, for example to add a unique
+    /// `X-Request-Id` for tracing. Requests that already set this header
+    /// keep their own value. The same value is sent again when following
+    /// redirects.
+    ///
+    /// If `generate` returns a value that isn't a valid header value, the
+    /// request fails with a builder error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # fn doc() -> Result<(), reqwest::Error> {
+    /// let counter = AtomicUsize::new(0);
+    /// let client = reqwest::Client::builder()
+    ///     .request_id_header("x-request-id", move || {
+    ///         format!("req-{}", counter.fetch_add(1, Ordering::Relaxed))
+    ///     })
+    ///     .build()?;
+    /// # drop(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_id_header<K, F>(mut self, name: K, generate: F) -> ClientBuilder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        match HeaderName::try_from(name) {
+            Ok(name) => {
+                self.config.request_id = Some(RequestIdGenerator {
+                    name,
+                    generate: Arc::new(generate),
+                });
+            }
+            Err(err) => self.config.error = Some(crate::error::builder(err.into())),
+        }
+        self
+    }
+
+    /// Sets a function that can modify every request just before it is sent.
+    ///
+    /// The function is called once per request, after the client's default
+    /// headers (and the `request_id_header`, if any) have been added, and
+    /// before the request is dispatched. It can change the URL, method and
+    /// headers, for example to sign the request or attach fresh credentials.
+    /// Changes to the body are not supported.
+    ///
+    /// Headers the client adds itself while sending, such as cookies from
+    /// the cookie store or `Accept-Encoding`, are added after the function
+    /// runs. Redirects that are followed don't call it again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn doc() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::Client::builder()
+    ///     .request_interceptor(|req| {
+    ///         req.headers_mut()
+    ///             .insert("x-signature", reqwest::header::HeaderValue::from_static("signed"));
+    ///     })
+    ///     .build()?;
+    /// # drop(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_interceptor<F>(mut self, interceptor: F) -> ClientBuilder
+    where
+        F: Fn(&mut Request) + Send + Sync + 'static,
+    {
+        self.config.request_interceptor = Some(Arc::new(interceptor));
+        self
+    }
+
+    /// Send each header name at most once.
+    ///
+    /// Header names are case-insensitive, so `X-Foo` and `x-foo` are the
+    /// same header, and setting both with `RequestBuilder::header` sends
+    /// it twice. When enabled, a header given several values is sent only
+    /// with the last one, after default headers and the request interceptor
+    /// have been applied. Names are always sent in lowercase, unless
+    /// `http1_title_case_headers` is set.
+    ///
+    /// This also collapses headers repeated on purpose, so leave it off
+    /// when sending several values of a header is wanted.
+    ///
+    /// Default is `false`.
+    pub fn normalize_headers(mut self, enable: bool) -> ClientBuilder {
+        self.config.normalize_headers = enable;
+        self
+    }
+
+    /// Sets a `Content-Type` for requests that have a body but no
+    /// `Content-Type` header.
+    ///
+    /// Content types set on the request, whether by `RequestBuilder::json`,
+    /// `RequestBuilder::form` or an explicit header, are left as they are,
+    /// as is one given in `default_headers`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn doc() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::Client::builder()
+    ///     .default_content_type("application/json")
+    ///     .build()?;
+    /// let res = client
+    ///     .post("http://httpbin.org/post")
+    ///     .body(r#"{"hello":"world"}"#)
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn default_content_type<V>(mut self, value: V) -> ClientBuilder
+    where
+        V: TryInto<HeaderValue>,
+        V::Error: Into<http::Error>,
+    {
+        match value.try_into() {
+            Ok(value) => {
+                self.config.default_content_type = Some(value);
+            }
+            Err(e) => {
+                self.config.error = Some(crate::error::builder(e.into()));
+            }
+        };
+        self
+    }
+
     /// Enable a persistent cookie store for the client.
     ///
     /// Cookies received in responses will be preserved and included in
@@ -511,6 +819,30 @@ impl ClientBuilder {
         }
     }
 
+    /// Enable a persistent cookie store with relaxed matching for local hosts.
+    ///
+    /// This is like `cookie_store(true)`, except that for `localhost` and IP
+    /// address hosts, the `Domain` attribute of received cookies is ignored
+    /// (they are always stored for the host that sent them), and `Secure`
+    /// cookies are sent over plain HTTP as well. Other hosts use the usual
+    /// strict matching. This is mostly useful when testing against local
+    /// servers.
+    ///
+    /// Passing `false` disables the cookie store, like `cookie_store(false)`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn cookie_store_relaxed(self, enable: bool) -> ClientBuilder {
+        if enable {
+            self.cookie_provider(Arc::new(cookie::Jar::relaxed()))
+        } else {
+            self.cookie_store(false)
+        }
+    }
+
     /// Set the persistent cookie store for the client.
     ///
     /// Cookies received in responses will be passed to this store, and
@@ -531,6 +863,51 @@ impl ClientBuilder {
         self
     }
 
+    /// Call a function whenever a cookie is stored in or removed from the
+    /// cookie store.
+    ///
+    /// The function is called after the cookies of each response were
+    /// stored, with each cookie the store accepted, and each cookie removed
+    /// by the response or because it expired. A cookie that the store
+    /// rejects, such as one for another domain, isn't reported.
+    ///
+    /// The changes are reported by the store, which `Jar` does. A custom
+    /// `CookieStore` only reports them if it implements
+    /// `set_cookies_with_changes`. Without a cookie store, the function is
+    /// never called.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reqwest::cookie::CookieChange;
+    ///
+    /// # fn doc() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::Client::builder()
+    ///     .cookie_store(true)
+    ///     .on_cookie_change(|change| match change {
+    ///         CookieChange::Stored(cookie) => println!("stored: {}", cookie.name()),
+    ///         CookieChange::Removed(cookie) => println!("removed: {}", cookie.name()),
+    ///         _ => (),
+    ///     })
+    ///     .build()?;
+    /// # drop(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn on_cookie_change<F>(mut self, callback: F) -> ClientBuilder
+    where
+        F: Fn(&cookie::CookieChange) + Send + Sync + 'static,
+    {
+        self.config.cookie_change = Some(Arc::new(callback));
+        self
+    }
+
     /// Enable auto gzip decompression by checking the `Content-Encoding` response header.
     ///
     /// If auto gzip decompression is turned on:
@@ -554,6 +931,61 @@ impl ClientBuilder {
         self
     }
 
+    /// Decompress responses labeled `Content-Type: application/x-gzip`.
+    ///
+    /// Some servers send gzip data with that content type instead of a
+    /// `Content-Encoding: gzip` header. When enabled, such response bodies
+    /// are automatically decompressed, and `Content-Length` is removed from
+    /// the headers. The `Content-Type` header is left untouched.
+    ///
+    /// Default is `false`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `gzip` feature to be enabled
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn decode_gzip_content_type(mut self, enable: bool) -> ClientBuilder {
+        self.config.accepts.gzip_content_type = enable;
+        self
+    }
+
+    /// Don't use gzip for these media types.
+    ///
+    /// A response whose `Content-Type` matches one of `types` isn't gzip
+    /// decompressed, even if it has `Content-Encoding: gzip`; the body and
+    /// headers are returned as received. A request whose `Accept` header
+    /// only lists matching types doesn't advertise gzip in
+    /// `Accept-Encoding`. This avoids pointless work for payloads that are
+    /// already compressed, such as images and video.
+    ///
+    /// A type is either exact, like `image/png`, or names all subtypes,
+    /// like `image/*`, and is matched ignoring ASCII case. Each call
+    /// replaces the previous list.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `gzip` feature to be enabled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn doc() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::Client::builder()
+    ///     .gzip_skip_content_types(&["image/*", "video/*"])
+    ///     .build()?;
+    /// # drop(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn gzip_skip_content_types(mut self, types: &[&str]) -> ClientBuilder {
+        self.config.gzip_skip_content_types =
+            types.iter().map(|ty| ty.to_ascii_lowercase()).collect();
+        self
+    }
+
     /// Enable auto brotli decompression by checking the `Content-Encoding` response header.
     ///
     /// If auto brotli decompression is turned on:
@@ -651,6 +1083,107 @@ impl ClientBuilder {
         }
     }
 
+    /// Controls whether enabled encodings are advertised in `Accept-Encoding`.
+    ///
+    /// When disabled, no `Accept-Encoding` header is added to requests, but
+    /// responses that arrive compressed anyway (for instance because a proxy
+    /// compressed them) are still decompressed, as long as the encoding is
+    /// enabled and `auto_decompress` is on.
+    ///
+    /// Methods like `gzip(bool)` set which encodings are enabled for both
+    /// advertising and decompression; this only affects advertising.
+    ///
+    /// Default is `true`.
+    pub fn accept_encoding(mut self, enable: bool) -> ClientBuilder {
+        self.config.accepts.advertise = enable;
+        self
+    }
+
+    /// Controls whether responses in enabled encodings are decompressed.
+    ///
+    /// When disabled, response bodies are returned as they were received,
+    /// with `Content-Encoding` and `Content-Length` left in place, even
+    /// though the enabled encodings are still advertised in
+    /// `Accept-Encoding` unless `accept_encoding(false)` is also set.
+    ///
+    /// Methods like `gzip(bool)` set which encodings are enabled for both
+    /// advertising and decompression; this only affects decompression.
+    ///
+    /// Default is `true`.
+    pub fn auto_decompress(mut self, enable: bool) -> ClientBuilder {
+        self.config.accepts.decompress = enable;
+        self
+    }
+
+    /// Send `Accept-Encoding: identity` with range requests.
+    ///
+    /// A request with a `Range` header never advertises the enabled
+    /// encodings, since byte offsets only make sense for the uncompressed
+    /// content. Some servers compress responses anyway when no
+    /// `Accept-Encoding` is sent, so enabling this explicitly asks them not
+    /// to. An `Accept-Encoding` header set on the request is kept as is.
+    ///
+    /// Default is `false`.
+    pub fn range_identity_encoding(mut self, enable: bool) -> ClientBuilder {
+        self.config.range_identity_encoding = enable;
+        self
+    }
+
+    // Retry options
+
+    /// Retry idempotent requests whose response has one of these statuses.
+    ///
+    /// Such a request is sent again, up to `max_retries` times, and the
+    /// last response is returned once the retries are exhausted. Requests
+    /// with a method that isn't idempotent, such as `POST` or `PATCH`, are
+    /// never retried, and neither are requests with a streaming body,
+    /// since it can't be sent twice.
+    ///
+    /// This is useful for statuses meaning the server is temporarily
+    /// unable to answer, such as `502 Bad Gateway`, `503 Service
+    /// Unavailable` or `504 Gateway Timeout`.
+    ///
+    /// Retries are sent after the delay asked for by the response's
+    /// `Retry-After` header, or after the delay set by `retry_backoff`
+    /// without one. If the server asks to wait longer than the backoff's
+    /// maximum, its response is returned instead. Cookies set by the
+    /// response are sent with the retry.
+    ///
+    /// Default is no statuses.
+    pub fn retry_on_status(mut self, statuses: &[StatusCode]) -> ClientBuilder {
+        self.config.retry_statuses = statuses.to_vec();
+        self
+    }
+
+    /// Set how many times a request is retried after failing to connect,
+    /// or after a status given to `retry_on_status`.
+    ///
+    /// A request that failed to connect never reached the server, so it is
+    /// sent again whatever its method. Requests with a streaming body are
+    /// never retried, since the body can't be sent twice.
+    ///
+    /// Default is 0, which disables retries.
+    pub fn max_retries(mut self, max: usize) -> ClientBuilder {
+        self.config.max_retries = max;
+        self
+    }
+
+    /// Set how long to wait before each retry of a request.
+    ///
+    /// The first retry waits `base`, and each one after it twice as long as
+    /// the one before, up to `max`. With `jitter`, a random extra of up to
+    /// half the delay is added, so that clients which failed together
+    /// don't all retry at the same time.
+    ///
+    /// A response's `Retry-After` header takes precedence over this delay.
+    ///
+    /// Default is a `base` of 100 milliseconds, a `max` of 10 seconds, and
+    /// no jitter.
+    pub fn retry_backoff(mut self, base: Duration, max: Duration, jitter: bool) -> ClientBuilder {
+        self.config.retry_backoff = RetryBackoff { base, max, jitter };
+        self
+    }
+
     // Redirect options
 
     /// Set a `RedirectPolicy` for this client.
@@ -661,11 +1194,36 @@ impl ClientBuilder {
         self
     }
 
+    /// Record the redirect responses that were followed.
+    ///
+    /// When enabled, each redirect followed on the way to a `Response` is
+    /// kept as its status code and the `Url` it pointed to, available with
+    /// [`Response::redirect_responses`](crate::Response::redirect_responses).
+    ///
+    /// Default is `false`.
+    pub fn record_redirects(mut self, enable: bool) -> ClientBuilder {
+        self.config.record_redirects = enable;
+        self
+    }
+
     /// Enable or disable automatic setting of the `Referer` header.
     ///
     /// Default is `true`.
     pub fn referer(mut self, enable: bool) -> ClientBuilder {
-        self.config.referer = enable;
+        self.config.referer = if enable {
+            redirect::RefererPolicy::NoReferrerWhenDowngrade
+        } else {
+            redirect::RefererPolicy::NoReferrer
+        };
+        self
+    }
+
+    /// Set the policy used to build the `Referer` header when following
+    /// redirects.
+    ///
+    /// Default is `RefererPolicy::NoReferrerWhenDowngrade`.
+    pub fn referer_policy(mut self, policy: redirect::RefererPolicy) -> ClientBuilder {
+        self.config.referer = policy;
         self
     }
 
@@ -684,7 +1242,14 @@ impl ClientBuilder {
 
     /// Clear all `Proxies`, so `Client` will use no proxy anymore.
     ///
-    /// This also disables the automatic usage of the "system" proxy.
+    /// This also disables the automatic usage of the "system" proxy, so
+    /// proxies configured through environment variables such as
+    /// `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` (or the Windows registry)
+    /// are ignored as well.
+    ///
+    /// Only proxies added before this call are cleared; calling `proxy()`
+    /// afterwards adds to the now empty list, which makes this useful to
+    /// reset a builder that was configured elsewhere.
     pub fn no_proxy(mut self) -> ClientBuilder {
         self.config.proxies.clear();
         self.config.auto_sys_proxy = false;
@@ -698,12 +1263,43 @@ impl ClientBuilder {
     /// The timeout is applied from when the request starts connecting until the
     /// response body has finished.
     ///
+    /// It is a single deadline for each attempt, including its redirects:
+    /// time spent on every redirect hop counts against it. A request that
+    /// is redirected through several slow hops fails with a timeout error
+    /// once the total reaches this duration. A retry starts a new timeout;
+    /// use `total_timeout` to limit the time spent on all of them.
+    ///
     /// Default is no timeout.
     pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
         self.config.timeout = Some(timeout);
         self
     }
 
+    /// Disable the request timeout.
+    ///
+    /// This is already the default for the async `Client`, and undoes an
+    /// earlier call to `timeout()`. The blocking `Client` has the same
+    /// method, to opt out of its default timeout.
+    pub fn timeout_disabled(mut self) -> ClientBuilder {
+        self.config.timeout = None;
+        self
+    }
+
+    /// Enables a timeout for the whole `send()`, across all retries and
+    /// redirects.
+    ///
+    /// Unlike `timeout`, this isn't restarted when a request is retried,
+    /// and it also runs while waiting to send a retry: once it passes, the
+    /// request fails with a timeout error, even in the middle of a retry's
+    /// delay. It then keeps applying to reading the response body, like
+    /// `timeout` does.
+    ///
+    /// Default is no total timeout.
+    pub fn total_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.config.total_timeout = Some(timeout);
+        self
+    }
+
     /// Set a timeout for only the connect phase of a `Client`.
     ///
     /// Default is `None`.
@@ -717,6 +1313,19 @@ impl ClientBuilder {
         self
     }
 
+    /// Set how many of the resolved addresses of a host are tried when connecting.
+    ///
+    /// When a name resolves to several addresses, they are tried in order,
+    /// moving on to the next one if connecting fails. If every attempt
+    /// fails, the error from the last one is returned. A `connect_timeout`
+    /// is shared between the attempts.
+    ///
+    /// By default, all of them are tried. A value of `0` is treated as `1`.
+    pub fn connect_attempts(mut self, attempts: usize) -> ClientBuilder {
+        self.config.connect_attempts = Some(attempts);
+        self
+    }
+
     /// Set whether connections should emit verbose logs.
     ///
     /// Enabling this option will emit [log][] messages at the `TRACE` level
@@ -749,33 +1358,120 @@ impl ClientBuilder {
         self
     }
 
-    /// Send headers as title case instead of lowercase.
-    pub fn http1_title_case_headers(mut self) -> ClientBuilder {
-        self.config.http1_title_case_headers = true;
+    /// Sets the maximum number of simultaneous connections per host.
+    ///
+    /// Unlike `pool_max_idle_per_host`, this bounds concurrency: once the
+    /// limit is reached, new connections to that host are not dialed until
+    /// an existing one closes, and requests wait for a pooled connection to
+    /// become free instead.
+    ///
+    /// The limit applies to each connection pool. Requests with a
+    /// per-request proxy use a pool of their own, so connections idle in
+    /// it never hold up requests using the client's pool. Within a pool,
+    /// an idle connection keeps its slot, but is reused before a new
+    /// connection to its host is dialed.
+    ///
+    /// Default is no limit.
+    pub fn max_connections_per_host(mut self, max: usize) -> ClientBuilder {
+        self.config.max_connections_per_host = Some(max);
         self
     }
 
-    /// Only use HTTP/1.
-    pub fn http1_only(mut self) -> ClientBuilder {
-        self.config.http_version_pref = HttpVersionPref::Http1;
+    /// Send headers as title case instead of lowercase.
+    pub fn http1_title_case_headers(mut self) -> ClientBuilder {
+        self.config.http1_title_case_headers = true;
         self
     }
 
-    /// Only use HTTP/2.
-    pub fn http2_prior_knowledge(mut self) -> ClientBuilder {
-        self.config.http_version_pref = HttpVersionPref::Http2;
+    /// Sets the exact size of the read buffer of HTTP/1 connections.
+    ///
+    /// By default, the read buffer starts small and grows adaptively up to
+    /// `http1_max_buf_size`. A large fixed buffer reduces the number of
+    /// reads for big transfers, while a small one keeps the memory used by
+    /// many concurrent connections down. Headers that don't fit in the
+    /// buffer fail the response.
+    ///
+    /// This unsets `http1_max_buf_size`. It has no effect on HTTP/2
+    /// connections.
+    pub fn http1_read_buf_exact_size(mut self, sz: usize) -> ClientBuilder {
+        self.config.http1_read_buf_exact_size = Some(sz);
+        self.config.http1_max_buf_size = None;
         self
     }
 
-    /// Sets the `SETTINGS_INITIAL_WINDOW_SIZE` option for HTTP2 stream-level flow control.
+    /// Sets the maximum size of the read and write buffers of HTTP/1
+    /// connections.
     ///
-    /// Default is currently 65,535 but may change internally to optimize for common uses.
-    pub fn http2_initial_stream_window_size(mut self, sz: impl Into<Option<u32>>) -> ClientBuilder {
-        self.config.http2_initial_stream_window_size = sz.into();
+    /// The read buffer grows up to this size, and the write buffer queues
+    /// up to this much data before the connection waits for it to be
+    /// flushed. Larger buffers mean fewer system calls for big transfers,
+    /// smaller ones less memory per connection.
+    ///
+    /// This unsets `http1_read_buf_exact_size`. It has no effect on HTTP/2
+    /// connections.
+    ///
+    /// Default is hyper's, currently about 400kb. The minimum is 8192;
+    /// smaller values make `build` return an error.
+    pub fn http1_max_buf_size(mut self, max: usize) -> ClientBuilder {
+        self.config.http1_max_buf_size = Some(max);
+        self.config.http1_read_buf_exact_size = None;
         self
     }
 
-    /// Sets the max connection-level flow control for HTTP2
+    /// Set how long to wait for a `100 Continue` before sending the body of
+    /// a request with an `Expect: 100-continue` header.
+    ///
+    /// Such a request asks the server to look at its headers first, so a
+    /// body the server would refuse isn't sent for nothing. The body is
+    /// held back until the server answers with `100 Continue`, or until
+    /// this duration has passed since the request head was written, and is
+    /// then sent. A final response, such as `417 Expectation Failed`, is
+    /// returned as soon as it arrives.
+    ///
+    /// Over HTTP/2, a `100 Continue` can't be noticed, so the body is sent
+    /// right away.
+    ///
+    /// This only applies to requests that set the `Expect` header
+    /// themselves. `None` sends the body right away.
+    ///
+    /// Default is 1 second.
+    pub fn expect_continue_timeout<D>(mut self, timeout: D) -> ClientBuilder
+    where
+        D: Into<Option<Duration>>,
+    {
+        self.config.expect_continue_timeout = timeout.into();
+        self
+    }
+
+    /// Only use HTTP/1.
+    pub fn http1_only(mut self) -> ClientBuilder {
+        self.config.http_version_pref = HttpVersionPref::Http1;
+        self
+    }
+
+    /// Only use HTTP/2.
+    pub fn http2_prior_knowledge(mut self) -> ClientBuilder {
+        self.config.http_version_pref = HttpVersionPref::Http2;
+        self
+    }
+
+    /// Sets the `SETTINGS_INITIAL_WINDOW_SIZE` option for HTTP2 stream-level flow control.
+    ///
+    /// Raising this can help large downloads over links with high latency,
+    /// where a small window limits how much data can be in flight. It has
+    /// no effect on HTTP/1.1 connections.
+    ///
+    /// Default is currently 65,535 but may change internally to optimize for common uses.
+    pub fn http2_initial_stream_window_size(mut self, sz: impl Into<Option<u32>>) -> ClientBuilder {
+        self.config.http2_initial_stream_window_size = sz.into();
+        self
+    }
+
+    /// Sets the max connection-level flow control for HTTP2
+    ///
+    /// This bounds the data in flight across all streams of a connection,
+    /// so it should be at least as large as `http2_initial_stream_window_size`.
+    /// It has no effect on HTTP/1.1 connections.
     ///
     /// Default is currently 65,535 but may change internally to optimize for common uses.
     pub fn http2_initial_connection_window_size(
@@ -843,6 +1539,43 @@ impl ClientBuilder {
         self
     }
 
+    /// Set whether sockets have `SO_REUSEADDR` enabled.
+    ///
+    /// This lets a local address be bound again while earlier connections
+    /// from it are still in `TIME_WAIT`, which can help clients that open
+    /// many short-lived connections, particularly together with
+    /// [`ClientBuilder::local_address`].
+    ///
+    /// The exact meaning differs between platforms. On Windows,
+    /// `SO_REUSEADDR` also allows binding an address that is actively in
+    /// use, so it should be used with care there.
+    ///
+    /// Default is `false`.
+    pub fn reuse_address(mut self, enabled: bool) -> ClientBuilder {
+        self.config.reuse_address = enabled;
+        self
+    }
+
+    /// Use streams made by `connect` as the transport for this client.
+    ///
+    /// `connect` is called whenever the client opens a new connection,
+    /// whatever the host of the request, and the stream it resolves to is
+    /// used as-is: no proxy is applied and no TLS handshake is done, so it
+    /// should already be connected (and secured, if needed) to the server.
+    /// An error from it fails the request.
+    ///
+    /// This is useful for special transports, such as a pre-authenticated
+    /// tunnel or an in-memory stream.
+    pub fn connect_with_stream<F, Fut, S>(mut self, connect: F) -> ClientBuilder
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::io::Result<S>> + Send + 'static,
+        S: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    {
+        self.config.provided_stream = Some(connect::provided::Provided::new(connect));
+        self
+    }
+
     // TLS options
 
     /// Add a custom root certificate.
@@ -868,10 +1601,54 @@ impl ClientBuilder {
         self
     }
 
+    /// Pins the public key that servers must present for `https` connections.
+    ///
+    /// `sha256_base64` is the base64 encoded SHA-256 hash of the
+    /// `SubjectPublicKeyInfo` of the server's certificate, the same format
+    /// used by `curl --pinnedpubkey` (an optional `sha256//` prefix is
+    /// accepted). This can be called more than once, in which case the
+    /// server's certificate must match any one of the pins. Connections to
+    /// servers that don't match fail with a connect error.
+    ///
+    /// Pinning is checked in addition to the normal certificate validation.
+    ///
+    /// # Errors
+    ///
+    /// The client will fail to build if `sha256_base64` is not a valid
+    /// base64 encoded SHA-256 hash.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `pinning` feature, and one of the
+    /// `default-tls`, `native-tls`, or `rustls-tls(-...)` features, to be
+    /// enabled.
+    #[cfg(all(feature = "__tls", feature = "pinning"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            feature = "pinning",
+            any(
+                feature = "default-tls",
+                feature = "native-tls",
+                feature = "rustls-tls"
+            )
+        )))
+    )]
+    pub fn add_pinned_public_key(mut self, sha256_base64: &str) -> ClientBuilder {
+        if let Err(err) = self.config.pinned_keys.add(sha256_base64) {
+            self.config.error = Some(err);
+        }
+        self
+    }
+
     /// Controls the use of built-in/preloaded certificates during certificate validation.
     ///
     /// Defaults to `true` -- built-in system certs will be used.
     ///
+    /// When `false`, the root store starts out empty, and only certificates
+    /// added with [`add_root_certificate`](ClientBuilder::add_root_certificate)
+    /// are trusted.
+    ///
     /// # Optional
     ///
     /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
@@ -890,6 +1667,37 @@ impl ClientBuilder {
         self
     }
 
+    /// Set a timeout for only the TLS handshake of new connections.
+    ///
+    /// The timeout starts once the TCP connection (or proxy tunnel) is
+    /// established, so it catches servers and middleboxes that accept the
+    /// connection but then stall the handshake. When it elapses, the request
+    /// fails with an error for which [`Error::is_timeout`](crate::Error::is_timeout)
+    /// returns `true`, and whose message says the TLS handshake timed out.
+    ///
+    /// `connect_timeout`, if also set, still bounds the whole connect
+    /// phase, including the handshake.
+    ///
+    /// Default is `None`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn tls_handshake_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.config.tls_handshake_timeout = Some(timeout);
+        self
+    }
+
     /// Sets the identity to be used for client certificate authentication.
     ///
     /// # Optional
@@ -903,6 +1711,44 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the identity to be used for client certificate authentication
+    /// with a specific host.
+    ///
+    /// The host is matched exactly, ignoring ASCII case. Connections to
+    /// other hosts use the identity set with `identity`, if any. When
+    /// tunneling through an HTTPS proxy, the connection to the proxy itself
+    /// always uses the default identity.
+    ///
+    /// # Optional
+    ///
+    /// This only works with the rustls backend, and requires one of the
+    /// `rustls-tls(-...)` features to be enabled. If another backend is in
+    /// use, building the `Client` fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn doc(internal: &[u8], partner: &[u8]) -> Result<(), reqwest::Error> {
+    /// use reqwest::Identity;
+    ///
+    /// let client = reqwest::Client::builder()
+    ///     .use_rustls_tls()
+    ///     .identity(Identity::from_pem(internal)?)
+    ///     .identity_for_host("api.partner.example", Identity::from_pem(partner)?)
+    ///     .build()?;
+    /// # drop(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "__rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls-tls")))]
+    pub fn identity_for_host(mut self, host: &str, identity: Identity) -> ClientBuilder {
+        self.config
+            .host_identities
+            .insert(host.to_ascii_lowercase(), identity);
+        self
+    }
+
     /// Controls the use of hostname verification.
     ///
     /// Defaults to `false`.
@@ -957,6 +1803,75 @@ impl ClientBuilder {
         self
     }
 
+    /// Skip certificate validation for connections to `host`.
+    ///
+    /// Certificates presented by this host are accepted without any
+    /// checks, while every other host is still validated fully. This is
+    /// meant for a specific machine with a self-signed or otherwise invalid
+    /// certificate, such as a staging box, and is much narrower than
+    /// `danger_accept_invalid_certs`. It can be called several times to
+    /// add more hosts; names are compared case-insensitively.
+    ///
+    /// # Warning
+    ///
+    /// Anyone able to intercept the traffic to `host`, for instance by
+    /// spoofing DNS, can impersonate it and read or change everything sent
+    /// to it, including credentials. Never use this for hosts reached over
+    /// untrusted networks.
+    ///
+    /// # Optional
+    ///
+    /// This only works with the rustls backend, and requires one of the
+    /// `rustls-tls(-...)` features to be enabled. If another backend is in
+    /// use, building the `Client` fails.
+    #[cfg(feature = "__rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls-tls")))]
+    pub fn danger_accept_invalid_certs_for(mut self, host: &str) -> ClientBuilder {
+        self.config.insecure_hosts.insert(host.to_ascii_lowercase());
+        self
+    }
+
+    /// Verify server certificates with a custom function.
+    ///
+    /// The function is given the certificates the server presented, and
+    /// decides alone whether they are trusted: the root certificates are not
+    /// consulted. This allows pinning a specific certificate, such as a
+    /// self-signed one, by checking its fingerprint. The handshake is still
+    /// checked to be signed by the presented certificate.
+    ///
+    /// Returning `false` fails the connection with a TLS error. Setting
+    /// `danger_accept_invalid_certs(true)` takes precedence over this.
+    ///
+    /// # Optional
+    ///
+    /// This only works with the rustls backend, and requires one of the
+    /// `rustls-tls(-...)` features to be enabled. If another backend is in
+    /// use, building the `Client` fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn doc(fingerprint: Vec<u8>) -> Result<(), reqwest::Error> {
+    /// let client = reqwest::Client::builder()
+    ///     .use_rustls_tls()
+    ///     .custom_cert_verifier(move |chain| {
+    ///         chain.end_entity().to_der().map_or(false, |der| der == fingerprint)
+    ///     })
+    ///     .build()?;
+    /// # drop(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "__rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls-tls")))]
+    pub fn custom_cert_verifier<F>(mut self, verifier: F) -> ClientBuilder
+    where
+        F: Fn(&CertificateChain) -> bool + Send + Sync + 'static,
+    {
+        self.config.cert_verifier = Some(Arc::new(verifier));
+        self
+    }
+
     /// Force using the native TLS backend.
     ///
     /// Since multiple TLS backends can be optionally enabled, this option will
@@ -1084,13 +1999,59 @@ impl ClientBuilder {
     /// traffic to a particular port you must include this port in the URL
     /// itself, any port in the overridden addr will be ignored and traffic sent
     /// to the conventional port for the given scheme (e.g. 80 for http).
-    pub fn resolve(mut self, domain: &str, addr: SocketAddr) -> ClientBuilder {
-        self.config.dns_overrides.insert(domain.to_string(), addr);
+    pub fn resolve(self, domain: &str, addr: SocketAddr) -> ClientBuilder {
+        self.resolve_to_addrs(domain, &[addr])
+    }
+
+    /// Override DNS resolution for specific domains to a list of IP addresses.
+    ///
+    /// The addresses are tried in order until a connection succeeds, the
+    /// same as when a name resolves to several addresses.
+    ///
+    /// Warning
+    ///
+    /// Since the DNS protocol has no notion of ports, if you wish to send
+    /// traffic to a particular port you must include this port in the URL
+    /// itself, any port in the overridden addresses will be ignored and traffic
+    /// sent to the conventional port for the given scheme (e.g. 80 for http).
+    ///
+    /// # IPv6 zone identifiers
+    ///
+    /// URLs can't carry an IPv6 zone identifier (such as
+    /// `http://[fe80::1%25eth0]/`), since they are rejected by the URL
+    /// parser. To reach a link-local address, map a name to a
+    /// [`SocketAddrV6`](std::net::SocketAddrV6) with the interface's scope id
+    /// instead; the scope id is used when connecting.
+    ///
+    /// ```
+    /// use std::net::{Ipv6Addr, SocketAddrV6};
+    ///
+    /// // fe80::1 on the interface with index 2
+    /// let addr = SocketAddrV6::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 0, 0, 2);
+    /// let client = reqwest::Client::builder()
+    ///     .resolve_to_addrs("router.local", &[addr.into()])
+    ///     .build()?;
+    /// # Ok::<(), reqwest::Error>(())
+    /// ```
+    pub fn resolve_to_addrs(mut self, domain: &str, addrs: &[SocketAddr]) -> ClientBuilder {
+        self.config
+            .dns_overrides
+            .insert(domain.to_string(), addrs.to_vec());
         self
     }
 }
 
-type HyperClient = hyper::Client<Connector, super::body::ImplStream>;
+type HyperClient = hyper::Client<Connector, ImplStream>;
+
+/// The smallest `http1_max_buf_size` hyper accepts.
+const HTTP1_MIN_BUF_SIZE: usize = 8192;
+
+/// How much of the body of a response that is retried is read, to reuse
+/// its connection.
+const MAX_RETRY_DRAIN: usize = 64 * 1024;
+
+/// How many distinct proxy overrides keep a connection pool at once.
+const MAX_PROXY_CLIENTS: usize = 16;
 
 impl Default for Client {
     fn default() -> Self {
@@ -1186,6 +2147,31 @@ impl Client {
         RequestBuilder::new(self.clone(), req)
     }
 
+    /// Convenience method to make a `GET` request to an already parsed `Url`.
+    ///
+    /// Unlike [`Client::get`], this can't fail to parse the URL.
+    pub fn get_url(&self, url: Url) -> RequestBuilder {
+        self.request_url(Method::GET, url)
+    }
+
+    /// Convenience method to make a `POST` request to an already parsed `Url`.
+    ///
+    /// Unlike [`Client::post`], this can't fail to parse the URL.
+    pub fn post_url(&self, url: Url) -> RequestBuilder {
+        self.request_url(Method::POST, url)
+    }
+
+    /// Start building a `Request` with the `Method` and an already parsed
+    /// `Url`.
+    ///
+    /// Unlike [`Client::request`], this skips converting the URL, so the
+    /// returned `RequestBuilder` never carries a URL error. A URL that
+    /// can't be used for a request, such as one with an unsupported scheme,
+    /// is still rejected when the request is sent.
+    pub fn request_url(&self, method: Method, url: Url) -> RequestBuilder {
+        RequestBuilder::new(self.clone(), Ok(Request::new(method, url)))
+    }
+
     /// Executes a `Request`.
     ///
     /// A `Request` can be built manually with `Request::new()` or obtained
@@ -1205,7 +2191,78 @@ impl Client {
         self.execute_request(request)
     }
 
-    pub(super) fn execute_request(&self, req: Request) -> Pending {
+    /// Open a connection to the host of `url` ahead of time.
+    ///
+    /// This sends a `HEAD` request to `url` and reads the response, so
+    /// that the connection, including any TLS handshake, is established
+    /// and kept in the pool. A following request to the same host can then
+    /// skip that setup, which helps before a latency-sensitive burst of
+    /// requests. The status of the response doesn't matter.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `url` can't be parsed or the request couldn't
+    /// be sent.
+    pub fn warmup<U: IntoUrl>(&self, url: U) -> impl Future<Output = crate::Result<()>> {
+        let pending = self.head(url).send();
+        async move {
+            // Reading the (empty) body hands the connection back to the pool.
+            pending.await?.bytes().await?;
+            Ok(())
+        }
+    }
+
+    pub(super) fn execute_request(&self, mut req: Request) -> Pending {
+        // insert default headers in the request headers
+        // without overwriting already appended headers.
+        let no_accept = req.is_accept_suppressed();
+        for (key, value) in &self.inner.headers {
+            if no_accept && key == ACCEPT {
+                continue;
+            }
+            if let Entry::Vacant(entry) = req.headers_mut().entry(key) {
+                entry.insert(value.clone());
+            }
+        }
+
+        if let Some(ref content_type) = self.inner.default_content_type {
+            if req.body().is_some() {
+                if let Entry::Vacant(entry) = req.headers_mut().entry(CONTENT_TYPE) {
+                    entry.insert(content_type.clone());
+                }
+            }
+        }
+
+        if let Some(ref request_id) = self.inner.request_id {
+            if let Entry::Vacant(entry) = req.headers_mut().entry(&request_id.name) {
+                match HeaderValue::try_from((request_id.generate)()) {
+                    Ok(value) => {
+                        entry.insert(value);
+                    }
+                    Err(err) => {
+                        return Pending::new_err(error::builder(err).with_url(req.url().clone()))
+                    }
+                }
+            }
+        }
+
+        if let Some(ref interceptor) = self.inner.request_interceptor {
+            interceptor(&mut req);
+        }
+
+        if self.inner.normalize_headers {
+            collapse_duplicate_headers(req.headers_mut());
+        }
+
+        let force_decode = req.forced_encoding();
+        let trailers = req.take_trailers();
+        let asterisk_form = req.is_asterisk_form();
+        #[cfg(feature = "brotli")]
+        let brotli = req.is_brotli();
+        let proxies = req
+            .take_proxy_override()
+            .map(|proxy| Arc::new(proxy.into_iter().collect::<Vec<_>>()));
+
         let (method, url, mut headers, body, timeout, version) = req.pieces();
         if url.scheme() != "http" && url.scheme() != "https" {
             return Pending::new_err(error::url_bad_scheme(url));
@@ -1216,14 +2273,6 @@ impl Client {
             return Pending::new_err(error::url_bad_scheme(url));
         }
 
-        // insert default headers in the request headers
-        // without overwriting already appended headers.
-        for (key, value) in &self.inner.headers {
-            if let Entry::Vacant(entry) = headers.entry(key) {
-                entry.insert(value.clone());
-            }
-        }
-
         // Add cookies from the cookie store.
         #[cfg(feature = "cookies")]
         {
@@ -1234,15 +2283,35 @@ impl Client {
             }
         }
 
-        let accept_encoding = self.inner.accepts.as_str();
+        self.inner.accept_encoding(&mut headers);
 
-        if let Some(accept_encoding) = accept_encoding {
-            if !headers.contains_key(ACCEPT_ENCODING) && !headers.contains_key(RANGE) {
-                headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(accept_encoding));
+        let mut uri = expect_uri(&url);
+        if asterisk_form {
+            if method != Method::OPTIONS {
+                return Pending::new_err(
+                    error::builder("asterisk-form is only valid for OPTIONS requests")
+                        .with_url(url),
+                );
             }
+            // A forwarded request must use the absolute-form instead.
+            if self.inner.forwards_http(proxies.as_deref(), &uri) {
+                return Pending::new_err(
+                    error::builder("asterisk-form can't be sent through an HTTP proxy")
+                        .with_url(url),
+                );
+            }
+            uri = into_asterisk_form(uri);
         }
 
-        let uri = expect_uri(&url);
+        #[cfg(feature = "brotli")]
+        let body = match body {
+            Some(body) if brotli => {
+                headers.insert(CONTENT_ENCODING, HeaderValue::from_static("br"));
+                headers.remove(CONTENT_LENGTH);
+                Some(body.brotli())
+            }
+            body => body,
+        };
 
         let (reusable, body) = match body {
             Some(body) => {
@@ -1252,67 +2321,85 @@ impl Client {
             None => (None, Body::empty()),
         };
 
-        self.proxy_auth(&uri, &mut headers);
+        let mut body = body.into_stream();
+        if let Some(trailers) = trailers {
+            if reusable != Some(None) {
+                return Pending::new_err(
+                    error::builder("trailers require a streaming request body").with_url(url),
+                );
+            }
+            // Only HTTP/2 can send them, so the request is refused unless
+            // it's sure to use HTTP/2, rather than having its body sent
+            // without them if a server only negotiates HTTP/1. hyper
+            // refuses an HTTP/2 request on an HTTP/1 connection before
+            // sending anything.
+            let http2 = match self.inner.http_version_pref {
+                HttpVersionPref::Http2 => true,
+                HttpVersionPref::Http1 => false,
+                HttpVersionPref::All => version == Version::HTTP_2,
+            };
+            if !http2 {
+                return Pending::new_err(
+                    error::builder("trailers can only be sent over HTTP/2").with_url(url),
+                );
+            }
+            body = body.trailers(trailers);
+        }
+
+        self.inner
+            .proxy_auth(proxies.as_deref(), &uri, &mut headers);
 
         let mut req = hyper::Request::builder()
             .method(method.clone())
             .uri(uri)
             .version(version)
-            .body(body.into_stream())
+            .body(body)
             .expect("valid request parts");
 
-        let timeout = timeout
-            .or(self.inner.request_timeout)
-            .map(tokio::time::sleep)
-            .map(Box::pin);
+        let started = Instant::now();
+        let attempt_timeout = timeout.or(self.inner.request_timeout);
+        let deadline = self.inner.total_timeout.map(|total| started + total);
+        let timeout = attempt_deadline(attempt_timeout, deadline)
+            .map(|deadline| Box::pin(tokio::time::sleep_until(deadline.into())));
 
         *req.headers_mut() = headers.clone();
+        self.inner.expect_continue(&mut req);
+        let closing = capture_closing(&mut req);
 
-        let in_flight = self.inner.hyper.request(req);
+        let hyper = proxies
+            .as_ref()
+            .map(|proxies| Box::new(self.inner.hyper_with_proxies(proxies.clone())));
+        let in_flight = hyper.as_deref().unwrap_or(&self.inner.hyper).request(req);
 
         Pending {
             inner: PendingInner::Request(PendingRequest {
                 method,
                 url,
+                version,
                 headers,
                 body: reusable,
 
                 urls: Vec::new(),
+                redirects: Vec::new(),
 
                 client: self.inner.clone(),
-
+                hyper,
+                proxies,
+                force_decode,
+                asterisk_form,
+                closing,
+                retries: 0,
+                retry: None,
+
+                started,
+                sent: started,
                 in_flight,
+                attempt_timeout,
+                deadline,
                 timeout,
             }),
         }
     }
-
-    fn proxy_auth(&self, dst: &Uri, headers: &mut HeaderMap) {
-        if !self.inner.proxies_maybe_http_auth {
-            return;
-        }
-
-        // Only set the header here if the destination scheme is 'http',
-        // since otherwise, the header will be included in the CONNECT tunnel
-        // request instead.
-        if dst.scheme() != Some(&Scheme::HTTP) {
-            return;
-        }
-
-        if headers.contains_key(PROXY_AUTHORIZATION) {
-            return;
-        }
-
-        for proxy in self.inner.proxies.iter() {
-            if proxy.is_match(dst) {
-                if let Some(header) = proxy.http_basic_auth(dst) {
-                    headers.insert(PROXY_AUTHORIZATION, header);
-                }
-
-                break;
-            }
-        }
-    }
 }
 
 impl fmt::Debug for Client {
@@ -1341,10 +2428,25 @@ impl Config {
             if let Some(_) = self.cookie_store {
                 f.field("cookie_store", &true);
             }
+
+            if self.cookie_change.is_some() {
+                f.field("on_cookie_change", &true);
+            }
         }
 
         f.field("accepts", &self.accepts);
 
+        #[cfg(feature = "gzip")]
+        {
+            if !self.gzip_skip_content_types.is_empty() {
+                f.field("gzip_skip_content_types", &self.gzip_skip_content_types);
+            }
+        }
+
+        if self.range_identity_encoding {
+            f.field("range_identity_encoding", &true);
+        }
+
         if !self.proxies.is_empty() {
             f.field("proxies", &self.proxies);
         }
@@ -1353,8 +2455,37 @@ impl Config {
             f.field("redirect_policy", &self.redirect_policy);
         }
 
-        if self.referer {
-            f.field("referer", &true);
+        if self.record_redirects {
+            f.field("record_redirects", &true);
+        }
+
+        if !self.retry_statuses.is_empty() {
+            f.field("retry_on_status", &self.retry_statuses);
+        }
+
+        if self.max_retries > 0 {
+            f.field("max_retries", &self.max_retries);
+            f.field("retry_backoff", &self.retry_backoff);
+        }
+
+        if self.referer != redirect::RefererPolicy::NoReferrerWhenDowngrade {
+            f.field("referer", &self.referer);
+        }
+
+        if let Some(ref request_id) = self.request_id {
+            f.field("request_id_header", &request_id.name);
+        }
+
+        if self.request_interceptor.is_some() {
+            f.field("request_interceptor", &true);
+        }
+
+        if self.normalize_headers {
+            f.field("normalize_headers", &true);
+        }
+
+        if let Some(ref content_type) = self.default_content_type {
+            f.field("default_content_type", content_type);
         }
 
         f.field("default_headers", &self.headers);
@@ -1363,6 +2494,18 @@ impl Config {
             f.field("http1_title_case_headers", &true);
         }
 
+        if let Some(ref sz) = self.http1_read_buf_exact_size {
+            f.field("http1_read_buf_exact_size", sz);
+        }
+
+        if let Some(ref max) = self.http1_max_buf_size {
+            f.field("http1_max_buf_size", max);
+        }
+
+        if self.expect_continue_timeout != Some(Duration::from_secs(1)) {
+            f.field("expect_continue_timeout", &self.expect_continue_timeout);
+        }
+
         if matches!(self.http_version_pref, HttpVersionPref::Http1) {
             f.field("http1_only", &true);
         }
@@ -1371,14 +2514,41 @@ impl Config {
             f.field("http2_prior_knowledge", &true);
         }
 
+        if let Some(sz) = self.http2_initial_stream_window_size {
+            f.field("http2_initial_stream_window_size", &sz);
+        }
+
+        if let Some(sz) = self.http2_initial_connection_window_size {
+            f.field("http2_initial_connection_window_size", &sz);
+        }
+
         if let Some(ref d) = self.connect_timeout {
             f.field("connect_timeout", d);
         }
 
+        #[cfg(feature = "__tls")]
+        {
+            if let Some(ref d) = self.tls_handshake_timeout {
+                f.field("tls_handshake_timeout", d);
+            }
+        }
+
         if let Some(ref d) = self.timeout {
             f.field("timeout", d);
         }
 
+        if let Some(ref d) = self.total_timeout {
+            f.field("total_timeout", d);
+        }
+
+        if let Some(ref max) = self.max_connections_per_host {
+            f.field("max_connections_per_host", max);
+        }
+
+        if self.provided_stream.is_some() {
+            f.field("provided_stream", &true);
+        }
+
         if let Some(ref v) = self.local_address {
             f.field("local_address", v);
         }
@@ -1387,6 +2557,10 @@ impl Config {
             f.field("tcp_nodelay", &true);
         }
 
+        if self.reuse_address {
+            f.field("reuse_address", &true);
+        }
+
         #[cfg(feature = "native-tls")]
         {
             if !self.hostname_verification {
@@ -1401,6 +2575,24 @@ impl Config {
             }
         }
 
+        #[cfg(feature = "__rustls")]
+        {
+            if self.cert_verifier.is_some() {
+                f.field("custom_cert_verifier", &true);
+            }
+
+            if !self.host_identities.is_empty() {
+                f.field(
+                    "identity_for_host",
+                    &self.host_identities.keys().collect::<Vec<_>>(),
+                );
+            }
+
+            if !self.insecure_hosts.is_empty() {
+                f.field("danger_accept_invalid_certs_for", &self.insecure_hosts);
+            }
+        }
+
         #[cfg(all(feature = "native-tls-crate", feature = "__rustls"))]
         {
             f.field("tls_backend", &self.tls);
@@ -1409,18 +2601,54 @@ impl Config {
         if !self.dns_overrides.is_empty() {
             f.field("dns_overrides", &self.dns_overrides);
         }
+
+        if let Some(ref attempts) = self.connect_attempts {
+            f.field("connect_attempts", attempts);
+        }
     }
 }
 
+struct RequestIdGenerator {
+    name: HeaderName,
+    generate: Arc<dyn Fn() -> String + Send + Sync>,
+}
+
+type RequestInterceptor = Arc<dyn Fn(&mut Request) + Send + Sync>;
+
+#[cfg(feature = "cookies")]
+type CookieChangeFn = Arc<dyn Fn(&cookie::CookieChange) + Send + Sync>;
+
 struct ClientRef {
     accepts: Accepts,
+    #[cfg(feature = "gzip")]
+    gzip_skip_content_types: Vec<String>,
+    range_identity_encoding: bool,
     #[cfg(feature = "cookies")]
     cookie_store: Option<Arc<dyn cookie::CookieStore>>,
+    #[cfg(feature = "cookies")]
+    cookie_change: Option<CookieChangeFn>,
     headers: HeaderMap,
     hyper: HyperClient,
+    hyper_builder: hyper::client::Builder,
+    // The hyper clients used by requests that override the proxies, with
+    // a connection pool for each distinct list of proxies, and when each
+    // was last used.
+    proxy_clients: Mutex<HashMap<Vec<crate::proxy::PoolKey>, (HyperClient, Instant)>>,
+    connector: Connector,
     redirect_policy: redirect::Policy,
-    referer: bool,
+    record_redirects: bool,
+    retry_statuses: Vec<StatusCode>,
+    max_retries: usize,
+    retry_backoff: RetryBackoff,
+    referer: redirect::RefererPolicy,
+    request_id: Option<RequestIdGenerator>,
+    request_interceptor: Option<RequestInterceptor>,
+    normalize_headers: bool,
+    default_content_type: Option<HeaderValue>,
+    http_version_pref: HttpVersionPref,
     request_timeout: Option<Duration>,
+    expect_continue_timeout: Option<Duration>,
+    total_timeout: Option<Duration>,
     proxies: Arc<Vec<Proxy>>,
     proxies_maybe_http_auth: bool,
     https_only: bool,
@@ -1436,10 +2664,25 @@ impl ClientRef {
             if let Some(_) = self.cookie_store {
                 f.field("cookie_store", &true);
             }
+
+            if self.cookie_change.is_some() {
+                f.field("on_cookie_change", &true);
+            }
         }
 
         f.field("accepts", &self.accepts);
 
+        #[cfg(feature = "gzip")]
+        {
+            if !self.gzip_skip_content_types.is_empty() {
+                f.field("gzip_skip_content_types", &self.gzip_skip_content_types);
+            }
+        }
+
+        if self.range_identity_encoding {
+            f.field("range_identity_encoding", &true);
+        }
+
         if !self.proxies.is_empty() {
             f.field("proxies", &self.proxies);
         }
@@ -1448,8 +2691,37 @@ impl ClientRef {
             f.field("redirect_policy", &self.redirect_policy);
         }
 
-        if self.referer {
-            f.field("referer", &true);
+        if self.record_redirects {
+            f.field("record_redirects", &true);
+        }
+
+        if !self.retry_statuses.is_empty() {
+            f.field("retry_on_status", &self.retry_statuses);
+        }
+
+        if self.max_retries > 0 {
+            f.field("max_retries", &self.max_retries);
+            f.field("retry_backoff", &self.retry_backoff);
+        }
+
+        if self.referer != redirect::RefererPolicy::NoReferrerWhenDowngrade {
+            f.field("referer", &self.referer);
+        }
+
+        if let Some(ref request_id) = self.request_id {
+            f.field("request_id_header", &request_id.name);
+        }
+
+        if self.request_interceptor.is_some() {
+            f.field("request_interceptor", &true);
+        }
+
+        if self.normalize_headers {
+            f.field("normalize_headers", &true);
+        }
+
+        if let Some(ref content_type) = self.default_content_type {
+            f.field("default_content_type", content_type);
         }
 
         f.field("default_headers", &self.headers);
@@ -1457,6 +2729,121 @@ impl ClientRef {
         if let Some(ref d) = self.request_timeout {
             f.field("timeout", d);
         }
+
+        if let Some(ref d) = self.total_timeout {
+            f.field("total_timeout", d);
+        }
+    }
+
+    fn accept_encoding(&self, headers: &mut HeaderMap) {
+        if headers.contains_key(ACCEPT_ENCODING) {
+            return;
+        }
+        if headers.contains_key(RANGE) {
+            if self.range_identity_encoding {
+                headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+            }
+        } else if let Some(accept_encoding) = self.accepts_for(headers.get(ACCEPT)).as_str() {
+            headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(accept_encoding));
+        }
+    }
+
+    /// The encodings to use for a message with this `Accept` or
+    /// `Content-Type` header value.
+    fn accepts_for(&self, _media_types: Option<&HeaderValue>) -> Accepts {
+        #[allow(unused_mut)]
+        let mut accepts = self.accepts;
+        #[cfg(feature = "gzip")]
+        {
+            if skips_gzip(&self.gzip_skip_content_types, _media_types) {
+                accepts.gzip = false;
+                accepts.gzip_content_type = false;
+            }
+        }
+        accepts
+    }
+
+    /// Holds back the body of `req` until the server answers with
+    /// `100 Continue`, if it asks for one.
+    fn expect_continue(&self, req: &mut hyper::Request<ImplStream>) {
+        if let Some(timeout) = self.expect_continue_timeout {
+            if expects_continue(req.headers()) {
+                let connection = hyper::client::connect::capture_connection(req);
+                req.body_mut().expect_continue(timeout, connection);
+            }
+        }
+    }
+
+    /// A hyper client that connects through `proxies` instead of the
+    /// client's own. Requests with equal proxies share its connection pool.
+    fn hyper_with_proxies(&self, proxies: Arc<Vec<Proxy>>) -> HyperClient {
+        let key = proxies.iter().map(Proxy::pool_key).collect::<Vec<_>>();
+        let mut clients = self.proxy_clients.lock().unwrap();
+        let now = Instant::now();
+        if let Some((hyper, last_used)) = clients.get_mut(&key) {
+            *last_used = now;
+            return hyper.clone();
+        }
+        // Requests with ever new proxies don't grow this without bound, and
+        // the pools that are in use are kept.
+        if clients.len() >= MAX_PROXY_CLIENTS {
+            let oldest = clients
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                clients.remove(&oldest);
+            }
+        }
+        let mut connector = self.connector.clone();
+        connector.set_proxies(proxies);
+        connector.separate_limit();
+        let hyper = self.hyper_builder.build(connector);
+        clients.insert(key, (hyper.clone(), now));
+        hyper
+    }
+
+    /// Whether a request to `dst` is forwarded by an HTTP proxy, rather than
+    /// sent directly or through a tunnel.
+    fn forwards_http(&self, proxies: Option<&Vec<Proxy>>, dst: &Uri) -> bool {
+        if dst.scheme() != Some(&Scheme::HTTP) {
+            return false;
+        }
+        let proxies = proxies.unwrap_or(&self.proxies);
+        matches!(
+            proxies.iter().find_map(|proxy| proxy.intercept(dst)),
+            Some(crate::proxy::ProxyScheme::Http { .. })
+                | Some(crate::proxy::ProxyScheme::Https { .. })
+        )
+    }
+
+    fn proxy_auth(&self, proxies: Option<&Vec<Proxy>>, dst: &Uri, headers: &mut HeaderMap) {
+        let proxies = match proxies {
+            Some(proxies) => proxies,
+            None if self.proxies_maybe_http_auth => &self.proxies,
+            None => return,
+        };
+
+        // Only set the header here if the destination scheme is 'http',
+        // since otherwise, the header will be included in the CONNECT tunnel
+        // request instead.
+        if dst.scheme() != Some(&Scheme::HTTP) {
+            return;
+        }
+
+        if headers.contains_key(PROXY_AUTHORIZATION) {
+            return;
+        }
+
+        for proxy in proxies.iter() {
+            if proxy.is_match(dst) {
+                if let Some(header) = proxy.http_basic_auth(dst) {
+                    headers.insert(PROXY_AUTHORIZATION, header);
+                }
+
+                break;
+            }
+        }
     }
 }
 
@@ -1476,15 +2863,33 @@ pin_project! {
     struct PendingRequest {
         method: Method,
         url: Url,
+        version: Version,
         headers: HeaderMap,
         body: Option<Option<Bytes>>,
 
         urls: Vec<Url>,
+        redirects: Vec<(StatusCode, Url, Version)>,
 
         client: Arc<ClientRef>,
-
+        // Set when the request overrides the client's proxies.
+        hyper: Option<Box<HyperClient>>,
+        proxies: Option<Arc<Vec<Proxy>>>,
+        force_decode: Option<Encoding>,
+        asterisk_form: bool,
+        // The connection of a request asking to close it.
+        closing: Option<CaptureConnection>,
+        retries: usize,
+        // Set while waiting to send a retry.
+        retry: Option<Retry>,
+
+        started: Instant,
+        // When `in_flight` was sent, for the time to first byte.
+        sent: Instant,
         #[pin]
         in_flight: ResponseFuture,
+        attempt_timeout: Option<Duration>,
+        // When the `total_timeout` passes.
+        deadline: Option<Instant>,
         #[pin]
         timeout: Option<Pin<Box<Sleep>>>,
     }
@@ -1503,9 +2908,153 @@ impl PendingRequest {
         self.project().urls
     }
 
+    fn redirects(self: Pin<&mut Self>) -> &mut Vec<(StatusCode, Url, Version)> {
+        self.project().redirects
+    }
+
     fn headers(self: Pin<&mut Self>) -> &mut HeaderMap {
         self.project().headers
     }
+
+    fn retry(self: Pin<&mut Self>) -> &mut Option<Retry> {
+        self.project().retry
+    }
+
+    /// Waits for `delay` before sending the request again, reading the
+    /// body of the response that caused the retry meanwhile, if any.
+    fn wait_to_retry(mut self: Pin<&mut Self>, delay: Duration, draining: Option<hyper::Body>) {
+        self.retries += 1;
+        *self.as_mut().retry() = Some(Retry::new(delay, draining));
+        // Only the total timeout runs while waiting.
+        let deadline = self.deadline;
+        self.set_timeout(deadline);
+    }
+
+    fn set_timeout(self: Pin<&mut Self>, deadline: Option<Instant>) {
+        self.timeout()
+            .set(deadline.map(|deadline| Box::pin(tokio::time::sleep_until(deadline.into()))));
+    }
+
+    /// Sends the request again, with the cookies the store holds now.
+    fn send_again(mut self: Pin<&mut Self>) {
+        #[cfg(feature = "cookies")]
+        {
+            let client = self.client.clone();
+            if let Some(ref cookie_store) = client.cookie_store {
+                let url = self.url.clone();
+                add_cookie_header(self.as_mut().headers(), &**cookie_store, &url);
+            }
+        }
+
+        let body = match self.body {
+            Some(Some(ref body)) => Body::reusable(body.clone()),
+            _ => Body::empty(),
+        };
+        let mut uri = expect_uri(&self.url);
+        if self.asterisk_form {
+            uri = into_asterisk_form(uri);
+        }
+        let mut req = hyper::Request::builder()
+            .method(self.method.clone())
+            .uri(uri)
+            .version(self.version)
+            .body(body.into_stream())
+            .expect("valid request parts");
+        *req.headers_mut() = self.headers.clone();
+        self.client.expect_continue(&mut req);
+        self.closing = capture_closing(&mut req);
+        self.sent = Instant::now();
+        *self.as_mut().in_flight().get_mut() = self
+            .hyper
+            .as_deref()
+            .unwrap_or(&self.client.hyper)
+            .request(req);
+
+        let deadline = attempt_deadline(self.attempt_timeout, self.deadline);
+        self.set_timeout(deadline);
+    }
+}
+
+/// The earlier of the end of an attempt starting now, and the deadline of
+/// the whole request.
+fn attempt_deadline(timeout: Option<Duration>, deadline: Option<Instant>) -> Option<Instant> {
+    match (timeout.map(|timeout| Instant::now() + timeout), deadline) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// A retry waiting for its delay to pass.
+///
+/// Meanwhile, the body of the response that caused it is read, so that its
+/// connection can go back to the pool.
+struct Retry {
+    delay: Pin<Box<Sleep>>,
+    draining: Option<hyper::Body>,
+    drained: usize,
+}
+
+impl Retry {
+    fn new(delay: Duration, draining: Option<hyper::Body>) -> Retry {
+        Retry {
+            delay: Box::pin(tokio::time::sleep(delay)),
+            draining,
+            drained: 0,
+        }
+    }
+
+    fn poll_delay(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        while let Some(ref mut body) = self.draining {
+            match http_body::Body::poll_data(Pin::new(body), cx) {
+                Poll::Ready(Some(Ok(chunk))) if self.drained < MAX_RETRY_DRAIN => {
+                    self.drained += chunk.len();
+                }
+                // Done, failed, or too long to be worth keeping the
+                // connection for.
+                Poll::Ready(_) => self.draining = None,
+                Poll::Pending => break,
+            }
+        }
+        self.delay.as_mut().poll(cx)
+    }
+}
+
+/// The delays between retries set by `ClientBuilder::retry_backoff`.
+#[derive(Clone, Copy, Debug)]
+struct RetryBackoff {
+    base: Duration,
+    max: Duration,
+    jitter: bool,
+}
+
+impl RetryBackoff {
+    /// The delay before the retry following `retries` earlier ones.
+    fn delay(&self, retries: usize) -> Duration {
+        let factor = 1u32.checked_shl(retries as u32).unwrap_or(u32::MAX);
+        let delay = self
+            .base
+            .checked_mul(factor)
+            .map_or(self.max, |delay| delay.min(self.max));
+        if self.jitter {
+            let half = delay.as_nanos() as u64 / 2;
+            delay + Duration::from_nanos(crate::util::fast_random() % (half + 1))
+        } else {
+            delay
+        }
+    }
+}
+
+/// How long a `Retry-After` header asks to wait, if it has a valid value.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(std::time::SystemTime::now())
+            .unwrap_or_default(),
+    )
 }
 
 impl Pending {
@@ -1538,33 +3087,95 @@ impl Future for PendingRequest {
     type Output = Result<Response, crate::Error>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if let Some(delay) = self.as_mut().timeout().as_mut().as_pin_mut() {
-            if let Poll::Ready(()) = delay.poll(cx) {
-                return Poll::Ready(Err(
-                    crate::error::request(crate::error::TimedOut).with_url(self.url.clone())
-                ));
+        loop {
+            if let Some(delay) = self.as_mut().timeout().as_mut().as_pin_mut() {
+                if let Poll::Ready(()) = delay.poll(cx) {
+                    return Poll::Ready(Err(
+                        crate::error::request(crate::error::TimedOut).with_url(self.url.clone())
+                    ));
+                }
+            }
+
+            if let Some(retry) = self.as_mut().retry() {
+                futures_core::ready!(retry.poll_delay(cx));
+                *self.as_mut().retry() = None;
+                // Polls the new attempt's timeout before sending it.
+                self.as_mut().send_again();
+                continue;
             }
-        }
 
-        loop {
             let res = match self.as_mut().in_flight().as_mut().poll(cx) {
                 Poll::Ready(Err(e)) => {
+                    // A request that failed to connect was never sent, so
+                    // it can be retried as long as its body can be.
+                    if e.is_connect()
+                        && self.retries < self.client.max_retries
+                        && !matches!(self.body, Some(None))
+                    {
+                        let delay = self.client.retry_backoff.delay(self.retries);
+                        debug!("retrying '{}' in {:?} after {}", self.url, delay, e);
+                        self.as_mut().wait_to_retry(delay, None);
+                        continue;
+                    }
                     return Poll::Ready(Err(crate::error::request(e).with_url(self.url.clone())));
                 }
                 Poll::Ready(Ok(res)) => res,
                 Poll::Pending => return Poll::Pending,
             };
 
+            // hyper can return a connection to the pool before it has seen
+            // that the connection is closing, and hand it to another request.
+            if res.version() < Version::HTTP_2 {
+                if let Some(ref closing) = self.closing {
+                    if let Some(ref connected) = *closing.connection_metadata() {
+                        connected.poison();
+                    }
+                }
+            }
+
             #[cfg(feature = "cookies")]
             {
                 if let Some(ref cookie_store) = self.client.cookie_store {
                     let mut cookies =
                         cookie::extract_response_cookie_headers(&res.headers()).peekable();
-                    if cookies.peek().is_some() {
+                    if let Some(ref on_change) = self.client.cookie_change {
+                        // Also without cookies, to report the ones that expired.
+                        cookie_store.set_cookies_with_changes(
+                            &mut cookies,
+                            &self.url,
+                            &mut |change| on_change(&change),
+                        );
+                    } else if cookies.peek().is_some() {
                         cookie_store.set_cookies(&mut cookies, &self.url);
                     }
                 }
             }
+
+            if self.retries < self.client.max_retries
+                && self.client.retry_statuses.contains(&res.status())
+                && is_idempotent(&self.method)
+                && !matches!(self.body, Some(None))
+            {
+                // A server asking for a longer wait gets its response
+                // returned instead.
+                let backoff = self.client.retry_backoff;
+                let delay = match retry_after(res.headers()) {
+                    Some(delay) if delay > backoff.max => None,
+                    Some(delay) => Some(delay),
+                    None => Some(backoff.delay(self.retries)),
+                };
+                if let Some(delay) = delay {
+                    debug!(
+                        "retrying '{}' in {:?} after status '{}'",
+                        self.url,
+                        delay,
+                        res.status()
+                    );
+                    self.as_mut().wait_to_retry(delay, Some(res.into_body()));
+                    continue;
+                }
+            }
+
             let should_redirect = match res.status() {
                 StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER => {
                     self.body = None;
@@ -1599,7 +3210,9 @@ impl Future for PendingRequest {
                         // Some sites may send a utf-8 Location header,
                         // even though we're supposed to treat those bytes
                         // as opaque, we'll check specifically for utf8.
-                        self.url.join(str::from_utf8(val.as_bytes()).ok()?).ok()
+                        // Other bytes are only used if the policy allows it.
+                        let location = self.client.redirect_policy.location(val.as_bytes())?;
+                        self.url.join(&location).ok()
                     })();
 
                     // Check that the `url` is also a valid `http::Uri`.
@@ -1619,10 +3232,8 @@ impl Future for PendingRequest {
                     loc
                 });
                 if let Some(loc) = loc {
-                    if self.client.referer {
-                        if let Some(referer) = make_referer(&loc, &self.url) {
-                            self.headers.insert(REFERER, referer);
-                        }
+                    if let Some(referer) = make_referer(self.client.referer, &loc, &self.url) {
+                        self.headers.insert(REFERER, referer);
                     }
                     let url = self.url.clone();
                     self.as_mut().urls().push(url);
@@ -1634,6 +3245,13 @@ impl Future for PendingRequest {
                     match action {
                         redirect::ActionKind::Follow => {
                             debug!("redirecting '{}' to '{}'", self.url, loc);
+                            if self.client.record_redirects {
+                                self.as_mut().redirects().push((
+                                    res.status(),
+                                    loc.clone(),
+                                    res.version(),
+                                ));
+                            }
                             self.url = loc;
 
                             let mut headers =
@@ -1641,6 +3259,14 @@ impl Future for PendingRequest {
 
                             remove_sensitive_headers(&mut headers, &self.url, &self.urls);
                             let uri = expect_uri(&self.url);
+
+                            // Headers carried over from the previous hop
+                            // already include the client defaults, but the
+                            // ones that depend on the destination need to
+                            // be worked out again.
+                            self.client.accept_encoding(&mut headers);
+                            self.client
+                                .proxy_auth(self.proxies.as_deref(), &uri, &mut headers);
                             let body = match self.body {
                                 Some(Some(ref body)) => Body::reusable(body.clone()),
                                 _ => Body::empty(),
@@ -1660,12 +3286,21 @@ impl Future for PendingRequest {
                             }
 
                             *req.headers_mut() = headers.clone();
+                            self.client.expect_continue(&mut req);
+                            self.closing = capture_closing(&mut req);
                             std::mem::swap(self.as_mut().headers(), &mut headers);
-                            *self.as_mut().in_flight().get_mut() = self.client.hyper.request(req);
+                            self.sent = Instant::now();
+                            *self.as_mut().in_flight().get_mut() = self
+                                .hyper
+                                .as_deref()
+                                .unwrap_or(&self.client.hyper)
+                                .request(req);
                             continue;
                         }
                         redirect::ActionKind::Stop => {
                             debug!("redirect policy disallowed redirection to '{}'", loc);
+                            // This hop wasn't followed, so it isn't part of the chain.
+                            self.as_mut().urls().pop();
                         }
                         redirect::ActionKind::Error(err) => {
                             return Poll::Ready(Err(crate::error::redirect(err, self.url.clone())));
@@ -1675,12 +3310,25 @@ impl Future for PendingRequest {
             }
 
             debug!("response '{}' for {}", res.status(), self.url);
-            let res = Response::new(
-                res,
-                self.url.clone(),
-                self.client.accepts,
-                self.timeout.take(),
-            );
+            // A response to HEAD has no body, whatever its headers say, so
+            // leave the encoding headers describing the resource alone.
+            let accepts = if self.method == Method::HEAD {
+                Accepts::none()
+            } else {
+                Accepts {
+                    force: self.force_decode,
+                    ..self.client.accepts_for(res.headers().get(CONTENT_TYPE))
+                }
+            };
+            let details = RequestDetails {
+                method: self.method.clone(),
+                redirect_count: self.urls.len(),
+                redirects: std::mem::take(self.as_mut().redirects()),
+                elapsed: self.started.elapsed(),
+                accepts,
+            };
+            let mut res = Response::new(res, self.url.clone(), details, self.timeout.take());
+            res.set_time_to_first_byte(self.sent.elapsed());
             return Poll::Ready(Ok(res));
         }
     }
@@ -1699,16 +3347,95 @@ impl fmt::Debug for Pending {
     }
 }
 
-fn make_referer(next: &Url, previous: &Url) -> Option<HeaderValue> {
-    if next.scheme() == "http" && previous.scheme() == "https" {
-        return None;
+fn expects_continue(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(EXPECT)
+        .iter()
+        .any(|value| value.as_bytes().eq_ignore_ascii_case(b"100-continue"))
+}
+
+/// Captures the connection of a request with a `Connection: close` header,
+/// to keep it out of the pool.
+fn capture_closing(req: &mut hyper::Request<ImplStream>) -> Option<CaptureConnection> {
+    let closes = req
+        .headers()
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|token| token.trim().eq_ignore_ascii_case("close"));
+    if closes {
+        Some(hyper::client::connect::capture_connection(req))
+    } else {
+        None
+    }
+}
+
+/// Replace the path and query of `uri` with `*`, the request-target of
+/// `OPTIONS *`.
+fn into_asterisk_form(uri: Uri) -> Uri {
+    let mut parts = uri.into_parts();
+    parts.path_and_query = Some(http::uri::PathAndQuery::from_static("*"));
+    Uri::from_parts(parts).expect("asterisk-form is a valid uri")
+}
+
+/// Keep only the last value of headers that were given several.
+fn collapse_duplicate_headers(headers: &mut HeaderMap) {
+    let repeated = headers
+        .keys()
+        .filter(|name| headers.get_all(*name).iter().nth(1).is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+    for name in repeated {
+        if let Some(last) = headers.get_all(&name).iter().next_back().cloned() {
+            headers.insert(name, last);
+        }
     }
+}
+
+/// Whether sending a request with `method` twice has the same effect as
+/// sending it once, per RFC 7231 section 4.2.2.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
 
-    let mut referer = previous.clone();
-    let _ = referer.set_username("");
-    let _ = referer.set_password(None);
-    referer.set_fragment(None);
-    referer.as_str().parse().ok()
+fn make_referer(
+    policy: redirect::RefererPolicy,
+    next: &Url,
+    previous: &Url,
+) -> Option<HeaderValue> {
+    policy.referer(next, previous)?.as_str().parse().ok()
+}
+
+/// Whether every media type in `value` matches one of `skip`.
+#[cfg(feature = "gzip")]
+fn skips_gzip(skip: &[String], value: Option<&HeaderValue>) -> bool {
+    if skip.is_empty() {
+        return false;
+    }
+    let value = match value.and_then(|value| value.to_str().ok()) {
+        Some(value) => value,
+        None => return false,
+    };
+    let mut types = value
+        .split(',')
+        .map(|ty| ty.split(';').next().unwrap_or("").trim())
+        .filter(|ty| !ty.is_empty())
+        .peekable();
+    types.peek().is_some()
+        && types.all(|ty| {
+            skip.iter().any(|pattern| {
+                let prefix = pattern.strip_suffix('*').unwrap_or(pattern);
+                if prefix.len() < pattern.len() {
+                    ty.len() > prefix.len() && ty[..prefix.len()].eq_ignore_ascii_case(prefix)
+                } else {
+                    ty.eq_ignore_ascii_case(pattern)
+                }
+            })
+        })
 }
 
 #[cfg(feature = "cookies")]
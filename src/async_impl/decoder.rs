@@ -13,6 +13,8 @@ use async_compression::tokio::bufread::BrotliDecoder;
 use async_compression::tokio::bufread::ZlibDecoder;
 
 use bytes::Bytes;
+#[cfg(feature = "stream")]
+use bytes::BytesMut;
 use futures_core::Stream;
 use futures_util::stream::Peekable;
 use http::HeaderMap;
@@ -30,10 +32,35 @@ use crate::error;
 pub(super) struct Accepts {
     #[cfg(feature = "gzip")]
     pub(super) gzip: bool,
+    #[cfg(feature = "gzip")]
+    pub(super) gzip_content_type: bool,
     #[cfg(feature = "brotli")]
     pub(super) brotli: bool,
     #[cfg(feature = "deflate")]
     pub(super) deflate: bool,
+    /// Whether the enabled encodings are sent in `Accept-Encoding`.
+    pub(super) advertise: bool,
+    /// Whether responses in the enabled encodings are decompressed.
+    pub(super) decompress: bool,
+    /// An encoding to decode with, whatever the response headers say.
+    pub(super) force: Option<Encoding>,
+}
+
+/// A content coding a response body can be decoded from.
+///
+/// See `RequestBuilder::force_decode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// The `gzip` coding.
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// The `br` coding.
+    #[cfg(feature = "brotli")]
+    Brotli,
+    /// The `deflate` coding.
+    #[cfg(feature = "deflate")]
+    Deflate,
 }
 
 /// A response decompressor over a non-blocking stream of chunks.
@@ -41,6 +68,8 @@ pub(super) struct Accepts {
 /// The inner decoder may be constructed asynchronously.
 pub(crate) struct Decoder {
     inner: Inner,
+    /// Whether the end of the stream has been returned.
+    eof: bool,
 }
 
 enum Inner {
@@ -89,6 +118,7 @@ impl Decoder {
     pub(crate) fn empty() -> Decoder {
         Decoder {
             inner: Inner::PlainText(Body::empty().into_stream()),
+            eof: false,
         }
     }
 
@@ -98,6 +128,7 @@ impl Decoder {
     fn plain_text(body: Body) -> Decoder {
         Decoder {
             inner: Inner::PlainText(body.into_stream()),
+            eof: false,
         }
     }
 
@@ -113,6 +144,7 @@ impl Decoder {
                 IoStream(body.into_stream()).peekable(),
                 DecoderType::Gzip,
             )),
+            eof: false,
         }
     }
 
@@ -128,6 +160,7 @@ impl Decoder {
                 IoStream(body.into_stream()).peekable(),
                 DecoderType::Brotli,
             )),
+            eof: false,
         }
     }
 
@@ -143,6 +176,7 @@ impl Decoder {
                 IoStream(body.into_stream()).peekable(),
                 DecoderType::Deflate,
             )),
+            eof: false,
         }
     }
 
@@ -176,6 +210,41 @@ impl Decoder {
         is_content_encoded
     }
 
+    #[cfg(feature = "gzip")]
+    fn detect_gzip_content_type(headers: &mut HeaderMap) -> bool {
+        use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+
+        let is_gzip = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<mime::Mime>().ok())
+            .map(|mime| mime.type_() == mime::APPLICATION && mime.subtype() == "x-gzip")
+            .unwrap_or(false);
+        if is_gzip {
+            if let Some(true) = headers.get(CONTENT_LENGTH).map(|len| len == "0") {
+                return false;
+            }
+            headers.remove(CONTENT_LENGTH);
+        }
+        is_gzip
+    }
+
+    /// A decoder for `encoding`, ignoring the response headers.
+    fn forced(headers: &mut HeaderMap, _body: Body, encoding: Encoding) -> Decoder {
+        use http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
+
+        headers.remove(CONTENT_ENCODING);
+        headers.remove(CONTENT_LENGTH);
+        match encoding {
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => Decoder::gzip(_body),
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => Decoder::brotli(_body),
+            #[cfg(feature = "deflate")]
+            Encoding::Deflate => Decoder::deflate(_body),
+        }
+    }
+
     /// Constructs a Decoder from a hyper request.
     ///
     /// A decoder is just a wrapper around the hyper request that knows
@@ -183,11 +252,22 @@ impl Decoder {
     ///
     /// Uses the correct variant by inspecting the Content-Encoding header.
     pub(super) fn detect(_headers: &mut HeaderMap, body: Body, _accepts: Accepts) -> Decoder {
+        if let Some(encoding) = _accepts.force {
+            return Decoder::forced(_headers, body, encoding);
+        }
+
+        if !_accepts.decompress {
+            return Decoder::plain_text(body);
+        }
+
         #[cfg(feature = "gzip")]
         {
             if _accepts.gzip && Decoder::detect_encoding(_headers, "gzip") {
                 return Decoder::gzip(body);
             }
+            if _accepts.gzip_content_type && Decoder::detect_gzip_content_type(_headers) {
+                return Decoder::gzip(body);
+            }
         }
 
         #[cfg(feature = "brotli")]
@@ -206,19 +286,23 @@ impl Decoder {
 
         Decoder::plain_text(body)
     }
-}
 
-impl Stream for Decoder {
-    type Item = Result<Bytes, error::Error>;
+    /// Returns whether the decoder has reached the end of the body.
+    pub(super) fn is_end_stream_reached(&self) -> bool {
+        self.eof
+    }
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+    fn poll_decode(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Result<Bytes, error::Error>>> {
         // Do a read or poll for a pending decoder value.
         match self.inner {
             #[cfg(any(feature = "brotli", feature = "gzip", feature = "deflate"))]
             Inner::Pending(ref mut future) => match Pin::new(future).poll(cx) {
                 Poll::Ready(Ok(inner)) => {
                     self.inner = inner;
-                    return self.poll_next(cx);
+                    return self.poll_decode(cx);
                 }
                 Poll::Ready(Err(e)) => {
                     return Poll::Ready(Some(Err(crate::error::decode_io(e))));
@@ -254,6 +338,18 @@ impl Stream for Decoder {
     }
 }
 
+impl Stream for Decoder {
+    type Item = Result<Bytes, error::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let item = futures_core::ready!(self.as_mut().poll_decode(cx));
+        if item.is_none() {
+            self.eof = true;
+        }
+        Poll::Ready(item)
+    }
+}
+
 impl HttpBody for Decoder {
     type Data = Bytes;
     type Error = crate::Error;
@@ -282,6 +378,60 @@ impl HttpBody for Decoder {
     }
 }
 
+/// A stream that buffers the chunks of a `Decoder` until at least
+/// `min_size` bytes are available.
+#[cfg(feature = "stream")]
+pub(super) struct Coalesce {
+    inner: Decoder,
+    min_size: usize,
+    buf: BytesMut,
+    done: bool,
+}
+
+#[cfg(feature = "stream")]
+impl Coalesce {
+    pub(super) fn new(inner: Decoder, min_size: usize) -> Coalesce {
+        Coalesce {
+            inner,
+            min_size,
+            buf: BytesMut::new(),
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+impl Stream for Coalesce {
+    type Item = Result<Bytes, error::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.done {
+                return Poll::Ready(None);
+            }
+
+            match futures_core::ready!(Pin::new(&mut self.inner).poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    if self.buf.is_empty() && chunk.len() >= self.min_size {
+                        return Poll::Ready(Some(Ok(chunk)));
+                    }
+                    self.buf.extend_from_slice(&chunk);
+                    if self.buf.len() >= self.min_size {
+                        return Poll::Ready(Some(Ok(self.buf.split().freeze())));
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => {
+                    self.done = true;
+                    if !self.buf.is_empty() {
+                        return Poll::Ready(Some(Ok(self.buf.split().freeze())));
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl Future for Pending {
     type Output = Result<Inner, std::io::Error>;
 
@@ -315,10 +465,13 @@ impl Future for Pending {
                 BytesCodec::new(),
             )))),
             #[cfg(feature = "gzip")]
-            DecoderType::Gzip => Poll::Ready(Ok(Inner::Gzip(FramedRead::new(
-                GzipDecoder::new(StreamReader::new(_body)),
-                BytesCodec::new(),
-            )))),
+            DecoderType::Gzip => {
+                // A gzip stream may be several members back to back, which
+                // decode to their concatenation.
+                let mut decoder = GzipDecoder::new(StreamReader::new(_body));
+                decoder.multiple_members(true);
+                Poll::Ready(Ok(Inner::Gzip(FramedRead::new(decoder, BytesCodec::new()))))
+            }
             #[cfg(feature = "deflate")]
             DecoderType::Deflate => Poll::Ready(Ok(Inner::Deflate(FramedRead::new(
                 ZlibDecoder::new(StreamReader::new(_body)),
@@ -347,14 +500,22 @@ impl Accepts {
         Accepts {
             #[cfg(feature = "gzip")]
             gzip: false,
+            #[cfg(feature = "gzip")]
+            gzip_content_type: false,
             #[cfg(feature = "brotli")]
             brotli: false,
             #[cfg(feature = "deflate")]
             deflate: false,
+            advertise: false,
+            decompress: false,
+            force: None,
         }
     }
 
     pub(super) fn as_str(&self) -> Option<&'static str> {
+        if !self.advertise {
+            return None;
+        }
         match (self.is_gzip(), self.is_brotli(), self.is_deflate()) {
             (true, true, true) => Some("gzip, br, deflate"),
             (true, true, false) => Some("gzip, br"),
@@ -409,10 +570,15 @@ impl Default for Accepts {
         Accepts {
             #[cfg(feature = "gzip")]
             gzip: true,
+            #[cfg(feature = "gzip")]
+            gzip_content_type: false,
             #[cfg(feature = "brotli")]
             brotli: true,
             #[cfg(feature = "deflate")]
             deflate: true,
+            advertise: true,
+            decompress: true,
+            force: None,
         }
     }
 }
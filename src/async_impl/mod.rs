@@ -1,7 +1,13 @@
 pub use self::body::Body;
 pub use self::client::{Client, ClientBuilder};
 pub use self::request::{Request, RequestBuilder};
-pub use self::response::{Response, ResponseBuilderExt};
+pub use self::response::{Response, ResponseBuilderExt, Timings};
+
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "deflate"))]
+pub use self::decoder::Encoding;
+
+#[cfg(feature = "stream")]
+pub use self::sse::SseEvent;
 
 #[cfg(feature = "blocking")]
 pub(crate) use self::decoder::Decoder;
@@ -13,3 +19,5 @@ pub mod decoder;
 pub mod multipart;
 pub(crate) mod request;
 mod response;
+#[cfg(feature = "stream")]
+mod sse;
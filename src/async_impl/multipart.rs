@@ -1,6 +1,10 @@
 //! multipart/form-data
 use std::borrow::Cow;
 use std::fmt;
+#[cfg(feature = "fs")]
+use std::io;
+#[cfg(feature = "fs")]
+use std::path::Path;
 use std::pin::Pin;
 
 use bytes::Bytes;
@@ -10,10 +14,14 @@ use percent_encoding::{self, AsciiSet, NON_ALPHANUMERIC};
 
 use futures_core::Stream;
 use futures_util::{future, stream, StreamExt};
+#[cfg(feature = "fs")]
+use tokio_util::io::ReaderStream;
 
 use super::Body;
 
 /// An async multipart/form-data request.
+///
+/// Parts are always sent in the order they were added to the `Form`.
 pub struct Form {
     inner: FormParts<Part>,
 }
@@ -32,6 +40,7 @@ pub(crate) struct FormParts<P> {
     pub(crate) percent_encoding: PercentEncoding,
 }
 
+#[derive(Clone)]
 pub(crate) struct PartMetadata {
     mime: Option<Mime>,
     file_name: Option<Cow<'static, str>>,
@@ -59,6 +68,34 @@ impl Form {
         }
     }
 
+    /// Creates a new async Form without any content, generating its
+    /// boundary from `rng` instead of the default random source.
+    ///
+    /// `rng` is called four times for the 64 random bits of each of the
+    /// boundary's parts. A seeded generator gives a reproducible boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // A tiny xorshift generator, seeded for reproducible boundaries.
+    /// let mut state = 42u64;
+    /// let form = reqwest::multipart::Form::new_with_rng(|| {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     state
+    /// });
+    /// # drop(form);
+    /// ```
+    pub fn new_with_rng<R>(rng: R) -> Form
+    where
+        R: FnMut() -> u64,
+    {
+        Form {
+            inner: FormParts::with_boundary(gen_boundary_with(rng)),
+        }
+    }
+
     /// Get the boundary that this form will use.
     #[inline]
     pub fn boundary(&self) -> &str {
@@ -82,6 +119,35 @@ impl Form {
         self.part(name, Part::text(value))
     }
 
+    /// Adds a file field.
+    ///
+    /// The path will be used to try to guess the filename and mime. The
+    /// contents are streamed when the request is sent.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> std::io::Result<()> {
+    /// let files = reqwest::multipart::Form::new()
+    ///     .file("key", "/path/to/file")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors when the file cannot be opened.
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub async fn file<T, U>(self, name: T, path: U) -> io::Result<Form>
+    where
+        T: Into<Cow<'static, str>>,
+        U: AsRef<Path>,
+    {
+        Ok(self.part(name, Part::file(path).await?))
+    }
+
     /// Adds a customized Part.
     pub fn part<T>(self, name: T, part: Part) -> Form
     where
@@ -105,6 +171,24 @@ impl Form {
         self.with_inner(|inner| inner.percent_encode_noop())
     }
 
+    /// Attempts to clone the `Form`, to send it in more than one request.
+    ///
+    /// `None` is returned if any part is a stream or a file, since those
+    /// can only be read once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let form = reqwest::multipart::Form::new().text("username", "seanmonstar");
+    /// let retry = form.try_clone().expect("text parts can be cloned");
+    /// # drop((form, retry));
+    /// ```
+    pub fn try_clone(&self) -> Option<Form> {
+        self.inner
+            .try_clone(Part::try_clone)
+            .map(|inner| Form { inner })
+    }
+
     /// Consume this instance and transform into an instance of Body for use in a request.
     pub(crate) fn stream(mut self) -> Body {
         if self.inner.fields.is_empty() {
@@ -218,6 +302,35 @@ impl Part {
         Part::new(value.into(), Some(length))
     }
 
+    /// Makes a file parameter.
+    ///
+    /// The path is used to set the filename, and to guess the mime from the
+    /// extension. The contents are streamed when the request is sent.
+    ///
+    /// # Errors
+    ///
+    /// Errors when the file cannot be opened.
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub async fn file<T: AsRef<Path>>(path: T) -> io::Result<Part> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .map(|filename| filename.to_string_lossy().into_owned());
+        let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        let mime = mime_guess::from_ext(ext).first_or_octet_stream();
+        let file = tokio::fs::File::open(path).await?;
+        let length = file.metadata().await?.len();
+        let body = Body::stream(ReaderStream::new(file));
+        let field = Part::new(body, Some(length)).mime(mime);
+
+        Ok(if let Some(file_name) = file_name {
+            field.file_name(file_name)
+        } else {
+            field
+        })
+    }
+
     fn new(value: Body, body_length: Option<u64>) -> Part {
         Part {
             meta: PartMetadata::new(),
@@ -226,7 +339,22 @@ impl Part {
         }
     }
 
+    fn try_clone(&self) -> Option<Part> {
+        Some(Part {
+            meta: self.meta.clone(),
+            value: self.value.try_clone()?,
+            body_length: self.body_length,
+        })
+    }
+
     /// Tries to set the mime of this part.
+    ///
+    /// The mime is sent as the part's `Content-Type` header. Parts made with
+    /// `Part::bytes` or `Part::stream` don't have one unless it is set here.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `mime` can't be parsed as a mime type.
     pub fn mime_str(self, mime: &str) -> crate::Result<Part> {
         Ok(self.mime(mime.parse().map_err(crate::error::builder)?))
     }
@@ -282,8 +410,12 @@ impl PartProps for Part {
 
 impl<P: PartProps> FormParts<P> {
     pub(crate) fn new() -> Self {
+        FormParts::with_boundary(gen_boundary())
+    }
+
+    pub(crate) fn with_boundary(boundary: String) -> Self {
         FormParts {
-            boundary: gen_boundary(),
+            boundary,
             computed_headers: Vec::new(),
             fields: Vec::new(),
             percent_encoding: PercentEncoding::PathSegment,
@@ -356,6 +488,23 @@ impl<P: PartProps> FormParts<P> {
         Some(length)
     }
 
+    /// Clones the form, if `clone_part` can clone every part.
+    pub(crate) fn try_clone<F>(&self, clone_part: F) -> Option<Self>
+    where
+        F: Fn(&P) -> Option<P>,
+    {
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for (name, part) in &self.fields {
+            fields.push((name.clone(), clone_part(part)?));
+        }
+        Some(FormParts {
+            boundary: self.boundary.clone(),
+            computed_headers: self.computed_headers.clone(),
+            fields,
+            percent_encoding: self.percent_encoding,
+        })
+    }
+
     /// Take the fields vector of this instance, replacing with an empty vector.
     fn take_fields(&mut self) -> Vec<(Cow<'static, str>, P)> {
         std::mem::replace(&mut self.fields, Vec::new())
@@ -436,6 +585,7 @@ const ATTR_CHAR_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
     .remove(b'|')
     .remove(b'~');
 
+#[derive(Clone, Copy)]
 pub(crate) enum PercentEncoding {
     PathSegment,
     AttrChar,
@@ -500,8 +650,13 @@ impl PercentEncoding {
 }
 
 fn gen_boundary() -> String {
-    use crate::util::fast_random as random;
+    gen_boundary_with(crate::util::fast_random)
+}
 
+pub(crate) fn gen_boundary_with<R>(mut random: R) -> String
+where
+    R: FnMut() -> u64,
+{
     let a = random();
     let b = random();
     let c = random();
@@ -588,6 +743,93 @@ mod tests {
         assert_eq!(std::str::from_utf8(&out).unwrap(), expected);
     }
 
+    #[test]
+    fn stream_keeps_insertion_order() {
+        let mut form = Form::new()
+            .text("zebra", "1")
+            .text("apple", "2")
+            .text("mango", "3");
+        form.inner.boundary = "boundary".to_string();
+        let expected = "--boundary\r\n\
+                        Content-Disposition: form-data; name=\"zebra\"\r\n\r\n\
+                        1\r\n\
+                        --boundary\r\n\
+                        Content-Disposition: form-data; name=\"apple\"\r\n\r\n\
+                        2\r\n\
+                        --boundary\r\n\
+                        Content-Disposition: form-data; name=\"mango\"\r\n\r\n\
+                        3\r\n\
+                        --boundary--\r\n";
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("new rt");
+        let body = form.stream().into_stream();
+        let s = body.map(|try_c| try_c.map(|r| r.to_vec())).try_concat();
+
+        let out = rt.block_on(s).unwrap();
+        assert_eq!(std::str::from_utf8(&out).unwrap(), expected);
+    }
+
+    #[test]
+    fn bytes_part_with_mime_str() {
+        let part = Part::bytes(&b"\x00\x01"[..])
+            .mime_str("application/octet-stream")
+            .unwrap();
+        let mut form = Form::new().part("data", part);
+        form.inner.boundary = "boundary".to_string();
+        let expected = "--boundary\r\n\
+                        Content-Disposition: form-data; name=\"data\"\r\n\
+                        Content-Type: application/octet-stream\r\n\r\n\
+                        \x00\x01\r\n\
+                        --boundary--\r\n";
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("new rt");
+        let body = form.stream().into_stream();
+        let s = body.map(|try_c| try_c.map(|r| r.to_vec())).try_concat();
+
+        let out = rt.block_on(s).unwrap();
+        assert_eq!(std::str::from_utf8(&out).unwrap(), expected);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn file_part_streams_contents() {
+        let dir = std::env::temp_dir().join(format!("reqwest-multipart-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("upload.txt");
+        std::fs::write(&path, "file contents").unwrap();
+
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("new rt");
+        let mut form = rt.block_on(Form::new().file("upload", &path)).unwrap();
+        form.inner.boundary = "boundary".to_string();
+        let expected = "--boundary\r\n\
+                        Content-Disposition: form-data; name=\"upload\"; filename=\"upload.txt\"\r\n\
+                        Content-Type: text/plain\r\n\r\n\
+                        file contents\r\n\
+                        --boundary--\r\n";
+        assert_eq!(form.compute_length(), Some(expected.len() as u64));
+        let body = form.stream().into_stream();
+        let s = body.map(|try_c| try_c.map(|r| r.to_vec())).try_concat();
+
+        let out = rt.block_on(s).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(std::str::from_utf8(&out).unwrap(), expected);
+    }
+
+    #[test]
+    fn mime_str_rejects_invalid_mime() {
+        let err = Part::bytes(&b"data"[..])
+            .mime_str("not a mime")
+            .unwrap_err();
+        assert!(err.is_builder());
+    }
+
     #[test]
     fn stream_to_end_with_header() {
         let mut part = Part::text("value2").mime(mime::IMAGE_BMP);
@@ -11,13 +11,14 @@ use serde_json;
 
 use super::body::Body;
 use super::client::{Client, Pending};
+use super::decoder::Encoding;
 #[cfg(feature = "multipart")]
 use super::multipart;
 use super::response::Response;
 #[cfg(feature = "multipart")]
 use crate::header::CONTENT_LENGTH;
-use crate::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
-use crate::{Method, Url};
+use crate::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, CONTENT_TYPE};
+use crate::{Method, Proxy, Url};
 use http::{request::Parts, Request as HttpRequest, Version};
 
 /// A request which can be executed with `Client::execute()`.
@@ -28,6 +29,13 @@ pub struct Request {
     body: Option<Body>,
     timeout: Option<Duration>,
     version: Version,
+    no_accept: bool,
+    proxy: Option<Option<Proxy>>,
+    force_decode: Option<Encoding>,
+    trailers: Option<HeaderMap>,
+    #[cfg(feature = "brotli")]
+    brotli: bool,
+    asterisk_form: bool,
 }
 
 /// A builder to construct the properties of a `Request`.
@@ -50,6 +58,13 @@ impl Request {
             body: None,
             timeout: None,
             version: Version::default(),
+            no_accept: false,
+            proxy: None,
+            force_decode: None,
+            trailers: None,
+            #[cfg(feature = "brotli")]
+            brotli: false,
+            asterisk_form: false,
         }
     }
 
@@ -137,10 +152,84 @@ impl Request {
         *req.timeout_mut() = self.timeout().cloned();
         *req.headers_mut() = self.headers().clone();
         *req.version_mut() = self.version().clone();
+        req.no_accept = self.no_accept;
+        req.proxy = self.proxy.clone();
+        req.force_decode = self.force_decode;
+        req.trailers = self.trailers.clone();
+        req.asterisk_form = self.asterisk_form;
+        #[cfg(feature = "brotli")]
+        {
+            req.brotli = self.brotli;
+        }
         req.body = body;
         Some(req)
     }
 
+    /// Remove the `Accept` header and prevent the client from adding its
+    /// default one when this request is sent.
+    pub(crate) fn suppress_accept(&mut self) {
+        self.headers.remove(ACCEPT);
+        self.no_accept = true;
+    }
+
+    pub(crate) fn is_accept_suppressed(&self) -> bool {
+        self.no_accept
+    }
+
+    /// Send this request through `proxy` instead of the client's proxies,
+    /// or directly if it is `None`.
+    pub(crate) fn set_proxy(&mut self, proxy: Option<Proxy>) {
+        self.proxy = Some(proxy);
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn proxy_override(&self) -> Option<&Option<Proxy>> {
+        self.proxy.as_ref()
+    }
+
+    /// Decode the response body with `encoding`, whatever its headers say.
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "deflate"))]
+    pub(crate) fn set_force_decode(&mut self, encoding: Encoding) {
+        self.force_decode = Some(encoding);
+    }
+
+    pub(crate) fn forced_encoding(&self) -> Option<Encoding> {
+        self.force_decode
+    }
+
+    pub(super) fn take_proxy_override(&mut self) -> Option<Option<Proxy>> {
+        self.proxy.take()
+    }
+
+    /// Send `trailers` after the body, which must be streamed.
+    pub(crate) fn set_trailers(&mut self, trailers: HeaderMap) {
+        self.trailers = Some(trailers);
+    }
+
+    pub(super) fn take_trailers(&mut self) -> Option<HeaderMap> {
+        self.trailers.take()
+    }
+
+    /// Send `*` as the request-target, instead of the URL's path.
+    pub(crate) fn set_asterisk_form(&mut self) {
+        self.asterisk_form = true;
+    }
+
+    pub(crate) fn is_asterisk_form(&self) -> bool {
+        self.asterisk_form
+    }
+
+    /// Compress the body with Brotli when sending.
+    #[cfg(feature = "brotli")]
+    pub(crate) fn set_brotli(&mut self) {
+        self.brotli = true;
+    }
+
+    #[cfg(feature = "brotli")]
+    pub(crate) fn is_brotli(&self) -> bool {
+        self.brotli
+    }
+
     pub(super) fn pieces(
         self,
     ) -> (
@@ -190,6 +279,39 @@ impl RequestBuilder {
         self.header_sensitive(key, value, false)
     }
 
+    /// Add a `Header` to this Request, skipping it if it is invalid.
+    ///
+    /// Unlike [`header`](RequestBuilder::header), an invalid name or value
+    /// doesn't make the request fail to build. The header is left out and
+    /// a warning is logged instead, which is useful when headers come from
+    /// untrusted input and a best-effort request is preferred.
+    pub fn header_opt<K, V>(mut self, key: K, value: V) -> RequestBuilder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        if let Ok(ref mut req) = self.request {
+            match <HeaderName as TryFrom<K>>::try_from(key) {
+                Ok(key) => match <HeaderValue as TryFrom<V>>::try_from(value) {
+                    Ok(value) => {
+                        req.headers_mut().append(key, value);
+                    }
+                    Err(e) => {
+                        let e: http::Error = e.into();
+                        log::warn!("skipping invalid value for header {:?}: {}", key, e);
+                    }
+                },
+                Err(e) => {
+                    let e: http::Error = e.into();
+                    log::warn!("skipping invalid header name: {}", e);
+                }
+            }
+        }
+        self
+    }
+
     /// Add a `Header` to this Request with ability to define if header_value is sensitive.
     fn header_sensitive<K, V>(mut self, key: K, value: V, sensitive: bool) -> RequestBuilder
     where
@@ -255,6 +377,188 @@ impl RequestBuilder {
         self.header_sensitive(crate::header::AUTHORIZATION, header_value, true)
     }
 
+    /// Record `ip` as a forwarding hop for this request.
+    ///
+    /// This is useful when the request is being forwarded on behalf of
+    /// another client. The address is appended to any `X-Forwarded-For`
+    /// header already set on the request, rather than replacing it, and a
+    /// matching `for=` element is appended to the `Forwarded` header.
+    ///
+    /// Set any incoming forwarding headers before calling this, so the
+    /// earlier hops are kept.
+    pub fn forwarded_for(mut self, ip: std::net::IpAddr) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            crate::util::append_forwarded_for(req.headers_mut(), ip);
+        }
+        self
+    }
+
+    /// Set the `Accept-Language` header of this request.
+    ///
+    /// The value is a comma-separated list of language tags with optional
+    /// weights, such as `"fr-CH, fr;q=0.9, en;q=0.8"`.
+    ///
+    /// # Errors
+    ///
+    /// The request will fail to build if `tags` is not a valid
+    /// `Accept-Language` value.
+    pub fn accept_language(mut self, tags: &str) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match HeaderValue::from_str(tags) {
+                Ok(value) if crate::util::is_valid_accept_language(tags) => {
+                    req.headers_mut().insert(ACCEPT_LANGUAGE, value);
+                }
+                _ => error = Some(crate::error::builder("invalid Accept-Language value")),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
+    /// Don't send an `Accept` header with this request.
+    ///
+    /// This removes any `Accept` header already set on the request, and
+    /// keeps the client from adding its default `Accept: */*`.
+    pub fn no_accept(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.suppress_accept();
+        }
+        self
+    }
+
+    /// Send a `Connection: close` header with this request.
+    ///
+    /// The server and the client both close an HTTP/1 connection after a
+    /// response to such a request, so it is never returned to the pool.
+    ///
+    /// A `Connection` header, whether set here or with `header`, is sent on
+    /// HTTP/1 connections as is. HTTP/2 forbids connection-specific headers,
+    /// so it is left out of HTTP/2 requests.
+    ///
+    /// A pooled connection may still be used to send the request itself; use
+    /// `ClientBuilder::pool_max_idle_per_host(0)` to never reuse connections
+    /// at all.
+    pub fn connection_close(self) -> RequestBuilder {
+        self.header(crate::header::CONNECTION, "close")
+    }
+
+    /// An alias of [`connection_close`](RequestBuilder::connection_close).
+    pub fn no_keepalive(self) -> RequestBuilder {
+        self.connection_close()
+    }
+
+    /// Override the client's proxy selection for this request.
+    ///
+    /// `Some(proxy)` sends the request through `proxy` if it matches the
+    /// request's URL, and `None` sends it directly, ignoring any proxies
+    /// configured on the `Client`.
+    ///
+    /// Requests overriding the proxies with equal ones share a connection
+    /// pool, separate from the one used with the client's proxies. Proxies
+    /// made with [`Proxy::custom`] are only equal to clones of themselves.
+    pub fn proxy(mut self, proxy: Option<Proxy>) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.set_proxy(proxy);
+        }
+        self
+    }
+
+    /// Decode the response body with `encoding`, whatever its headers say.
+    ///
+    /// The body is decompressed even without a matching `Content-Encoding`
+    /// header, and even if decompression is disabled on the `Client`. The
+    /// `Content-Encoding` and `Content-Length` headers are removed from the
+    /// response, as they would be for an automatically decoded body.
+    ///
+    /// # Optional
+    ///
+    /// This requires one of the optional `gzip`, `brotli` or `deflate`
+    /// features to be enabled.
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "deflate"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "gzip", feature = "brotli", feature = "deflate")))
+    )]
+    pub fn force_decode(mut self, encoding: Encoding) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.set_force_decode(encoding);
+        }
+        self
+    }
+
+    /// Send the request with `*` as its target, as in `OPTIONS * HTTP/1.1`.
+    ///
+    /// This asks about the server as a whole, rather than a resource on it.
+    /// The scheme, host and port of the URL are still used for connecting
+    /// and the `Host` header, while its path and query are ignored. Only
+    /// `OPTIONS` requests can be sent this way; sending any other method
+    /// fails with a builder error.
+    ///
+    /// A plain `http` request that an HTTP proxy would forward also fails
+    /// with a builder error, since a forwarded request must name its URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let res = reqwest::Client::new()
+    ///     .request(reqwest::Method::OPTIONS, "http://example.com")
+    ///     .asterisk_form()
+    ///     .send()
+    ///     .await?;
+    /// println!("allow: {:?}", res.headers().get("allow"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn asterisk_form(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.set_asterisk_form();
+        }
+        self
+    }
+
+    /// Compress the request body with Brotli.
+    ///
+    /// The body is sent with a `Content-Encoding: br` header. As its
+    /// compressed length isn't known up front, any `Content-Length` header
+    /// is removed and the body is streamed, using chunked encoding over
+    /// HTTP/1. Like any streamed body, it can't be sent again, so `307` and
+    /// `308` redirects aren't followed and the request isn't retried.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `brotli` feature to be enabled.
+    #[cfg(feature = "brotli")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "brotli")))]
+    pub fn brotli(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.set_brotli();
+        }
+        self
+    }
+
+    /// Send trailer headers after the request body.
+    ///
+    /// Trailers can only follow a streamed body, such as one made with
+    /// `Body::wrap_stream`; sending the request fails with a builder error
+    /// otherwise.
+    ///
+    /// Trailers are only sent over HTTP/2, since the HTTP/1 client can't
+    /// send them. The request fails with a builder error, before anything
+    /// is sent, unless it is sure to use HTTP/2: the client must use
+    /// `http2_prior_knowledge`, or the request must set its version to
+    /// `HTTP/2`. An `https` request that could fall back to HTTP/1 is
+    /// refused too.
+    pub fn trailers(mut self, trailers: HeaderMap) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.set_trailers(trailers);
+        }
+        self
+    }
+
     /// Set the request body.
     pub fn body<T: Into<Body>>(mut self, body: T) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
@@ -353,6 +657,44 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the query string of the URL as-is, without re-encoding it.
+    ///
+    /// This replaces any existing query string, including parameters added
+    /// with [`RequestBuilder::query`]. It's meant for passing along a query
+    /// string that is already encoded, such as one received from another
+    /// client, where `query` would encode the `%` escapes a second time.
+    /// A leading `?` is ignored, and an empty string removes the query.
+    ///
+    /// # Note
+    /// The caller is responsible for the encoding: percent escapes and
+    /// separators like `&` and `=` are sent exactly as given, so a value
+    /// containing them must already be escaped. Only characters that can
+    /// never appear in a query, such as spaces, are still percent-encoded.
+    ///
+    /// ```rust
+    /// # use reqwest::Error;
+    /// #
+    /// # async fn run() -> Result<(), Error> {
+    /// let res = reqwest::Client::new()
+    ///     .get("http://httpbin.org/get")
+    ///     .query_raw("redirect=%2Fhome%3Fa%3D1&x=%E2%9C%93")
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_raw(mut self, query: &str) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            let query = query.strip_prefix('?').unwrap_or(query);
+            if query.is_empty() {
+                req.url_mut().set_query(None);
+            } else {
+                req.url_mut().set_query(Some(query));
+            }
+        }
+        self
+    }
+
     /// Set HTTP version
     pub fn version(mut self, version: Version) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
@@ -361,6 +703,22 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the HTTP/2 priority of this request, as a best-effort hint.
+    ///
+    /// `weight` is the stream's share of the connection relative to its
+    /// siblings, and `dependency` is the ID of a stream it depends on.
+    ///
+    /// # Support
+    ///
+    /// This option currently has no effect. Priority only exists in HTTP/2,
+    /// so it is a no-op on HTTP/1.1, and the HTTP/2 stack used by the client
+    /// (hyper 0.14 and h2 0.3) has no way to send stream priority, so it is a
+    /// no-op on HTTP/2 as well.
+    pub fn priority(self, weight: u8, dependency: Option<u32>) -> RequestBuilder {
+        let _ = (weight, dependency);
+        self
+    }
+
     /// Send a form body.
     pub fn form<T: Serialize + ?Sized>(mut self, form: &T) -> RequestBuilder {
         let mut error = None;
@@ -382,6 +740,39 @@ impl RequestBuilder {
         self
     }
 
+    /// Send a form body, encoding nested values with bracket notation.
+    ///
+    /// Unlike `form`, which only accepts flat values, fields of nested
+    /// structs and maps are sent as `user[name]=x&user[age]=5`, and
+    /// sequences as `tags[]=a&tags[]=b`, as commonly understood by Rails
+    /// and PHP applications. `None` values are left out. The
+    /// `Content-Type: application/x-www-form-urlencoded` header is set as
+    /// well.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the passed value is not a struct or map, or
+    /// contains values that can't be form encoded, such as bytes.
+    pub fn form_nested<T: Serialize + ?Sized>(mut self, form: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match crate::nested_form::to_string(form) {
+                Ok(body) => {
+                    req.headers_mut().insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/x-www-form-urlencoded"),
+                    );
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
     /// Send a JSON body.
     ///
     /// # Optional
@@ -412,6 +803,74 @@ impl RequestBuilder {
         self
     }
 
+    /// Send a CBOR body.
+    ///
+    /// The `Content-Type` header is set to `application/cbor`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cbor` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `T`'s implementation of `Serialize` decides to
+    /// fail.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub fn cbor<T: Serialize>(mut self, cbor: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            let mut body = Vec::new();
+            match ciborium::ser::into_writer(cbor, &mut body) {
+                Ok(()) => {
+                    req.headers_mut()
+                        .insert(CONTENT_TYPE, HeaderValue::from_static("application/cbor"));
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
+    /// Send a MessagePack body.
+    ///
+    /// The `Content-Type` header is set to `application/msgpack`. Structs
+    /// are encoded as maps keyed by field name.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `msgpack` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `T`'s implementation of `Serialize` decides to
+    /// fail.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub fn msgpack<T: Serialize + ?Sized>(mut self, msgpack: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match rmp_serde::to_vec_named(msgpack) {
+                Ok(body) => {
+                    req.headers_mut().insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/msgpack"),
+                    );
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
     /// Disable CORS on fetching the request.
     ///
     /// # WASM
@@ -434,6 +893,12 @@ impl RequestBuilder {
     /// Constructs the Request and sends it to the target URL, returning a
     /// future Response.
     ///
+    /// The future resolves as soon as the response headers have been
+    /// received; the body is not read until it is asked for, such as with
+    /// [`Response::bytes`] or [`Response::chunk`]. Together with
+    /// [`Response::elapsed`], this can be used to measure time to headers
+    /// separately from the body download.
+    ///
     /// # Errors
     ///
     /// This method fails if there was an error while sending request,
@@ -566,6 +1031,13 @@ where
             body: Some(body.into()),
             timeout: None,
             version: version,
+            no_accept: false,
+            proxy: None,
+            force_decode: None,
+            trailers: None,
+            #[cfg(feature = "brotli")]
+            brotli: false,
+            asterisk_form: false,
         })
     }
 }
@@ -642,6 +1114,62 @@ mod tests {
         assert_eq!(req.url().query(), Some("foo=bar&qux=three"));
     }
 
+    #[test]
+    fn add_form_nested() {
+        #[derive(Serialize)]
+        struct User {
+            name: String,
+            age: u8,
+            tags: Vec<&'static str>,
+            nickname: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct Signup {
+            user: User,
+            plan: BTreeMap<&'static str, &'static str>,
+            terms: bool,
+        }
+
+        let mut plan = BTreeMap::new();
+        plan.insert("id", "pro plan");
+        let signup = Signup {
+            user: User {
+                name: "x".into(),
+                age: 5,
+                tags: vec!["a", "b"],
+                nickname: None,
+            },
+            plan,
+            terms: true,
+        };
+
+        let client = Client::new();
+        let req = client
+            .post("https://google.com/")
+            .form_nested(&signup)
+            .build()
+            .expect("request is valid");
+
+        assert_eq!(
+            req.headers()["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        let body = req.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(
+            body,
+            &b"user%5Bname%5D=x&user%5Bage%5D=5&user%5Btags%5D%5B%5D=a\
+              &user%5Btags%5D%5B%5D=b&plan%5Bid%5D=pro+plan&terms=true"[..]
+        );
+
+        let err = client
+            .post("https://google.com/")
+            .form_nested(&["not", "a", "struct"])
+            .build()
+            .unwrap_err();
+        assert!(err.is_builder());
+    }
+
     #[test]
     fn test_replace_headers() {
         use http::HeaderMap;
@@ -683,6 +1211,28 @@ mod tests {
         assert_eq!(req.url().as_str(), "https://google.com/");
     }
 
+    #[test]
+    fn accept_language_header() {
+        let client = Client::new();
+        let req = client
+            .get("https://hyper.rs")
+            .accept_language("fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5")
+            .build()
+            .expect("request build");
+
+        assert_eq!(
+            req.headers()["accept-language"],
+            "fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5"
+        );
+
+        let err = client
+            .get("https://hyper.rs")
+            .accept_language("en;q=2")
+            .build()
+            .unwrap_err();
+        assert!(err.is_builder());
+    }
+
     #[test]
     fn try_clone_reusable() {
         let client = Client::new();
@@ -814,6 +1364,29 @@ mod tests {
         assert_eq!(req.version(), Version::HTTP_11);
     }
 
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_body_bytes() {
+        let client = Client::new();
+        let json = serde_json::json!({"foo": "bar", "n": 1});
+        let req = client
+            .post("https://localhost/sign")
+            .json(&json)
+            .build()
+            .unwrap();
+
+        let bytes = req.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(bytes, &serde_json::to_vec(&json).unwrap()[..]);
+
+        let stream = futures_util::stream::iter(vec![Ok::<_, std::io::Error>("chunk")]);
+        let req = client
+            .post("https://localhost/sign")
+            .body(super::Body::stream(stream))
+            .build()
+            .unwrap();
+        assert!(req.body().unwrap().as_bytes().is_none());
+    }
+
     /*
     use {body, Method};
     use super::Client;
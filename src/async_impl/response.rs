@@ -1,15 +1,19 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::net::SocketAddr;
+#[cfg(feature = "fs")]
+use std::path::Path;
 use std::pin::Pin;
+use std::time::Duration;
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use encoding_rs::{Encoding, UTF_8};
 use futures_util::stream::StreamExt;
+use http::header::{AsHeaderName, HeaderValue, CONTENT_LOCATION, LOCATION};
 use hyper::client::connect::HttpInfo;
-use hyper::{HeaderMap, StatusCode, Version};
+use hyper::{HeaderMap, Method, StatusCode, Version};
 use mime::Mime;
-#[cfg(feature = "json")]
+#[cfg(any(feature = "json", feature = "cbor", feature = "msgpack"))]
 use serde::de::DeserializeOwned;
 #[cfg(feature = "json")]
 use serde_json;
@@ -17,10 +21,14 @@ use tokio::time::Sleep;
 use url::Url;
 
 use super::body::Body;
+use super::client::Client;
 use super::decoder::{Accepts, Decoder};
 #[cfg(feature = "cookies")]
 use crate::cookie;
 
+/// The most of a redirect's body that `follow` reads to reuse its connection.
+const MAX_FOLLOW_DRAIN: usize = 64 * 1024;
+
 /// A Response to a submitted `Request`.
 pub struct Response {
     status: StatusCode,
@@ -31,19 +39,52 @@ pub struct Response {
     body: Decoder,
     version: Version,
     extensions: http::Extensions,
+    redirect_count: usize,
+    redirects: Box<[(StatusCode, Url)]>,
+    redirect_versions: Box<[Version]>,
+    elapsed: Duration,
+    timings: Timings,
+    // The method of the request, to work out the next one in `follow`. It
+    // isn't known for a response converted from an `http::Response`.
+    method: Option<Method>,
+}
+
+/// What happened to the request a `Response` answers, on its way there.
+pub(super) struct RequestDetails {
+    pub(super) method: Method,
+    pub(super) redirect_count: usize,
+    pub(super) redirects: Vec<(StatusCode, Url, Version)>,
+    pub(super) elapsed: Duration,
+    pub(super) accepts: Accepts,
 }
 
 impl Response {
     pub(super) fn new(
         res: hyper::Response<hyper::Body>,
         url: Url,
-        accepts: Accepts,
+        details: RequestDetails,
         timeout: Option<Pin<Box<Sleep>>>,
     ) -> Response {
+        let RequestDetails {
+            method,
+            redirect_count,
+            redirects,
+            elapsed,
+            accepts,
+        } = details;
         let (parts, body) = res.into_parts();
         let status = parts.status;
         let version = parts.version;
-        let extensions = parts.extensions;
+        let mut extensions = parts.extensions;
+        let connection = extensions
+            .remove::<crate::connect::NewConnection>()
+            .and_then(|timings| timings.take())
+            .unwrap_or_default();
+
+        let (redirects, redirect_versions): (Vec<_>, Vec<_>) = redirects
+            .into_iter()
+            .map(|(status, url, version)| ((status, url), version))
+            .unzip();
 
         let mut headers = parts.headers;
         let decoder = Decoder::detect(&mut headers, Body::response(body, timeout), accepts);
@@ -55,15 +96,43 @@ impl Response {
             body: decoder,
             version,
             extensions,
+            redirect_count,
+            redirects: redirects.into_boxed_slice(),
+            redirect_versions: redirect_versions.into_boxed_slice(),
+            elapsed,
+            timings: Timings {
+                dns: connection.dns,
+                connect: connection.connect,
+                tls: connection.tls,
+                ttfb: None,
+            },
+            method: Some(method),
         }
     }
 
+    pub(super) fn set_time_to_first_byte(&mut self, ttfb: Duration) {
+        self.timings.ttfb = Some(ttfb);
+    }
+
     /// Get the `StatusCode` of this `Response`.
     #[inline]
     pub fn status(&self) -> StatusCode {
         self.status
     }
 
+    /// Get the reason phrase of this `Response`.
+    ///
+    /// For HTTP/1 responses, this is the reason phrase the server actually
+    /// sent in the status line, which may differ from the canonical one.
+    /// Otherwise, or if the sent phrase isn't valid UTF-8, this falls back
+    /// to the canonical reason for the status code, if there is one.
+    pub fn reason(&self) -> Option<&str> {
+        self.extensions
+            .get::<hyper::ext::ReasonPhrase>()
+            .and_then(|reason| std::str::from_utf8(reason.as_bytes()).ok())
+            .or_else(|| self.status.canonical_reason())
+    }
+
     /// Get the HTTP `Version` of this `Response`.
     #[inline]
     pub fn version(&self) -> Version {
@@ -82,6 +151,15 @@ impl Response {
         &mut self.headers
     }
 
+    /// Get all values of a header of this `Response`.
+    ///
+    /// Headers such as `Set-Cookie` can appear more than once; unlike
+    /// `headers().get(name)`, this returns every value, in the order they
+    /// were received. The result is empty if the header is missing.
+    pub fn header_all<K: AsHeaderName>(&self, name: K) -> Vec<&HeaderValue> {
+        self.headers.get_all(name).iter().collect()
+    }
+
     /// Get the content-length of this response, if known.
     ///
     /// Reasons it may not be known:
@@ -95,6 +173,49 @@ impl Response {
         HttpBody::size_hint(&self.body).exact()
     }
 
+    /// Get the parsed `Content-Type` of this response, if present.
+    ///
+    /// Returns `None` if the header is missing or isn't a valid media type.
+    /// Parameters such as `charset` are available on the returned `Mime`.
+    pub fn content_type(&self) -> Option<Mime> {
+        self.headers
+            .get(crate::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<Mime>().ok())
+    }
+
+    /// Check whether the `Content-Type` of this response is `essence`.
+    ///
+    /// Only the type and subtype are compared, such as `"text/plain"`, and
+    /// any parameters like `charset` are ignored. The comparison is
+    /// case-insensitive. Returns `false` if the header is missing or isn't
+    /// a valid media type.
+    pub fn content_type_is(&self, essence: &str) -> bool {
+        match self.content_type() {
+            Some(mime) => mime.essence_str().eq_ignore_ascii_case(essence),
+            None => false,
+        }
+    }
+
+    /// Check whether this response has a JSON `Content-Type`.
+    ///
+    /// This matches `application/json`, as well as types with a `+json`
+    /// suffix, such as `application/problem+json`.
+    pub fn is_json(&self) -> bool {
+        match self.content_type() {
+            Some(mime) => {
+                mime.type_() == mime::APPLICATION
+                    && (mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether this response has a `text/html` `Content-Type`.
+    pub fn is_html(&self) -> bool {
+        self.content_type_is("text/html")
+    }
+
     /// Retrieve the cookies contained in the response.
     ///
     /// Note that invalid 'Set-Cookie' headers will be ignored.
@@ -114,6 +235,199 @@ impl Response {
         &self.url
     }
 
+    /// Get the value of a query parameter of the final `Url` of this `Response`.
+    ///
+    /// If the parameter appears more than once, the first value is returned.
+    /// Use `url().query_pairs()` to see every value.
+    pub fn url_query_param(&self, name: &str) -> Option<String> {
+        self.url
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    /// Get the `Content-Location` of this `Response`, if any.
+    ///
+    /// A relative value is resolved against the final `Url` of the response.
+    /// Returns `None` if the header is missing or can't be parsed as a URL.
+    pub fn content_location(&self) -> Option<Url> {
+        let location = self.headers.get(CONTENT_LOCATION)?.to_str().ok()?;
+        self.url.join(location).ok()
+    }
+
+    /// Returns true if this is a redirect that wasn't followed.
+    ///
+    /// That is, the status is `3xx` and there is a `Location` header. The
+    /// client returns such a response when its redirect policy stops, such
+    /// as with `redirect::Policy::none()`.
+    pub fn is_redirect(&self) -> bool {
+        self.status.is_redirection() && self.headers.contains_key(LOCATION)
+    }
+
+    /// Get the `Location` of this `Response`, if any.
+    ///
+    /// A relative value is resolved against the final `Url` of the response.
+    /// Returns `None` if the header is missing or can't be parsed as a URL.
+    pub fn location(&self) -> Option<Url> {
+        let location = self.headers.get(LOCATION)?.to_str().ok()?;
+        self.url.join(location).ok()
+    }
+
+    /// Follow this redirect by a single hop, and get the response to it.
+    ///
+    /// This sends a request to the redirect's
+    /// [`location`](Response::location), as a `GET` after a `301`, `302` or
+    /// `303` (a `HEAD` stays a `HEAD`), and with the same method after a
+    /// `307` or `308`. Combined with `redirect::Policy::none()`, a redirect
+    /// chain can be stepped through one hop at a time.
+    ///
+    /// The request is built with `client` like any other, so it has the
+    /// client's default headers and cookies, but none of the headers of the
+    /// original request. Unlike a redirect the client follows itself, the
+    /// redirect policy isn't consulted and no `Referer` header is added.
+    ///
+    /// The rest of this response's body is read first, so that its
+    /// connection can be reused.
+    ///
+    /// # Errors
+    ///
+    /// Fails if this response isn't a redirect with a valid `Location`, or
+    /// if it's a `307` or `308` for a request other than `GET` or `HEAD`,
+    /// since its body would have to be sent again. A response converted
+    /// from an `http::Response` that didn't come from one of reqwest's
+    /// doesn't know its request's method, and can't be followed either.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::Client::builder()
+    ///     .redirect(reqwest::redirect::Policy::none())
+    ///     .build()?;
+    /// let mut res = client.get("http://httpbin.org/redirect/2").send().await?;
+    /// while res.is_redirect() {
+    ///     println!("{} redirects to {:?}", res.url(), res.location());
+    ///     res = res.follow(&client).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn follow(mut self, client: &Client) -> crate::Result<Response> {
+        let (method, url) = self.next_hop()?;
+        // A long body isn't worth reading to keep its connection.
+        let mut drained = 0;
+        while drained < MAX_FOLLOW_DRAIN {
+            match self.chunk().await {
+                Ok(Some(chunk)) => drained += chunk.len(),
+                _ => break,
+            }
+        }
+        drop(self);
+        client.request(method, url).send().await
+    }
+
+    /// Work out the method and URL of the request that follows this
+    /// redirect.
+    pub(crate) fn next_hop(&self) -> crate::Result<(Method, Url)> {
+        let not_followable = |reason: &str| {
+            Err(crate::error::redirect(
+                format!("{} response {}", self.status, reason),
+                (*self.url).clone(),
+            ))
+        };
+        let url = match self.location() {
+            Some(url) if self.status.is_redirection() => url,
+            _ => return not_followable("is not a redirect with a valid location"),
+        };
+        let previous = match self.method {
+            Some(ref method) => method,
+            None => {
+                return Err(crate::error::builder(
+                    "the method of the request for this response isn't known",
+                )
+                .with_url((*self.url).clone()))
+            }
+        };
+        let method = match self.status {
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER => {
+                if *previous == Method::HEAD {
+                    Method::HEAD
+                } else {
+                    Method::GET
+                }
+            }
+            StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => match *previous {
+                Method::GET | Method::HEAD => previous.clone(),
+                _ => return not_followable("would need the request body sent again"),
+            },
+            _ => return not_followable("can't be followed"),
+        };
+        Ok((method, url))
+    }
+
+    /// Get the number of redirects that were followed to reach this
+    /// `Response`.
+    ///
+    /// This is `0` if the request was answered directly. A redirect that
+    /// the policy declined to follow is not counted.
+    pub fn redirect_count(&self) -> usize {
+        self.redirect_count
+    }
+
+    /// Get the redirect responses that were followed to reach this
+    /// `Response`.
+    ///
+    /// Each entry is the status code of a redirect response and the `Url`
+    /// its `Location` pointed to, in the order they were followed. This is
+    /// only recorded when the client was built with
+    /// [`ClientBuilder::record_redirects`](crate::ClientBuilder::record_redirects),
+    /// and is empty otherwise.
+    pub fn redirect_responses(&self) -> &[(StatusCode, Url)] {
+        &self.redirects
+    }
+
+    /// Get the HTTP version of each redirect response that was followed.
+    ///
+    /// The entries match those of
+    /// [`redirect_responses`](Response::redirect_responses), so a hop that
+    /// switched between HTTP/1.1 and HTTP/2 shows up here, while
+    /// [`version`](Response::version) is that of the final response.
+    pub fn redirect_versions(&self) -> &[Version] {
+        &self.redirect_versions
+    }
+
+    /// Get the time it took to receive this `Response`.
+    ///
+    /// This is measured from when the request was dispatched until the
+    /// response headers were received, including any redirects that were
+    /// followed. Reading the body is not included, so this is the time to
+    /// headers; time the body separately if needed.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Get a breakdown of where the time to this `Response` went.
+    ///
+    /// The connection phases are only set on the first response over a
+    /// new connection, as a reused connection had nothing to set up.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let res = reqwest::get("https://hyper.rs").await?;
+    /// let timings = res.timings();
+    /// println!("dns: {:?}", timings.dns_lookup());
+    /// println!("connect: {:?}", timings.connect());
+    /// println!("tls: {:?}", timings.tls_handshake());
+    /// println!("ttfb: {:?}", timings.time_to_first_byte());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn timings(&self) -> Timings {
+        self.timings
+    }
+
     /// Get the remote address used to get this `Response`.
     pub fn remote_addr(&self) -> Option<SocketAddr> {
         self.extensions
@@ -121,6 +435,136 @@ impl Response {
             .map(|info| info.remote_addr())
     }
 
+    /// Parse the challenges in the `WWW-Authenticate` headers.
+    ///
+    /// A server may offer several challenges, in one header or across
+    /// several, and they are returned in the order they were sent. Returns
+    /// an empty `Vec` if there are none.
+    pub fn www_authenticate(&self) -> Vec<crate::AuthChallenge> {
+        crate::auth::challenges(&self.headers)
+    }
+
+    /// Returns whether the request was routed through a proxy.
+    ///
+    /// This is `true` when the connection used for this `Response` was made
+    /// through one of the client's proxies, whether a plain HTTP proxy, a
+    /// tunnel, or SOCKS. For a redirected request, this describes the
+    /// connection used for the final hop.
+    pub fn via_proxy(&self) -> bool {
+        self.extensions.get::<crate::connect::ViaProxy>().is_some()
+    }
+
+    /// Get the URL of the proxy the request was routed through.
+    ///
+    /// This is the proxy that was picked for the connection, whether it was
+    /// configured directly, chosen by a [`Proxy::custom`](crate::Proxy::custom)
+    /// function, or found in the environment. Any credentials are left out.
+    /// Returns `None` for direct connections.
+    pub fn proxy_url(&self) -> Option<&Url> {
+        self.extensions
+            .get::<crate::connect::ViaProxy>()
+            .map(|via| &via.0)
+    }
+
+    /// Get the certificates the server presented during the TLS handshake.
+    ///
+    /// The first certificate is the server's own, followed by any
+    /// intermediates it sent. With the native-tls backend, only the
+    /// server's own certificate is available. Returns `None` if the
+    /// response wasn't received over TLS.
+    ///
+    /// Use [`Certificate::to_der`] to compare them against pinned values.
+    ///
+    /// [`Certificate::to_der`]: crate::Certificate::to_der
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn peer_certificates(&self) -> Option<Vec<crate::Certificate>> {
+        self.extensions
+            .get::<crate::tls::PeerCertificates>()
+            .map(|certs| certs.0.clone())
+    }
+
+    /// Get the protocol selected with ALPN during the TLS handshake, such
+    /// as `b"h2"` or `b"http/1.1"`.
+    ///
+    /// Returns `None` if the response wasn't received over TLS, or no
+    /// protocol was negotiated. The `native-tls` backend only negotiates
+    /// ALPN with the optional `native-tls-alpn` feature.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        self.extensions
+            .get::<crate::tls::AlpnProtocol>()
+            .map(|alpn| alpn.0.clone())
+    }
+
+    /// Get whether the TLS handshake of the connection this response was
+    /// received on resumed an earlier session, with a session ticket or ID,
+    /// instead of doing a full handshake.
+    ///
+    /// This is the same for every response on a connection, so a response
+    /// on a reused connection reports how that connection was established.
+    /// Through a proxy, a full handshake with either the proxy or the
+    /// destination counts as not resumed.
+    ///
+    /// Returns `None` if the response wasn't received over TLS, or whether
+    /// the session was resumed is unknown. It's only known for the
+    /// `rustls-tls(-...)` backend, when the client didn't use a
+    /// preconfigured TLS config.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn tls_session_reused(&self) -> Option<bool> {
+        self.extensions
+            .get::<crate::connect::TlsSessionReused>()
+            .map(|reused| reused.0)
+    }
+
+    /// Returns whether the response body has been fully read.
+    ///
+    /// This becomes `true` once the end of the body has been reached, such
+    /// as after [`Response::chunk`] returns `None`. Wrappers that only
+    /// conditionally read a body can use this to decide whether the rest
+    /// needs draining before the connection can be reused.
+    pub fn is_body_consumed(&self) -> bool {
+        self.body.is_end_stream_reached()
+    }
+
     // body methods
 
     /// Get the full response text.
@@ -171,11 +615,7 @@ impl Response {
     /// # }
     /// ```
     pub async fn text_with_charset(self, default_encoding: &str) -> crate::Result<String> {
-        let content_type = self
-            .headers
-            .get(crate::header::CONTENT_TYPE)
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| value.parse::<Mime>().ok());
+        let content_type = self.content_type();
         let encoding_name = content_type
             .as_ref()
             .and_then(|mime| mime.get_param("charset").map(|charset| charset.as_str()))
@@ -244,6 +684,129 @@ impl Response {
         serde_json::from_slice(&full).map_err(crate::error::decode)
     }
 
+    /// Read the rest of the response body as a dynamic `serde_json::Value`.
+    ///
+    /// This is useful when the shape of the JSON isn't known ahead of time.
+    /// Unlike [`Response::json`], the `Response` is borrowed, so its headers
+    /// and status stay available afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::get("http://httpbin.org/ip").await?;
+    /// let value = res.json_value().await?;
+    ///
+    /// println!("origin: {}", value["origin"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid JSON.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn json_value(&mut self) -> crate::Result<serde_json::Value> {
+        let mut full = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            full.extend_from_slice(&chunk);
+        }
+
+        serde_json::from_slice(&full).map_err(crate::error::decode)
+    }
+
+    /// Read the rest of the response body as JSON, with a configured
+    /// deserializer.
+    ///
+    /// `deserialize` is given a `serde_json::Deserializer` over the body,
+    /// which can be tuned before deserializing from it, such as lifting the
+    /// recursion limit for deeply nested documents. Trailing data after the
+    /// JSON value is still an error. Like [`Response::json_value`], the
+    /// `Response` is borrowed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// use serde::Deserialize;
+    ///
+    /// let mut res = reqwest::get("http://httpbin.org/ip").await?;
+    /// let value = res
+    ///     .json_with(|de| serde_json::Value::deserialize(de))
+    ///     .await?;
+    /// # drop(value);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Lifting the recursion limit requires serde_json's `unbounded_depth`
+    /// feature, and calling `de.disable_recursion_limit()` in `deserialize`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid JSON, or
+    /// `deserialize` returns an error.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn json_with<T, F>(&mut self, deserialize: F) -> crate::Result<T>
+    where
+        F: FnOnce(
+            &mut serde_json::Deserializer<serde_json::de::SliceRead<'_>>,
+        ) -> serde_json::Result<T>,
+    {
+        let mut full = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            full.extend_from_slice(&chunk);
+        }
+
+        crate::util::json_with(&full, deserialize)
+    }
+
+    /// Try to deserialize the response body as CBOR.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cbor` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid CBOR
+    /// or it cannot be properly deserialized to target type `T`.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub async fn cbor<T: DeserializeOwned>(self) -> crate::Result<T> {
+        let full = self.bytes().await?;
+
+        ciborium::de::from_reader(&full[..]).map_err(crate::error::decode)
+    }
+
+    /// Try to deserialize the response body as MessagePack.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `msgpack` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid MessagePack
+    /// or it cannot be properly deserialized to target type `T`.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub async fn msgpack<T: DeserializeOwned>(self) -> crate::Result<T> {
+        let full = self.bytes().await?;
+
+        rmp_serde::from_slice(&full).map_err(crate::error::decode)
+    }
+
     /// Get the full response body as `Bytes`.
     ///
     /// # Example
@@ -263,10 +826,149 @@ impl Response {
         hyper::body::to_bytes(self.body).await
     }
 
+    /// Get as much of the response body as could be read, along with the
+    /// error that stopped reading, if any.
+    ///
+    /// Unlike [`Response::bytes`], which discards everything on error, this
+    /// keeps the bytes received before a failure. For a compressed body that
+    /// is truncated or corrupt partway through, these are the bytes decoded
+    /// before the decoder gave up, which can be used to recover a usable
+    /// prefix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (bytes, err) = reqwest::get("http://httpbin.org/gzip")
+    ///     .await?
+    ///     .bytes_partial()
+    ///     .await;
+    ///
+    /// if let Some(err) = err {
+    ///     println!("got {} bytes before failing: {}", bytes.len(), err);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bytes_partial(mut self) -> (Bytes, Option<crate::Error>) {
+        let mut buf = BytesMut::new();
+        loop {
+            match self.chunk().await {
+                Ok(Some(chunk)) => buf.extend_from_slice(&chunk),
+                Ok(None) => return (buf.freeze(), None),
+                Err(err) => return (buf.freeze(), Some(err)),
+            }
+        }
+    }
+
+    /// Get the full response body as `Bytes`, failing if it takes longer
+    /// than `timeout` to read.
+    ///
+    /// The timeout starts when this is called and covers only reading the
+    /// body, so a body that stalls after the headers arrived quickly can be
+    /// given up on without a large client-wide timeout. Any timeout set on
+    /// the client or request still applies as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    ///
+    /// let mut res = reqwest::get("http://httpbin.org/ip").await?;
+    /// let bytes = res.bytes_timeout(Duration::from_secs(5)).await?;
+    ///
+    /// println!("bytes: {:?}", bytes);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bytes_timeout(&mut self, timeout: Duration) -> crate::Result<Bytes> {
+        let read = async {
+            let mut buf = BytesMut::new();
+            while let Some(chunk) = self.chunk().await? {
+                buf.extend_from_slice(&chunk);
+            }
+            Ok(buf.freeze())
+        };
+        match tokio::time::timeout(timeout, read).await {
+            Ok(res) => res,
+            Err(_elapsed) => Err(crate::error::body(crate::error::TimedOut)),
+        }
+    }
+
+    /// Get the full response body as `Bytes`, failing if it is longer than
+    /// `max` bytes.
+    ///
+    /// Reading stops as soon as the limit is passed, so a large body is not
+    /// buffered in full just to be rejected. The error is a body error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::get("http://httpbin.org/ip").await?;
+    /// let bytes = res.bytes_limited(64 * 1024).await?;
+    ///
+    /// println!("bytes: {:?}", bytes);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bytes_limited(&mut self, max: usize) -> crate::Result<Bytes> {
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            if chunk.len() > max - buf.len() {
+                return Err(crate::error::body(format!(
+                    "response body is longer than {} bytes",
+                    max
+                )));
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(buf.freeze())
+    }
+
+    /// Get at most the first `max` bytes of the response body.
+    ///
+    /// Unlike [`Response::bytes_limited`], a longer body is not an error:
+    /// it is cut off at `max` bytes. This is useful for reading a preview of
+    /// a response.
+    ///
+    /// The body is read a chunk at a time, so the part of the last chunk
+    /// past `max` is discarded. Later calls to `chunk()` continue after
+    /// that chunk, not at byte `max`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::get("https://hyper.rs").await?;
+    /// let preview = res.bytes_truncated(512).await?;
+    ///
+    /// println!("preview: {:?}", preview);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bytes_truncated(&mut self, max: usize) -> crate::Result<Bytes> {
+        let mut buf = BytesMut::new();
+        while buf.len() < max {
+            match self.chunk().await? {
+                Some(chunk) => {
+                    let take = chunk.len().min(max - buf.len());
+                    buf.extend_from_slice(&chunk[..take]);
+                }
+                None => break,
+            }
+        }
+        Ok(buf.freeze())
+    }
+
     /// Stream a chunk of the response body.
     ///
     /// When the response body has been exhausted, this will return `None`.
     ///
+    /// If a compressed body fails to decode partway through, the chunks
+    /// decoded before the failure are returned first, followed by the error.
+    ///
     /// # Example
     ///
     /// ```
@@ -287,6 +989,96 @@ impl Response {
         }
     }
 
+    /// Copy the response body into a writer.
+    ///
+    /// The body is decoded as it is read, and each chunk is written to `w`
+    /// as soon as it arrives. On success, the number of bytes written is
+    /// returned, after flushing `w`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::get("http://httpbin.org/range/5").await?;
+    /// let mut buf: Vec<u8> = vec![];
+    /// res.copy_to(&mut buf).await?;
+    /// assert_eq!(buf, b"abcde");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_to<W>(&mut self, w: &mut W) -> crate::Result<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let mut written = 0;
+        while let Some(chunk) = self.chunk().await? {
+            w.write_all(&chunk).await.map_err(crate::error::body)?;
+            written += chunk.len() as u64;
+        }
+        w.flush().await.map_err(crate::error::body)?;
+        Ok(written)
+    }
+
+    /// Stream the response body into a newly created file at `path`.
+    ///
+    /// If a file already exists at `path`, it is truncated. On success, the
+    /// number of bytes written is returned. If reading the body or writing
+    /// the file fails, the partially written file is removed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let written = reqwest::get("https://hyper.rs")
+    ///     .await?
+    ///     .save_to("hyper.html")
+    ///     .await?;
+    ///
+    /// println!("saved {} bytes", written);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub async fn save_to<P: AsRef<Path>>(self, path: P) -> crate::Result<u64> {
+        self.save(path.as_ref(), false).await
+    }
+
+    /// Like `save_to`, but first creates any missing parent directories of
+    /// `path`.
+    ///
+    /// Directories that were created are kept even if saving fails.
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub async fn save_to_creating_dirs<P: AsRef<Path>>(self, path: P) -> crate::Result<u64> {
+        self.save(path.as_ref(), true).await
+    }
+
+    #[cfg(feature = "fs")]
+    async fn save(mut self, path: &Path, create_dirs: bool) -> crate::Result<u64> {
+        if create_dirs {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(crate::error::body)?;
+            }
+        }
+
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(crate::error::body)?;
+
+        let copied = self.copy_to(&mut file).await;
+
+        if copied.is_err() {
+            drop(file);
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        copied
+    }
+
     /// Convert the response into a `Stream` of `Bytes` from the body.
     ///
     /// # Example
@@ -315,6 +1107,76 @@ impl Response {
         self.body
     }
 
+    /// Convert the response into a `Stream` of `Bytes`, coalescing small
+    /// chunks of the body until at least `min_size` bytes are buffered.
+    ///
+    /// Chunks that already contain `min_size` bytes are passed through as-is.
+    /// Any remaining bytes are yielded as a final, smaller chunk once the
+    /// body ends.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut stream = reqwest::get("http://httpbin.org/ip")
+    ///     .await?
+    ///     .chunked(64 * 1024);
+    ///
+    /// while let Some(item) = stream.next().await {
+    ///     println!("Chunk of {} bytes", item?.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn chunked(
+        self,
+        min_size: usize,
+    ) -> impl futures_core::Stream<Item = crate::Result<Bytes>> {
+        super::decoder::Coalesce::new(self.body, min_size)
+    }
+
+    /// Convert the response into a `Stream` of Server-Sent Events.
+    ///
+    /// The body is parsed as `text/event-stream`, with events yielded as
+    /// soon as their terminating blank line arrives. Comments are skipped,
+    /// and an unterminated event at the end of the body is dropped. A line
+    /// longer than 1 MiB ends the stream with a decode error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut events = reqwest::get("http://example.com/events")
+    ///     .await?
+    ///     .event_stream();
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     let event = event?;
+    ///     println!("{}: {}", event.event(), event.data());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `stream` feature to be enabled.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn event_stream(self) -> impl futures_core::Stream<Item = crate::Result<super::SseEvent>> {
+        super::sse::events(self.body)
+    }
+
     // util methods
 
     /// Turn a response into an error if the server returned an error.
@@ -375,6 +1237,52 @@ impl Response {
         }
     }
 
+    /// Turn a response into a custom error if the server returned an error.
+    ///
+    /// Like [`Response::error_for_status`], but for a client or server error
+    /// status the body is read in full, and `f` builds the error from the
+    /// status, headers and body. This keeps the translation of an API's
+    /// error responses in one place.
+    ///
+    /// An error reading the body is converted with `From<reqwest::Error>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use reqwest::{Response, StatusCode};
+    /// enum ApiError {
+    ///     Status(StatusCode, String),
+    ///     Reqwest(reqwest::Error),
+    /// }
+    ///
+    /// impl From<reqwest::Error> for ApiError {
+    ///     fn from(err: reqwest::Error) -> ApiError {
+    ///         ApiError::Reqwest(err)
+    ///     }
+    /// }
+    ///
+    /// async fn on_response(res: Response) -> Result<Response, ApiError> {
+    ///     res.error_for_status_with(|status, _headers, body| {
+    ///         ApiError::Status(status, String::from_utf8_lossy(&body).into_owned())
+    ///     })
+    ///     .await
+    /// }
+    /// # fn main() {}
+    /// ```
+    pub async fn error_for_status_with<F, E>(mut self, f: F) -> Result<Self, E>
+    where
+        F: FnOnce(StatusCode, HeaderMap, Bytes) -> E,
+        E: From<crate::Error>,
+    {
+        if self.status.is_client_error() || self.status.is_server_error() {
+            let headers = std::mem::take(&mut self.headers);
+            let body = hyper::body::to_bytes(self.body).await?;
+            Err(f(self.status, headers, body))
+        } else {
+            Ok(self)
+        }
+    }
+
     // private
 
     // The Response's body is an implementation detail.
@@ -408,6 +1316,10 @@ impl<T: Into<Body>> From<http::Response<T>> for Response {
             .remove::<ResponseUrl>()
             .unwrap_or_else(|| ResponseUrl(Url::parse("http://no.url.provided.local").unwrap()));
         let url = url.0;
+        let method = parts
+            .extensions
+            .remove::<ResponseMethod>()
+            .map(|method| method.0);
         Response {
             status: parts.status,
             headers: parts.headers,
@@ -415,6 +1327,12 @@ impl<T: Into<Body>> From<http::Response<T>> for Response {
             body,
             version: parts.version,
             extensions: parts.extensions,
+            redirect_count: 0,
+            redirects: Box::new([]),
+            redirect_versions: Box::new([]),
+            elapsed: Duration::default(),
+            timings: Timings::default(),
+            method,
         }
     }
 }
@@ -426,9 +1344,74 @@ impl From<Response> for Body {
     }
 }
 
+/// A `Response` can be converted into an `http::Response`.
+///
+/// The status, version, headers, extensions and (already decompressed) body
+/// are moved over. The final `Url` and the method of the request are kept as
+/// extensions, so converting back with `Response::from` restores them.
+impl From<Response> for http::Response<Body> {
+    fn from(r: Response) -> http::Response<Body> {
+        let mut res = http::Response::new(Body::stream(r.body));
+        *res.status_mut() = r.status;
+        *res.version_mut() = r.version;
+        *res.headers_mut() = r.headers;
+        *res.extensions_mut() = r.extensions;
+        res.extensions_mut().insert(ResponseUrl(*r.url));
+        if let Some(method) = r.method {
+            res.extensions_mut().insert(ResponseMethod(method));
+        }
+        res
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct ResponseUrl(Url);
 
+#[derive(Debug, Clone, PartialEq)]
+struct ResponseMethod(Method);
+
+/// How long the phases leading up to a `Response` took.
+///
+/// A phase that didn't happen is `None`: there is no DNS lookup for an IP
+/// address, no TLS handshake for plain HTTP, and nothing to connect for a
+/// reused connection. When connecting through a proxy, the lookup and
+/// connect phases are those of reaching the proxy, and setting up a tunnel
+/// is counted in the TLS handshake.
+///
+/// See [`Response::timings`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    dns: Option<Duration>,
+    connect: Option<Duration>,
+    tls: Option<Duration>,
+    ttfb: Option<Duration>,
+}
+
+impl Timings {
+    /// Get the time spent resolving the host name.
+    pub fn dns_lookup(&self) -> Option<Duration> {
+        self.dns
+    }
+
+    /// Get the time spent opening the TCP connection, after the lookup.
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    /// Get the time spent on the TLS handshake, after connecting.
+    pub fn tls_handshake(&self) -> Option<Duration> {
+        self.tls
+    }
+
+    /// Get the time from sending the request until the response headers
+    /// arrived, including any connection setup.
+    ///
+    /// After redirects, this is for the final request only.
+    pub fn time_to_first_byte(&self) -> Option<Duration> {
+        self.ttfb
+    }
+}
+
 /// Extension trait for http::response::Builder objects
 ///
 /// Allows the user to add a `Url` to the http::Response
@@ -478,4 +1461,32 @@ mod tests {
         assert_eq!(response.status, 200);
         assert_eq!(response.url, Box::new(url));
     }
+
+    #[tokio::test]
+    async fn test_into_http_response_round_trip() {
+        let url = Url::parse("http://example.com/final").unwrap();
+        let response = Builder::new()
+            .status(201)
+            .version(http::Version::HTTP_2)
+            .header("x-custom", "value")
+            .url(url.clone())
+            .body("foo")
+            .unwrap();
+        let response = Response::from(response);
+
+        let http_response = http::Response::<super::Body>::from(response);
+        assert_eq!(http_response.status(), 201);
+        assert_eq!(http_response.version(), http::Version::HTTP_2);
+        assert_eq!(http_response.headers()["x-custom"], "value");
+        assert_eq!(
+            http_response.extensions().get::<ResponseUrl>(),
+            Some(&ResponseUrl(url.clone()))
+        );
+
+        let response = Response::from(http_response);
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.url(), &url);
+        assert_eq!(response.headers()["x-custom"], "value");
+        assert_eq!(response.text().await.unwrap(), "foo");
+    }
 }
@@ -0,0 +1,287 @@
+//! Parsing of `text/event-stream` bodies, as sent by Server-Sent Events
+//! endpoints.
+
+use std::mem;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_util::StreamExt;
+
+/// An event received from a Server-Sent Events stream.
+///
+/// See [`Response::event_stream`](crate::Response::event_stream).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SseEvent {
+    event: String,
+    data: String,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl SseEvent {
+    /// Get the type of the event.
+    ///
+    /// This is the value of its `event` field, or `"message"` if it had
+    /// none.
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// Get the data of the event.
+    ///
+    /// The values of several `data` fields are joined with newlines.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Get the last event ID.
+    ///
+    /// This is the value of the latest `id` field in the stream so far,
+    /// which need not be part of this event, or `None` if no ID was set.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Get the reconnection time the server asked for with a `retry` field
+    /// in this event, if any.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+}
+
+/// The longest line that is buffered, so that a stream that never ends its
+/// line can't use up memory.
+const MAX_LINE_LEN: usize = 1024 * 1024;
+
+/// Parse the chunks of `body` into events.
+pub(super) fn events<S>(body: S) -> impl Stream<Item = crate::Result<SseEvent>>
+where
+    S: Stream<Item = crate::Result<Bytes>>,
+{
+    let state = (Box::pin(body), Parser::default(), false);
+    // Boxed so that the stream is `Unpin`, and `StreamExt::next` can be used.
+    Box::pin(futures_util::stream::unfold(
+        state,
+        |(mut body, mut parser, mut done)| async move {
+            loop {
+                match parser.next_event() {
+                    Ok(Some(event)) => return Some((Ok(event), (body, parser, done))),
+                    Ok(None) => (),
+                    Err(err) => return Some((Err(err), (body, parser, true))),
+                }
+                if done {
+                    return None;
+                }
+                match body.next().await {
+                    Some(Ok(chunk)) => parser.feed(&chunk),
+                    Some(Err(err)) => return Some((Err(err), (body, parser, true))),
+                    None => {
+                        parser.eof = true;
+                        done = true;
+                    }
+                }
+            }
+        },
+    ))
+}
+
+#[derive(Default)]
+struct Parser {
+    buf: Vec<u8>,
+    /// How much of `buf` is known not to contain a line ending.
+    scanned: usize,
+    /// Whether the start of the stream, and any byte order mark, was seen.
+    started: bool,
+    eof: bool,
+    event: String,
+    data: String,
+    last_id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl Parser {
+    fn feed(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+        if !self.started && self.buf.len() >= 3 {
+            self.started = true;
+            if self.buf.starts_with(b"\xEF\xBB\xBF") {
+                self.buf.drain(..3);
+                self.scanned = 0;
+            }
+        }
+    }
+
+    /// Process complete lines until an event is dispatched.
+    ///
+    /// An event that isn't terminated by a blank line before the end of
+    /// the stream is dropped. A line longer than `MAX_LINE_LEN` is an
+    /// error.
+    fn next_event(&mut self) -> crate::Result<Option<SseEvent>> {
+        while let Some(line) = self.next_line() {
+            if line.is_empty() {
+                if let Some(event) = self.dispatch() {
+                    return Ok(Some(event));
+                }
+                continue;
+            }
+            let line = String::from_utf8_lossy(&line).into_owned();
+            self.field(&line);
+        }
+        if self.buf.len() > MAX_LINE_LEN {
+            // Dropped, so that the stream ends after the error.
+            self.buf = Vec::new();
+            self.scanned = 0;
+            return Err(crate::error::decode("event stream line too long"));
+        }
+        Ok(None)
+    }
+
+    /// Take the next line, ended by CRLF, LF or CR.
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        let pos = match self.buf[self.scanned..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+        {
+            Some(pos) => self.scanned + pos,
+            None => {
+                self.scanned = self.buf.len();
+                return None;
+            }
+        };
+        let len = if self.buf[pos] == b'\r' {
+            match self.buf.get(pos + 1) {
+                Some(b'\n') => 2,
+                Some(_) => 1,
+                // The next chunk may start with the LF of a CRLF.
+                None if !self.eof => {
+                    self.scanned = pos;
+                    return None;
+                }
+                None => 1,
+            }
+        } else {
+            1
+        };
+        let mut line = self.buf.drain(..pos + len).collect::<Vec<_>>();
+        line.truncate(pos);
+        self.scanned = 0;
+        Some(line)
+    }
+
+    fn field(&mut self, line: &str) {
+        if line.starts_with(':') {
+            // A comment.
+            return;
+        }
+        let (name, value) = match line.find(':') {
+            Some(colon) => {
+                let value = &line[colon + 1..];
+                (&line[..colon], value.strip_prefix(' ').unwrap_or(value))
+            }
+            None => (line, ""),
+        };
+        match name {
+            "event" => self.event = value.to_owned(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => {
+                self.last_id = if value.is_empty() {
+                    None
+                } else {
+                    Some(value.to_owned())
+                };
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = mem::take(&mut self.event);
+        let mut data = mem::take(&mut self.data);
+        let retry = self.retry.take();
+        if data.is_empty() {
+            return None;
+        }
+        data.pop();
+        Some(SseEvent {
+            event: if event.is_empty() {
+                "message".to_owned()
+            } else {
+                event
+            },
+            data,
+            id: self.last_id.clone(),
+            retry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&str]) -> Vec<SseEvent> {
+        let mut parser = Parser::default();
+        let mut events = Vec::new();
+        for chunk in chunks {
+            parser.feed(chunk.as_bytes());
+            events.extend(std::iter::from_fn(|| parser.next_event().unwrap()));
+        }
+        parser.eof = true;
+        events.extend(std::iter::from_fn(|| parser.next_event().unwrap()));
+        events
+    }
+
+    #[test]
+    fn fields_split_across_chunks() {
+        let events = parse(&[
+            "\u{feff}: a comment\nevent: gre",
+            "eting\ndata: hello\r",
+            "\ndata:world\r\nid: 1\nretry: 300\n\n",
+            "data: second\n\ndata: unterminated",
+        ]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event(), "greeting");
+        assert_eq!(events[0].data(), "hello\nworld");
+        assert_eq!(events[0].id(), Some("1"));
+        assert_eq!(events[0].retry(), Some(Duration::from_millis(300)));
+        assert_eq!(events[1].event(), "message");
+        assert_eq!(events[1].data(), "second");
+        assert_eq!(events[1].id(), Some("1"));
+        assert_eq!(events[1].retry(), None);
+    }
+
+    #[test]
+    fn events_without_data_are_skipped() {
+        let events = parse(&["event: empty\n\nid\n\ndata\n\n"]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data(), "");
+        assert_eq!(events[0].id(), None);
+    }
+
+    #[test]
+    fn unterminated_line_is_capped() {
+        let mut parser = Parser::default();
+        let chunk = vec![b'a'; 64 * 1024];
+        for _ in 0..MAX_LINE_LEN / chunk.len() {
+            parser.feed(&chunk);
+            assert!(parser.next_event().unwrap().is_none());
+        }
+        // Only the new part of the line was searched for its end.
+        assert_eq!(parser.scanned, MAX_LINE_LEN);
+
+        parser.feed(b"a");
+        assert!(parser.next_event().unwrap_err().is_decode());
+        assert!(parser.next_event().unwrap().is_none());
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use crate::header::{HeaderMap, WWW_AUTHENTICATE};
+
+/// A challenge from a `WWW-Authenticate` header.
+///
+/// A challenge names an authentication scheme, such as `Basic` or `Digest`,
+/// and carries either a list of `name=value` parameters or a single
+/// `token68` value.
+///
+/// Challenges are returned by
+/// [`Response::www_authenticate`](crate::Response::www_authenticate).
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuthChallenge {
+    scheme: String,
+    token68: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl AuthChallenge {
+    /// Get the authentication scheme, as sent by the server.
+    ///
+    /// Schemes are case-insensitive, so compare them with
+    /// `eq_ignore_ascii_case`.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Get the value of a parameter.
+    ///
+    /// The name is matched case-insensitively. Quoted values are returned
+    /// without their quotes and escapes.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| &**v)
+    }
+
+    /// Get all parameters, in the order they were sent.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(n, v)| (&**n, &**v))
+    }
+
+    /// Get the `token68` value, for schemes that send one instead of
+    /// parameters.
+    pub fn token68(&self) -> Option<&str> {
+        self.token68.as_deref()
+    }
+}
+
+impl fmt::Debug for AuthChallenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = f.debug_struct("AuthChallenge");
+        builder.field("scheme", &self.scheme);
+        if let Some(ref token68) = self.token68 {
+            builder.field("token68", token68);
+        }
+        builder.field("params", &self.params).finish()
+    }
+}
+
+/// Parses every challenge in the `WWW-Authenticate` headers.
+///
+/// Parsing of a header value stops at the first malformed part, keeping
+/// the challenges before it.
+pub(crate) fn challenges(headers: &HeaderMap) -> Vec<AuthChallenge> {
+    let mut challenges = Vec::new();
+    for value in headers.get_all(WWW_AUTHENTICATE) {
+        if let Ok(value) = value.to_str() {
+            parse(value, &mut challenges);
+        }
+    }
+    challenges
+}
+
+fn parse(s: &str, out: &mut Vec<AuthChallenge>) {
+    let mut p = Parser { s, pos: 0 };
+    loop {
+        p.skip_separators();
+        let scheme = p.token();
+        if scheme.is_empty() {
+            return;
+        }
+        let mut challenge = AuthChallenge {
+            scheme: scheme.to_owned(),
+            token68: None,
+            params: Vec::new(),
+        };
+        p.skip_ws();
+
+        // `Negotiate YIIG...==` carries a single token68 instead of params.
+        let start = p.pos;
+        let token68 = p.token68();
+        p.skip_ws();
+        if !token68.is_empty() && p.at_list_end() {
+            challenge.token68 = Some(token68.to_owned());
+            out.push(challenge);
+            continue;
+        }
+        p.pos = start;
+
+        loop {
+            let start = p.pos;
+            let name = p.token();
+            if name.is_empty() {
+                break;
+            }
+            p.skip_ws();
+            if p.peek() != Some(b'=') {
+                // This is the scheme of the next challenge.
+                p.pos = start;
+                break;
+            }
+            p.pos += 1;
+            p.skip_ws();
+            let value = if p.peek() == Some(b'"') {
+                p.quoted_string()
+            } else {
+                p.token().to_owned()
+            };
+            challenge.params.push((name.to_owned(), value));
+            p.skip_separators();
+        }
+        out.push(challenge);
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    fn at_list_end(&self) -> bool {
+        matches!(self.peek(), None | Some(b','))
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if !f(b) {
+                break;
+            }
+            self.pos += 1;
+        }
+        &self.s[start..self.pos]
+    }
+
+    fn skip_ws(&mut self) {
+        self.take_while(|b| b == b' ' || b == b'\t');
+    }
+
+    fn skip_separators(&mut self) {
+        self.take_while(|b| b == b' ' || b == b'\t' || b == b',');
+    }
+
+    fn token(&mut self) -> &'a str {
+        self.take_while(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+    }
+
+    fn token68(&mut self) -> &'a str {
+        let start = self.pos;
+        self.take_while(|b| b.is_ascii_alphanumeric() || b"-._~+/".contains(&b));
+        self.take_while(|b| b == b'=');
+        &self.s[start..self.pos]
+    }
+
+    fn quoted_string(&mut self) -> String {
+        // skip the opening quote
+        self.pos += 1;
+        let mut value = String::new();
+        let mut chars = self.s[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return value;
+                }
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+        // unterminated, take the rest
+        self.pos = self.s.len();
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderValue;
+
+    fn parse_str(s: &str) -> Vec<AuthChallenge> {
+        let mut headers = HeaderMap::new();
+        headers.insert(WWW_AUTHENTICATE, HeaderValue::from_str(s).unwrap());
+        challenges(&headers)
+    }
+
+    #[test]
+    fn multiple_challenges() {
+        let challenges = parse_str(
+            r#"Negotiate YIIGhgYGKwYB==, Basic realm="simple", Newauth realm="apps", type=1, title="Login to \"apps\"""#,
+        );
+        assert_eq!(challenges.len(), 3);
+
+        assert_eq!(challenges[0].scheme(), "Negotiate");
+        assert_eq!(challenges[0].token68(), Some("YIIGhgYGKwYB=="));
+
+        assert_eq!(challenges[1].scheme(), "Basic");
+        assert_eq!(challenges[1].param("REALM"), Some("simple"));
+
+        assert_eq!(challenges[2].scheme(), "Newauth");
+        assert_eq!(
+            challenges[2].params().collect::<Vec<_>>(),
+            vec![
+                ("realm", "apps"),
+                ("type", "1"),
+                ("title", "Login to \"apps\"")
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_scheme_and_malformed_tail() {
+        let challenges = parse_str("Bearer, Basic realm=\"x\" @garbage");
+        assert_eq!(challenges.len(), 2);
+        assert_eq!(challenges[0].scheme(), "Bearer");
+        assert_eq!(challenges[0].params().count(), 0);
+        assert_eq!(challenges[1].param("realm"), Some("x"));
+    }
+}
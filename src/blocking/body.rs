@@ -27,6 +27,13 @@ pub struct Body {
 impl Body {
     /// Instantiate a `Body` from a reader.
     ///
+    /// The reader is read on the thread sending the request, while it waits
+    /// for the response, and streamed as the request body using chunked
+    /// transfer encoding, since the length is not known up front. Nothing
+    /// is buffered beyond a single chunk, so this can be used to upload from
+    /// sources like stdin or a pipe. A read that blocks holds up that thread,
+    /// and the request's timeout isn't checked until it returns.
+    ///
     /// # Note
     ///
     /// While allowing for many types to be used, these bodies do not have
@@ -1,14 +1,15 @@
 #[cfg(any(feature = "native-tls", feature = "__rustls",))]
 use std::any::Any;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::future::Future;
 use std::net::IpAddr;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use http::header::HeaderValue;
+use http::header::{HeaderName, HeaderValue};
 use log::{error, trace};
 use tokio::sync::{mpsc, oneshot};
 
@@ -19,7 +20,7 @@ use super::wait;
 use crate::Certificate;
 #[cfg(any(feature = "native-tls", feature = "__rustls"))]
 use crate::Identity;
-use crate::{async_impl, header, redirect, IntoUrl, Method, Proxy};
+use crate::{async_impl, header, redirect, IntoUrl, Method, Proxy, StatusCode, Url};
 
 /// A `Client` to make Requests with.
 ///
@@ -66,6 +67,7 @@ pub struct Client {
 pub struct ClientBuilder {
     inner: async_impl::ClientBuilder,
     timeout: Timeout,
+    runtime: Option<tokio::runtime::Handle>,
 }
 
 impl Default for ClientBuilder {
@@ -82,6 +84,7 @@ impl ClientBuilder {
         ClientBuilder {
             inner: async_impl::ClientBuilder::new(),
             timeout: Timeout::default(),
+            runtime: None,
         }
     }
 
@@ -178,6 +181,61 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.default_headers(headers))
     }
 
+    /// Sets a header that is filled with a freshly generated value on every request.
+    ///
+    /// `generate` is called once per request, for example to add a unique
+    /// `X-Request-Id` for tracing. Requests that already set this header
+    /// keep their own value. The same value is sent again when following
+    /// redirects.
+    ///
+    /// If `generate` returns a value that isn't a valid header value, the
+    /// request fails with a builder error.
+    pub fn request_id_header<K, F>(self, name: K, generate: F) -> ClientBuilder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        self.with_inner(move |inner| inner.request_id_header(name, generate))
+    }
+
+    /// Sets a function that can modify every request just before it is sent.
+    ///
+    /// The function is called once per request, after the client's default
+    /// headers have been added, and can change the URL, method and headers,
+    /// for example to sign the request. It is given the request as the
+    /// underlying async [`Request`](crate::Request). Changes to the body are
+    /// not supported, and redirects that are followed don't call it again.
+    pub fn request_interceptor<F>(self, interceptor: F) -> ClientBuilder
+    where
+        F: Fn(&mut crate::Request) + Send + Sync + 'static,
+    {
+        self.with_inner(move |inner| inner.request_interceptor(interceptor))
+    }
+
+    /// Send each header name at most once.
+    ///
+    /// When enabled, a header given several values, for instance with both
+    /// `X-Foo` and `x-foo`, is sent only with the last one.
+    ///
+    /// Default is `false`.
+    pub fn normalize_headers(self, enable: bool) -> ClientBuilder {
+        self.with_inner(move |inner| inner.normalize_headers(enable))
+    }
+
+    /// Sets a `Content-Type` for requests that have a body but no
+    /// `Content-Type` header.
+    ///
+    /// Content types set on the request, by `RequestBuilder::json`,
+    /// `RequestBuilder::form` or an explicit header, are left as they are.
+    pub fn default_content_type<V>(self, value: V) -> ClientBuilder
+    where
+        V: TryInto<HeaderValue>,
+        V::Error: Into<http::Error>,
+    {
+        self.with_inner(move |inner| inner.default_content_type(value))
+    }
+
     /// Enable a persistent cookie store for the client.
     ///
     /// Cookies received in responses will be preserved and included in
@@ -194,6 +252,26 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.cookie_store(enable))
     }
 
+    /// Enable a persistent cookie store with relaxed matching for local hosts.
+    ///
+    /// This is like `cookie_store(true)`, except that for `localhost` and IP
+    /// address hosts, the `Domain` attribute of received cookies is ignored
+    /// (they are always stored for the host that sent them), and `Secure`
+    /// cookies are sent over plain HTTP as well. Other hosts use the usual
+    /// strict matching. This is mostly useful when testing against local
+    /// servers.
+    ///
+    /// Passing `false` disables the cookie store, like `cookie_store(false)`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn cookie_store_relaxed(self, enable: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.cookie_store_relaxed(enable))
+    }
+
     /// Set the persistent cookie store for the client.
     ///
     /// Cookies received in responses will be passed to this store, and
@@ -213,6 +291,25 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.cookie_provider(cookie_store))
     }
 
+    /// Call a function whenever a cookie is stored in or removed from the
+    /// cookie store.
+    ///
+    /// Cookies the store rejects aren't reported, and removals include
+    /// cookies that expired. A custom `CookieStore` only reports changes if
+    /// it implements `set_cookies_with_changes`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cookies` feature to be enabled.
+    #[cfg(feature = "cookies")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    pub fn on_cookie_change<F>(self, callback: F) -> ClientBuilder
+    where
+        F: Fn(&crate::cookie::CookieChange) + Send + Sync + 'static,
+    {
+        self.with_inner(move |inner| inner.on_cookie_change(callback))
+    }
+
     /// Enable auto gzip decompression by checking the `Content-Encoding` response header.
     ///
     /// If auto gzip decompresson is turned on:
@@ -235,6 +332,40 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.gzip(enable))
     }
 
+    /// Decompress responses labeled `Content-Type: application/x-gzip`.
+    ///
+    /// Some servers send gzip data with that content type instead of a
+    /// `Content-Encoding: gzip` header. When enabled, such response bodies
+    /// are automatically decompressed, and `Content-Length` is removed from
+    /// the headers. The `Content-Type` header is left untouched.
+    ///
+    /// Default is `false`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `gzip` feature to be enabled
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn decode_gzip_content_type(self, enable: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.decode_gzip_content_type(enable))
+    }
+
+    /// Don't use gzip for these media types.
+    ///
+    /// A response whose `Content-Type` matches one of `types` isn't gzip
+    /// decompressed, and a request whose `Accept` header only lists
+    /// matching types doesn't advertise gzip. A type is either exact, like
+    /// `image/png`, or names all subtypes, like `image/*`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `gzip` feature to be enabled
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn gzip_skip_content_types(self, types: &[&str]) -> ClientBuilder {
+        self.with_inner(|inner| inner.gzip_skip_content_types(types))
+    }
+
     /// Enable auto brotli decompression by checking the `Content-Encoding` response header.
     ///
     /// If auto brotli decompression is turned on:
@@ -306,6 +437,88 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.no_deflate())
     }
 
+    /// Controls whether enabled encodings are advertised in `Accept-Encoding`.
+    ///
+    /// When disabled, no `Accept-Encoding` header is added to requests, but
+    /// responses that arrive compressed anyway (for instance because a proxy
+    /// compressed them) are still decompressed, as long as the encoding is
+    /// enabled and `auto_decompress` is on.
+    ///
+    /// Methods like `gzip(bool)` set which encodings are enabled for both
+    /// advertising and decompression; this only affects advertising.
+    ///
+    /// Default is `true`.
+    pub fn accept_encoding(self, enable: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.accept_encoding(enable))
+    }
+
+    /// Controls whether responses in enabled encodings are decompressed.
+    ///
+    /// When disabled, response bodies are returned as they were received,
+    /// with `Content-Encoding` and `Content-Length` left in place, even
+    /// though the enabled encodings are still advertised in
+    /// `Accept-Encoding` unless `accept_encoding(false)` is also set.
+    ///
+    /// Methods like `gzip(bool)` set which encodings are enabled for both
+    /// advertising and decompression; this only affects decompression.
+    ///
+    /// Default is `true`.
+    pub fn auto_decompress(self, enable: bool) -> ClientBuilder {
+        self.with_inner(|inner| inner.auto_decompress(enable))
+    }
+
+    /// Send `Accept-Encoding: identity` with range requests.
+    ///
+    /// Some servers compress responses to range requests unless asked not
+    /// to. An `Accept-Encoding` header set on the request is kept as is.
+    ///
+    /// Default is `false`.
+    pub fn range_identity_encoding(self, enable: bool) -> ClientBuilder {
+        self.with_inner(move |inner| inner.range_identity_encoding(enable))
+    }
+
+    // Retry options
+
+    /// Retry idempotent requests whose response has one of these statuses.
+    ///
+    /// Such a request is sent again, up to `max_retries` times, and the
+    /// last response is returned once the retries are exhausted. Requests
+    /// with a method that isn't idempotent, or with a streaming body, are
+    /// never retried.
+    ///
+    /// Retries are sent after the delay asked for by the response's
+    /// `Retry-After` header, or after the delay set by `retry_backoff`
+    /// without one.
+    ///
+    /// Default is no statuses.
+    pub fn retry_on_status(self, statuses: &[StatusCode]) -> ClientBuilder {
+        self.with_inner(move |inner| inner.retry_on_status(statuses))
+    }
+
+    /// Set how many times a request is retried after failing to connect,
+    /// or after a status given to `retry_on_status`.
+    ///
+    /// Requests with a streaming body are never retried.
+    ///
+    /// Default is 0, which disables retries.
+    pub fn max_retries(self, max: usize) -> ClientBuilder {
+        self.with_inner(move |inner| inner.max_retries(max))
+    }
+
+    /// Set how long to wait before each retry of a request.
+    ///
+    /// The first retry waits `base`, and each one after it twice as long as
+    /// the one before, up to `max`, plus a random extra of up to half the
+    /// delay with `jitter`.
+    ///
+    /// A response's `Retry-After` header takes precedence over this delay.
+    ///
+    /// Default is a `base` of 100 milliseconds, a `max` of 10 seconds, and
+    /// no jitter.
+    pub fn retry_backoff(self, base: Duration, max: Duration, jitter: bool) -> ClientBuilder {
+        self.with_inner(move |inner| inner.retry_backoff(base, max, jitter))
+    }
+
     // Redirect options
 
     /// Set a `redirect::Policy` for this client.
@@ -315,6 +528,15 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.redirect(policy))
     }
 
+    /// Record the redirect responses that were followed.
+    ///
+    /// See [`Response::redirect_responses`](crate::blocking::Response::redirect_responses).
+    ///
+    /// Default is `false`.
+    pub fn record_redirects(self, enable: bool) -> ClientBuilder {
+        self.with_inner(move |inner| inner.record_redirects(enable))
+    }
+
     /// Enable or disable automatic setting of the `Referer` header.
     ///
     /// Default is `true`.
@@ -322,6 +544,14 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.referer(enable))
     }
 
+    /// Set the policy used to build the `Referer` header when following
+    /// redirects.
+    ///
+    /// Default is `RefererPolicy::NoReferrerWhenDowngrade`.
+    pub fn referer_policy(self, policy: redirect::RefererPolicy) -> ClientBuilder {
+        self.with_inner(|inner| inner.referer_policy(policy))
+    }
+
     // Proxy options
 
     /// Add a `Proxy` to the list of proxies the `Client` will use.
@@ -335,7 +565,14 @@ impl ClientBuilder {
 
     /// Clear all `Proxies`, so `Client` will use no proxy anymore.
     ///
-    /// This also disables the automatic usage of the "system" proxy.
+    /// This also disables the automatic usage of the "system" proxy, so
+    /// proxies configured through environment variables such as
+    /// `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` (or the Windows registry)
+    /// are ignored as well.
+    ///
+    /// Only proxies added before this call are cleared; calling `proxy()`
+    /// afterwards adds to the now empty list, which makes this useful to
+    /// reset a builder that was configured elsewhere.
     pub fn no_proxy(self) -> ClientBuilder {
         self.with_inner(move |inner| inner.no_proxy())
     }
@@ -344,7 +581,8 @@ impl ClientBuilder {
 
     /// Set a timeout for connect, read and write operations of a `Client`.
     ///
-    /// Default is 30 seconds.
+    /// Default is [`DEFAULT_TIMEOUT`](super::DEFAULT_TIMEOUT), 30 seconds,
+    /// while the async `Client` has no timeout by default.
     ///
     /// Pass `None` to disable timeout.
     pub fn timeout<T>(mut self, timeout: T) -> ClientBuilder
@@ -355,6 +593,22 @@ impl ClientBuilder {
         self
     }
 
+    /// Disable the timeout of a `Client`.
+    ///
+    /// This is the same as `timeout(None)`, and makes the `Client` behave
+    /// like the async `Client` does by default.
+    pub fn timeout_disabled(self) -> ClientBuilder {
+        self.timeout(None)
+    }
+
+    /// Set a timeout for the whole request, across all retries and
+    /// redirects, that also runs while waiting to send a retry.
+    ///
+    /// Default is no total timeout.
+    pub fn total_timeout(self, timeout: Duration) -> ClientBuilder {
+        self.with_inner(move |inner| inner.total_timeout(timeout))
+    }
+
     /// Set a timeout for only the connect phase of a `Client`.
     ///
     /// Default is `None`.
@@ -370,6 +624,18 @@ impl ClientBuilder {
         }
     }
 
+    /// Set how many of the resolved addresses of a host are tried when connecting.
+    ///
+    /// When a name resolves to several addresses, they are tried in order,
+    /// moving on to the next one if connecting fails. If every attempt
+    /// fails, the error from the last one is returned. A `connect_timeout`
+    /// is shared between the attempts.
+    ///
+    /// By default, all of them are tried. A value of `0` is treated as `1`.
+    pub fn connect_attempts(self, attempts: usize) -> ClientBuilder {
+        self.with_inner(|inner| inner.connect_attempts(attempts))
+    }
+
     /// Set whether connections should emit verbose logs.
     ///
     /// Enabling this option will emit [log][] messages at the `TRACE` level
@@ -399,11 +665,53 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.pool_max_idle_per_host(max))
     }
 
+    /// Sets the maximum number of simultaneous connections per host.
+    ///
+    /// The limit applies to each connection pool, and requests with a
+    /// per-request proxy use a pool of their own.
+    ///
+    /// Default is no limit.
+    pub fn max_connections_per_host(self, max: usize) -> ClientBuilder {
+        self.with_inner(move |inner| inner.max_connections_per_host(max))
+    }
+
     /// Send headers as title case instead of lowercase.
     pub fn http1_title_case_headers(self) -> ClientBuilder {
         self.with_inner(|inner| inner.http1_title_case_headers())
     }
 
+    /// Sets the exact size of the read buffer of HTTP/1 connections.
+    ///
+    /// A large fixed buffer reduces the number of reads for big transfers,
+    /// while a small one keeps the memory used by many connections down.
+    /// This unsets `http1_max_buf_size`.
+    pub fn http1_read_buf_exact_size(self, sz: usize) -> ClientBuilder {
+        self.with_inner(move |inner| inner.http1_read_buf_exact_size(sz))
+    }
+
+    /// Sets the maximum size of the read and write buffers of HTTP/1
+    /// connections.
+    ///
+    /// Default is hyper's, currently about 400kb. The minimum is 8192.
+    /// This unsets `http1_read_buf_exact_size`.
+    pub fn http1_max_buf_size(self, max: usize) -> ClientBuilder {
+        self.with_inner(move |inner| inner.http1_max_buf_size(max))
+    }
+
+    /// Set how long to wait for a `100 Continue` before sending the body of
+    /// a request with an `Expect: 100-continue` header.
+    ///
+    /// `None` sends the body right away.
+    ///
+    /// Default is 1 second.
+    pub fn expect_continue_timeout<D>(self, timeout: D) -> ClientBuilder
+    where
+        D: Into<Option<Duration>>,
+    {
+        let timeout = timeout.into();
+        self.with_inner(move |inner| inner.expect_continue_timeout(timeout))
+    }
+
     /// Only use HTTP/1.
     pub fn http1_only(self) -> ClientBuilder {
         self.with_inner(|inner| inner.http1_only())
@@ -416,6 +724,10 @@ impl ClientBuilder {
 
     /// Sets the `SETTINGS_INITIAL_WINDOW_SIZE` option for HTTP2 stream-level flow control.
     ///
+    /// Raising this can help large downloads over links with high latency,
+    /// where a small window limits how much data can be in flight. It has
+    /// no effect on HTTP/1.1 connections.
+    ///
     /// Default is currently 65,535 but may change internally to optimize for common uses.
     pub fn http2_initial_stream_window_size(self, sz: impl Into<Option<u32>>) -> ClientBuilder {
         self.with_inner(|inner| inner.http2_initial_stream_window_size(sz))
@@ -423,6 +735,10 @@ impl ClientBuilder {
 
     /// Sets the max connection-level flow control for HTTP2
     ///
+    /// This bounds the data in flight across all streams of a connection,
+    /// so it should be at least as large as `http2_initial_stream_window_size`.
+    /// It has no effect on HTTP/1.1 connections.
+    ///
     /// Default is currently 65,535 but may change internally to optimize for common uses.
     pub fn http2_initial_connection_window_size(self, sz: impl Into<Option<u32>>) -> ClientBuilder {
         self.with_inner(|inner| inner.http2_initial_connection_window_size(sz))
@@ -480,6 +796,22 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.tcp_keepalive(val))
     }
 
+    /// Set whether sockets have `SO_REUSEADDR` enabled.
+    ///
+    /// This lets a local address be bound again while earlier connections
+    /// from it are still in `TIME_WAIT`, which can help clients that open
+    /// many short-lived connections, particularly together with
+    /// [`ClientBuilder::local_address`].
+    ///
+    /// The exact meaning differs between platforms. On Windows,
+    /// `SO_REUSEADDR` also allows binding an address that is actively in
+    /// use, so it should be used with care there.
+    ///
+    /// Default is `false`.
+    pub fn reuse_address(self, enabled: bool) -> ClientBuilder {
+        self.with_inner(move |inner| inner.reuse_address(enabled))
+    }
+
     // TLS options
 
     /// Add a custom root certificate.
@@ -526,10 +858,42 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.add_root_certificate(cert))
     }
 
+    /// Pins the public key that servers must present for `https` connections.
+    ///
+    /// `sha256_base64` is the base64 encoded SHA-256 hash of the
+    /// `SubjectPublicKeyInfo` of the server's certificate, the same format
+    /// used by `curl --pinnedpubkey`. Connections to servers that don't
+    /// match any of the pins fail with a connect error.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `pinning` feature, and one of the
+    /// `default-tls`, `native-tls`, or `rustls-tls(-...)` features, to be
+    /// enabled.
+    #[cfg(all(feature = "__tls", feature = "pinning"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            feature = "pinning",
+            any(
+                feature = "default-tls",
+                feature = "native-tls",
+                feature = "rustls-tls"
+            )
+        )))
+    )]
+    pub fn add_pinned_public_key(self, sha256_base64: &str) -> ClientBuilder {
+        self.with_inner(move |inner| inner.add_pinned_public_key(sha256_base64))
+    }
+
     /// Controls the use of built-in system certificates during certificate validation.
     ///         
     /// Defaults to `true` -- built-in system certs will be used.
     ///
+    /// When `false`, the root store starts out empty, and only certificates
+    /// added with [`add_root_certificate`](ClientBuilder::add_root_certificate)
+    /// are trusted.
+    ///
     /// # Optional
     ///
     /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
@@ -547,6 +911,36 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.tls_built_in_root_certs(tls_built_in_root_certs))
     }
 
+    /// Set a timeout for only the TLS handshake of new connections.
+    ///
+    /// The timeout starts once the TCP connection (or proxy tunnel) is
+    /// established, so it catches servers and middleboxes that accept the
+    /// connection but then stall the handshake. When it elapses, the request
+    /// fails with an error for which [`Error::is_timeout`](crate::Error::is_timeout)
+    /// returns `true`, and whose message says the TLS handshake timed out.
+    ///
+    /// `connect_timeout`, if also set, still bounds the whole connect
+    /// phase, including the handshake.
+    ///
+    /// Default is `None`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn tls_handshake_timeout(self, timeout: Duration) -> ClientBuilder {
+        self.with_inner(move |inner| inner.tls_handshake_timeout(timeout))
+    }
+
     /// Sets the identity to be used for client certificate authentication.
     ///
     /// # Optional
@@ -559,6 +953,23 @@ impl ClientBuilder {
         self.with_inner(move |inner| inner.identity(identity))
     }
 
+    /// Sets the identity to be used for client certificate authentication
+    /// with a specific host.
+    ///
+    /// The host is matched exactly, ignoring ASCII case. Connections to
+    /// other hosts use the identity set with `identity`, if any.
+    ///
+    /// # Optional
+    ///
+    /// This only works with the rustls backend, and requires one of the
+    /// `rustls-tls(-...)` features to be enabled. If another backend is in
+    /// use, building the `Client` fails.
+    #[cfg(feature = "__rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls-tls")))]
+    pub fn identity_for_host(self, host: &str, identity: Identity) -> ClientBuilder {
+        self.with_inner(move |inner| inner.identity_for_host(host, identity))
+    }
+
     /// Controls the use of hostname verification.
     ///
     /// Defaults to `false`.
@@ -603,6 +1014,47 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.danger_accept_invalid_certs(accept_invalid_certs))
     }
 
+    /// Skip certificate validation for connections to `host`.
+    ///
+    /// Every other host is still validated fully. See the async
+    /// [`ClientBuilder::danger_accept_invalid_certs_for`](crate::ClientBuilder::danger_accept_invalid_certs_for)
+    /// for details.
+    ///
+    /// # Warning
+    ///
+    /// Anyone able to intercept the traffic to `host` can impersonate it.
+    /// Never use this for hosts reached over untrusted networks.
+    ///
+    /// # Optional
+    ///
+    /// This only works with the rustls backend, and requires one of the
+    /// `rustls-tls(-...)` features to be enabled.
+    #[cfg(feature = "__rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls-tls")))]
+    pub fn danger_accept_invalid_certs_for(self, host: &str) -> ClientBuilder {
+        self.with_inner(move |inner| inner.danger_accept_invalid_certs_for(host))
+    }
+
+    /// Verify server certificates with a custom function.
+    ///
+    /// The function alone decides whether the presented certificates are
+    /// trusted. See the async
+    /// [`ClientBuilder::custom_cert_verifier`](crate::ClientBuilder::custom_cert_verifier)
+    /// for details.
+    ///
+    /// # Optional
+    ///
+    /// This only works with the rustls backend, and requires one of the
+    /// `rustls-tls(-...)` features to be enabled.
+    #[cfg(feature = "__rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls-tls")))]
+    pub fn custom_cert_verifier<F>(self, verifier: F) -> ClientBuilder
+    where
+        F: Fn(&crate::CertificateChain) -> bool + Send + Sync + 'static,
+    {
+        self.with_inner(move |inner| inner.custom_cert_verifier(verifier))
+    }
+
     /// Force using the native TLS backend.
     ///
     /// Since multiple TLS backends can be optionally enabled, this option will
@@ -684,6 +1136,22 @@ impl ClientBuilder {
         self.with_inner(|inner| inner.https_only(enabled))
     }
 
+    // Runtime options
+
+    /// Drive requests on an existing Tokio runtime.
+    ///
+    /// By default, each `Client` spawns a dedicated thread running its own
+    /// runtime. When a handle is provided, requests are instead executed on
+    /// that runtime, which must have both the IO and time drivers enabled.
+    ///
+    /// The blocking methods of the `Client` must still not be called from
+    /// within an async context, including the worker threads of this runtime.
+    /// Once the runtime shuts down, requests fail with an error.
+    pub fn runtime(mut self, handle: tokio::runtime::Handle) -> ClientBuilder {
+        self.runtime = Some(handle);
+        self
+    }
+
     // private
 
     fn with_inner<F>(mut self, func: F) -> ClientBuilder
@@ -700,6 +1168,7 @@ impl From<async_impl::ClientBuilder> for ClientBuilder {
         Self {
             inner: builder,
             timeout: Timeout::default(),
+            runtime: None,
         }
     }
 }
@@ -734,6 +1203,30 @@ impl Client {
         ClientBuilder::new()
     }
 
+    /// Wraps an existing async `Client` in a blocking `Client`.
+    ///
+    /// Both clients share the same connection pool and configuration, so
+    /// connections (and TLS sessions) opened by one can be reused by the
+    /// other. Requests made through the blocking client are still subject
+    /// to the default blocking timeout,
+    /// [`DEFAULT_TIMEOUT`](super::DEFAULT_TIMEOUT).
+    ///
+    /// Connections opened by the async `Client` are driven by the runtime
+    /// they were opened on, so that runtime must keep running for them to
+    /// be reused here.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the internal runtime thread cannot be started.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if called from within an async runtime. See docs on
+    /// [`reqwest::blocking`][crate::blocking] for details.
+    pub fn from_async(client: crate::Client) -> crate::Result<Client> {
+        ClientHandle::from_async(client).map(|handle| Client { inner: handle })
+    }
+
     /// Convenience method to make a `GET` request to a URL.
     ///
     /// # Errors
@@ -801,6 +1294,31 @@ impl Client {
         RequestBuilder::new(self.clone(), req)
     }
 
+    /// Convenience method to make a `GET` request to an already parsed `Url`.
+    ///
+    /// Unlike [`Client::get`], this can't fail to parse the URL.
+    pub fn get_url(&self, url: Url) -> RequestBuilder {
+        self.request_url(Method::GET, url)
+    }
+
+    /// Convenience method to make a `POST` request to an already parsed `Url`.
+    ///
+    /// Unlike [`Client::post`], this can't fail to parse the URL.
+    pub fn post_url(&self, url: Url) -> RequestBuilder {
+        self.request_url(Method::POST, url)
+    }
+
+    /// Start building a `Request` with the `Method` and an already parsed
+    /// `Url`.
+    ///
+    /// Unlike [`Client::request`], this skips converting the URL, so the
+    /// returned `RequestBuilder` never carries a URL error. A URL that
+    /// can't be used for a request, such as one with an unsupported scheme,
+    /// is still rejected when the request is sent.
+    pub fn request_url(&self, method: Method, url: Url) -> RequestBuilder {
+        RequestBuilder::new(self.clone(), Ok(Request::new(method, url)))
+    }
+
     /// Executes a `Request`.
     ///
     /// A `Request` can be built manually with `Request::new()` or obtained
@@ -816,11 +1334,27 @@ impl Client {
     pub fn execute(&self, request: Request) -> crate::Result<Response> {
         self.inner.execute_request(request)
     }
+
+    /// Open a connection to the host of `url` ahead of time.
+    ///
+    /// This sends a `HEAD` request to `url` and reads the response, so the
+    /// connection is kept in the pool for the next request to that host.
+    /// See the async [`Client::warmup`](crate::Client::warmup).
+    ///
+    /// # Errors
+    ///
+    /// This method fails if `url` can't be parsed or the request couldn't
+    /// be sent.
+    pub fn warmup<U: IntoUrl>(&self, url: U) -> crate::Result<()> {
+        self.head(url).send()?.bytes()?;
+        Ok(())
+    }
 }
 
 impl fmt::Debug for Client {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Client")
+            .field("timeout", &self.inner.timeout.0)
             //.field("gzip", &self.inner.gzip)
             //.field("redirect_policy", &self.inner.redirect_policy)
             //.field("referer", &self.inner.referer)
@@ -840,7 +1374,7 @@ struct ClientHandle {
     inner: Arc<InnerClientHandle>,
 }
 
-type OneshotResponse = oneshot::Sender<crate::Result<async_impl::Response>>;
+type OneshotResponse = oneshot::Sender<thread::Result<crate::Result<async_impl::Response>>>;
 type ThreadSender = mpsc::UnboundedSender<(async_impl::Request, OneshotResponse)>;
 
 struct InnerClientHandle {
@@ -850,25 +1384,61 @@ struct InnerClientHandle {
 
 impl Drop for InnerClientHandle {
     fn drop(&mut self) {
-        let id = self
-            .thread
-            .as_ref()
-            .map(|h| h.thread().id())
-            .expect("thread not dropped yet");
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => {
+                // Running on a provided runtime, closing the channel
+                // is enough to stop the dispatch task.
+                self.tx.take();
+                return;
+            }
+        };
+        let id = thread.thread().id();
 
         trace!("closing runtime thread ({:?})", id);
         self.tx.take();
         trace!("signaled close for runtime thread ({:?})", id);
-        self.thread.take().map(|h| h.join());
+        let _ = thread.join();
         trace!("closed runtime thread ({:?})", id);
     }
 }
 
 impl ClientHandle {
     fn new(builder: ClientBuilder) -> crate::Result<ClientHandle> {
-        let timeout = builder.timeout;
-        let builder = builder.inner;
+        let inner = builder.inner;
+        ClientHandle::spawn(builder.timeout, builder.runtime, move || inner.build())
+    }
+
+    fn from_async(client: async_impl::Client) -> crate::Result<ClientHandle> {
+        ClientHandle::spawn(Timeout::default(), None, move || Ok(client))
+    }
+
+    fn spawn<F>(
+        timeout: Timeout,
+        runtime: Option<tokio::runtime::Handle>,
+        make_client: F,
+    ) -> crate::Result<ClientHandle>
+    where
+        F: FnOnce() -> crate::Result<async_impl::Client> + Send + 'static,
+    {
         let (tx, rx) = mpsc::unbounded_channel::<(async_impl::Request, OneshotResponse)>();
+
+        if let Some(handle) = runtime {
+            let client = {
+                let _enter = handle.enter();
+                make_client()?
+            };
+            handle.spawn(dispatch(client, rx));
+
+            return Ok(ClientHandle {
+                timeout,
+                inner: Arc::new(InnerClientHandle {
+                    tx: Some(tx),
+                    thread: None,
+                }),
+            });
+        }
+
         let (spawn_tx, spawn_rx) = oneshot::channel::<crate::Result<()>>();
         let handle = thread::Builder::new()
             .name("reqwest-internal-sync-runtime".into())
@@ -889,7 +1459,7 @@ impl ClientHandle {
                 };
 
                 let f = async move {
-                    let client = match builder.build() {
+                    let client = match make_client() {
                         Err(e) => {
                             if let Err(e) = spawn_tx.send(Err(e)) {
                                 error!("Failed to communicate client creation failure: {:?}", e);
@@ -903,14 +1473,7 @@ impl ClientHandle {
                         return;
                     }
 
-                    let mut rx = rx;
-
-                    while let Some((req, req_tx)) = rx.recv().await {
-                        let req_fut = client.execute(req);
-                        tokio::spawn(forward(req_fut, req_tx));
-                    }
-
-                    trace!("({:?}) Receiver is shutdown", thread::current().id());
+                    dispatch(client, rx).await;
                 };
 
                 trace!("({:?}) start runtime::block_on", thread::current().id());
@@ -945,28 +1508,47 @@ impl ClientHandle {
         let url = req.url().clone();
         let timeout = req.timeout().copied().or(self.timeout.0);
 
-        self.inner
+        // On a provided runtime, the dispatch task is dropped when the
+        // runtime shuts down. Our own runtime thread only stops by panicking.
+        let provided_runtime = self.inner.thread.is_none();
+        let dispatch_gone = move || {
+            if provided_runtime {
+                runtime_shut_down()
+            } else {
+                event_loop_panicked()
+            }
+        };
+
+        let sent = self
+            .inner
             .tx
             .as_ref()
             .expect("core thread exited early")
-            .send((req, tx))
-            .expect("core thread panicked");
+            .send((req, tx));
+        if sent.is_err() {
+            return Err(dispatch_gone().with_url(url));
+        }
 
-        let result: Result<crate::Result<async_impl::Response>, wait::Waited<crate::Error>> =
-            if let Some(body) = body {
-                let f = async move {
-                    body.send().await?;
-                    rx.await.map_err(|_canceled| event_loop_panicked())
-                };
-                wait::timeout(f, timeout)
-            } else {
-                let f = async move { rx.await.map_err(|_canceled| event_loop_panicked()) };
-                wait::timeout(f, timeout)
+        let result: Result<
+            thread::Result<crate::Result<async_impl::Response>>,
+            wait::Waited<crate::Error>,
+        > = if let Some(body) = body {
+            let f = async move {
+                body.send().await?;
+                rx.await.map_err(|_canceled| dispatch_gone())
             };
+            wait::timeout(f, timeout)
+        } else {
+            let f = async move { rx.await.map_err(|_canceled| dispatch_gone()) };
+            wait::timeout(f, timeout)
+        };
 
         match result {
-            Ok(Err(err)) => Err(err.with_url(url)),
-            Ok(Ok(res)) => Ok(Response::new(
+            // The request panicked on the core thread, such as in a request
+            // interceptor. Continue the panic here, with its original payload.
+            Ok(Err(panic)) => std::panic::resume_unwind(panic),
+            Ok(Ok(Err(err))) => Err(err.with_url(url)),
+            Ok(Ok(Ok(res))) => Ok(Response::new(
                 res,
                 self.timeout.0,
                 KeepCoreThreadAlive(Some(self.inner.clone())),
@@ -977,6 +1559,26 @@ impl ClientHandle {
     }
 }
 
+async fn dispatch(
+    client: async_impl::Client,
+    mut rx: mpsc::UnboundedReceiver<(async_impl::Request, OneshotResponse)>,
+) {
+    while let Some((req, req_tx)) = rx.recv().await {
+        // A panic while starting the request must not take down the core
+        // thread, which is shared by every request of the client.
+        match panic::catch_unwind(AssertUnwindSafe(|| client.execute(req))) {
+            Ok(req_fut) => {
+                tokio::spawn(forward(req_fut, req_tx));
+            }
+            Err(panic) => {
+                let _ = req_tx.send(Err(panic));
+            }
+        }
+    }
+
+    trace!("({:?}) Receiver is shutdown", thread::current().id());
+}
+
 async fn forward<F>(fut: F, mut tx: OneshotResponse)
 where
     F: Future<Output = crate::Result<async_impl::Response>>,
@@ -987,13 +1589,15 @@ where
 
     // "select" on the sender being canceled, and the future completing
     let res = futures_util::future::poll_fn(|cx| {
-        match fut.as_mut().poll(cx) {
-            Poll::Ready(val) => Poll::Ready(Some(val)),
-            Poll::Pending => {
+        match panic::catch_unwind(AssertUnwindSafe(|| fut.as_mut().poll(cx))) {
+            Ok(Poll::Ready(val)) => Poll::Ready(Some(Ok(val))),
+            Ok(Poll::Pending) => {
                 // check if the callback is canceled
                 futures_core::ready!(tx.poll_closed(cx));
                 Poll::Ready(None)
             }
+            // Hand the panic to the waiting caller, to be continued there.
+            Err(panic) => Poll::Ready(Some(Err(panic))),
         }
     })
     .await;
@@ -1009,8 +1613,7 @@ struct Timeout(Option<Duration>);
 
 impl Default for Timeout {
     fn default() -> Timeout {
-        // default mentioned in ClientBuilder::timeout() doc comment
-        Timeout(Some(Duration::from_secs(30)))
+        Timeout(Some(super::DEFAULT_TIMEOUT))
     }
 }
 
@@ -1032,3 +1635,7 @@ fn event_loop_panicked() -> ! {
     // is not normal, and should likely be propagated.
     panic!("event loop thread panicked");
 }
+
+fn runtime_shut_down() -> crate::Error {
+    crate::error::request("the runtime given to ClientBuilder::runtime has shut down")
+}
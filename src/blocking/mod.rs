@@ -77,6 +77,12 @@ pub use self::client::{Client, ClientBuilder};
 pub use self::request::{Request, RequestBuilder};
 pub use self::response::Response;
 
+/// The timeout of a blocking `Client`, unless one is set with
+/// [`ClientBuilder::timeout`].
+///
+/// The async `Client` has no timeout by default.
+pub const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// Shortcut method to quickly make a *blocking* `GET` request.
 ///
 /// **NOTE**: This function creates a new internal `Client` on each call,
@@ -45,9 +45,11 @@ use std::path::Path;
 use mime_guess::{self, Mime};
 
 use super::Body;
-use crate::async_impl::multipart::{FormParts, PartMetadata, PartProps};
+use crate::async_impl::multipart::{gen_boundary_with, FormParts, PartMetadata, PartProps};
 
 /// A multipart/form-data request.
+///
+/// Parts are always sent in the order they were added to the `Form`.
 pub struct Form {
     inner: FormParts<Part>,
 }
@@ -72,6 +74,20 @@ impl Form {
         }
     }
 
+    /// Creates a new Form without any content, generating its boundary
+    /// from `rng` instead of the default random source.
+    ///
+    /// `rng` is called four times for the 64 random bits of each of the
+    /// boundary's parts. A seeded generator gives a reproducible boundary.
+    pub fn new_with_rng<R>(rng: R) -> Form
+    where
+        R: FnMut() -> u64,
+    {
+        Form {
+            inner: FormParts::with_boundary(gen_boundary_with(rng)),
+        }
+    }
+
     /// Get the boundary that this form will use.
     #[inline]
     pub fn boundary(&self) -> &str {
@@ -154,6 +170,16 @@ impl Form {
         self.inner.compute_length()
     }
 
+    /// Attempts to clone the `Form`, to send it in more than one request.
+    ///
+    /// `None` is returned if any part is a reader or a file, since those
+    /// can only be read once.
+    pub fn try_clone(&self) -> Option<Form> {
+        self.inner
+            .try_clone(Part::try_clone)
+            .map(|inner| Form { inner })
+    }
+
     fn with_inner<F>(self, func: F) -> Self
     where
         F: FnOnce(FormParts<Part>) -> FormParts<Part>,
@@ -239,6 +265,13 @@ impl Part {
     }
 
     /// Tries to set the mime of this part.
+    ///
+    /// The mime is sent as the part's `Content-Type` header. Parts made with
+    /// `Part::bytes` or `Part::reader` don't have one unless it is set here.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `mime` can't be parsed as a mime type.
     pub fn mime_str(self, mime: &str) -> crate::Result<Part> {
         Ok(self.mime(mime.parse().map_err(crate::error::builder)?))
     }
@@ -256,6 +289,13 @@ impl Part {
         self.with_inner(move |inner| inner.file_name(filename))
     }
 
+    fn try_clone(&self) -> Option<Part> {
+        Some(Part {
+            meta: self.meta.clone(),
+            value: self.value.try_clone()?,
+        })
+    }
+
     fn with_inner<F>(self, func: F) -> Self
     where
         F: FnOnce(PartMetadata) -> PartMetadata,
@@ -13,8 +13,8 @@ use super::body::{self, Body};
 #[cfg(feature = "multipart")]
 use super::multipart;
 use super::Client;
-use crate::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
-use crate::{async_impl, Method, Url};
+use crate::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT_LANGUAGE, CONTENT_TYPE};
+use crate::{async_impl, Method, Proxy, Url};
 
 /// A request which can be executed with `Client::execute()`.
 pub struct Request {
@@ -129,6 +129,27 @@ impl Request {
             None
         };
         let mut req = Request::new(self.method().clone(), self.url().clone());
+        if self.inner.is_accept_suppressed() {
+            req.inner.suppress_accept();
+        }
+        if let Some(proxy) = self.inner.proxy_override() {
+            req.inner.set_proxy(proxy.clone());
+        }
+        #[cfg(any(feature = "gzip", feature = "brotli", feature = "deflate"))]
+        {
+            if let Some(encoding) = self.inner.forced_encoding() {
+                req.inner.set_force_decode(encoding);
+            }
+        }
+        if self.inner.is_asterisk_form() {
+            req.inner.set_asterisk_form();
+        }
+        #[cfg(feature = "brotli")]
+        {
+            if self.inner.is_brotli() {
+                req.inner.set_brotli();
+            }
+        }
         *req.headers_mut() = self.headers().clone();
         *req.version_mut() = self.version().clone();
         req.body = body;
@@ -191,6 +212,39 @@ impl RequestBuilder {
         self.header_sensitive(key, value, false)
     }
 
+    /// Add a `Header` to this Request, skipping it if it is invalid.
+    ///
+    /// Unlike [`header`](RequestBuilder::header), an invalid name or value
+    /// doesn't make the request fail to build. The header is left out and
+    /// a warning is logged instead, which is useful when headers come from
+    /// untrusted input and a best-effort request is preferred.
+    pub fn header_opt<K, V>(mut self, key: K, value: V) -> RequestBuilder
+    where
+        HeaderName: TryFrom<K>,
+        HeaderValue: TryFrom<V>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        if let Ok(ref mut req) = self.request {
+            match <HeaderName as TryFrom<K>>::try_from(key) {
+                Ok(key) => match <HeaderValue as TryFrom<V>>::try_from(value) {
+                    Ok(value) => {
+                        req.headers_mut().append(key, value);
+                    }
+                    Err(e) => {
+                        let e: http::Error = e.into();
+                        log::warn!("skipping invalid value for header {:?}: {}", key, e);
+                    }
+                },
+                Err(e) => {
+                    let e: http::Error = e.into();
+                    log::warn!("skipping invalid header name: {}", e);
+                }
+            }
+        }
+        self
+    }
+
     /// Add a `Header` to this Request with ability to define if header_value is sensitive.
     fn header_sensitive<K, V>(mut self, key: K, value: V, sensitive: bool) -> RequestBuilder
     where
@@ -293,6 +347,160 @@ impl RequestBuilder {
         self.header_sensitive(crate::header::AUTHORIZATION, &*header_value, true)
     }
 
+    /// Record `ip` as a forwarding hop for this request.
+    ///
+    /// This is useful when the request is being forwarded on behalf of
+    /// another client. The address is appended to any `X-Forwarded-For`
+    /// header already set on the request, rather than replacing it, and a
+    /// matching `for=` element is appended to the `Forwarded` header.
+    ///
+    /// Set any incoming forwarding headers before calling this, so the
+    /// earlier hops are kept.
+    pub fn forwarded_for(mut self, ip: std::net::IpAddr) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            crate::util::append_forwarded_for(req.headers_mut(), ip);
+        }
+        self
+    }
+
+    /// Set the `Accept-Language` header of this request.
+    ///
+    /// The value is a comma-separated list of language tags with optional
+    /// weights, such as `"fr-CH, fr;q=0.9, en;q=0.8"`.
+    ///
+    /// # Errors
+    ///
+    /// The request will fail to build if `tags` is not a valid
+    /// `Accept-Language` value.
+    pub fn accept_language(mut self, tags: &str) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match HeaderValue::from_str(tags) {
+                Ok(value) if crate::util::is_valid_accept_language(tags) => {
+                    req.headers_mut().insert(ACCEPT_LANGUAGE, value);
+                }
+                _ => error = Some(crate::error::builder("invalid Accept-Language value")),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
+    /// Don't send an `Accept` header with this request.
+    ///
+    /// This removes any `Accept` header already set on the request, and
+    /// keeps the client from adding its default `Accept: */*`.
+    pub fn no_accept(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.inner.suppress_accept();
+        }
+        self
+    }
+
+    /// Decode the response body with `encoding`, whatever its headers say.
+    ///
+    /// The body is decompressed even without a matching `Content-Encoding`
+    /// header, and even if decompression is disabled on the `Client`. The
+    /// `Content-Encoding` and `Content-Length` headers are removed from the
+    /// response, as they would be for an automatically decoded body.
+    ///
+    /// # Optional
+    ///
+    /// This requires one of the optional `gzip`, `brotli` or `deflate`
+    /// features to be enabled.
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "deflate"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "gzip", feature = "brotli", feature = "deflate")))
+    )]
+    pub fn force_decode(mut self, encoding: crate::Encoding) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.inner.set_force_decode(encoding);
+        }
+        self
+    }
+
+    /// Send the request with `*` as its target, as in `OPTIONS * HTTP/1.1`.
+    ///
+    /// The URL's path and query are ignored. Only `OPTIONS` requests can be
+    /// sent this way, and not through an HTTP proxy that forwards them.
+    pub fn asterisk_form(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.inner.set_asterisk_form();
+        }
+        self
+    }
+
+    /// Compress the request body with Brotli.
+    ///
+    /// The body is sent with a `Content-Encoding: br` header, streamed as
+    /// its compressed length isn't known up front.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `brotli` feature to be enabled.
+    #[cfg(feature = "brotli")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "brotli")))]
+    pub fn brotli(mut self) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.inner.set_brotli();
+        }
+        self
+    }
+
+    /// Send trailer headers after the request body.
+    ///
+    /// Trailers can only follow a body read from a reader, as made with
+    /// `Body::new` or `Body::sized`; sending the request fails otherwise.
+    /// They are only sent over HTTP/2, and the request fails with an error
+    /// unless the client uses `http2_prior_knowledge` or the request's
+    /// version is `HTTP/2`.
+    pub fn trailers(mut self, trailers: HeaderMap) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.inner.set_trailers(trailers);
+        }
+        self
+    }
+
+    /// Override the client's proxy selection for this request.
+    ///
+    /// `Some(proxy)` sends the request through `proxy` if it matches the
+    /// request's URL, and `None` sends it directly, ignoring any proxies
+    /// configured on the `Client`.
+    ///
+    /// Requests overriding the proxies with equal ones share a connection
+    /// pool, separate from the one used with the client's proxies. Proxies
+    /// made with [`Proxy::custom`] are only equal to clones of themselves.
+    pub fn proxy(mut self, proxy: Option<Proxy>) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            req.inner.set_proxy(proxy);
+        }
+        self
+    }
+
+    /// Send a `Connection: close` header with this request.
+    ///
+    /// The server and the client both close an HTTP/1 connection after a
+    /// response to such a request, so it is never returned to the pool.
+    ///
+    /// A `Connection` header, whether set here or with `header`, is sent on
+    /// HTTP/1 connections as is. HTTP/2 forbids connection-specific headers,
+    /// so it is left out of HTTP/2 requests.
+    ///
+    /// A pooled connection may still be used to send the request itself; use
+    /// `ClientBuilder::pool_max_idle_per_host(0)` to never reuse connections
+    /// at all.
+    pub fn connection_close(self) -> RequestBuilder {
+        self.header(crate::header::CONNECTION, "close")
+    }
+
+    /// An alias of [`connection_close`](RequestBuilder::connection_close).
+    pub fn no_keepalive(self) -> RequestBuilder {
+        self.connection_close()
+    }
+
     /// Set the request body.
     ///
     /// # Examples
@@ -407,6 +615,43 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the query string of the URL as-is, without re-encoding it.
+    ///
+    /// This replaces any existing query string, including parameters added
+    /// with [`RequestBuilder::query`]. It's meant for passing along a query
+    /// string that is already encoded, such as one received from another
+    /// client, where `query` would encode the `%` escapes a second time.
+    /// A leading `?` is ignored, and an empty string removes the query.
+    ///
+    /// # Note
+    /// The caller is responsible for the encoding: percent escapes and
+    /// separators like `&` and `=` are sent exactly as given, so a value
+    /// containing them must already be escaped. Only characters that can
+    /// never appear in a query, such as spaces, are still percent-encoded.
+    ///
+    /// ```rust
+    /// # use reqwest::Error;
+    /// #
+    /// # fn run() -> Result<(), Error> {
+    /// let res = reqwest::blocking::Client::new()
+    ///     .get("http://httpbin.org/get")
+    ///     .query_raw("redirect=%2Fhome%3Fa%3D1&x=%E2%9C%93")
+    ///     .send()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_raw(mut self, query: &str) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            let query = query.strip_prefix('?').unwrap_or(query);
+            if query.is_empty() {
+                req.url_mut().set_query(None);
+            } else {
+                req.url_mut().set_query(Some(query));
+            }
+        }
+        self
+    }
+
     /// Set HTTP version
     pub fn version(mut self, version: Version) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
@@ -415,6 +660,22 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the HTTP/2 priority of this request, as a best-effort hint.
+    ///
+    /// `weight` is the stream's share of the connection relative to its
+    /// siblings, and `dependency` is the ID of a stream it depends on.
+    ///
+    /// # Support
+    ///
+    /// This option currently has no effect. Priority only exists in HTTP/2,
+    /// so it is a no-op on HTTP/1.1, and the HTTP/2 stack used by the client
+    /// (hyper 0.14 and h2 0.3) has no way to send stream priority, so it is a
+    /// no-op on HTTP/2 as well.
+    pub fn priority(self, weight: u8, dependency: Option<u32>) -> RequestBuilder {
+        let _ = (weight, dependency);
+        self
+    }
+
     /// Send a form body.
     ///
     /// Sets the body to the url encoded serialization of the passed value,
@@ -461,6 +722,36 @@ impl RequestBuilder {
         self
     }
 
+    /// Send a form body, encoding nested values with bracket notation.
+    ///
+    /// Fields of nested structs and maps are sent as
+    /// `user[name]=x&user[age]=5`, and sequences as `tags[]=a&tags[]=b`.
+    /// See the async `RequestBuilder::form_nested` for details.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the passed value is not a struct or map, or
+    /// contains values that can't be form encoded.
+    pub fn form_nested<T: Serialize + ?Sized>(mut self, form: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match crate::nested_form::to_string(form) {
+                Ok(body) => {
+                    req.headers_mut().insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/x-www-form-urlencoded"),
+                    );
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
     /// Send a JSON body.
     ///
     /// Sets the body to the JSON serialization of the passed value, and
@@ -512,6 +803,74 @@ impl RequestBuilder {
         self
     }
 
+    /// Send a CBOR body.
+    ///
+    /// The `Content-Type` header is set to `application/cbor`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cbor` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `T`'s implementation of `Serialize` decides to
+    /// fail.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub fn cbor<T: Serialize>(mut self, cbor: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            let mut body = Vec::new();
+            match ciborium::ser::into_writer(cbor, &mut body) {
+                Ok(()) => {
+                    req.headers_mut()
+                        .insert(CONTENT_TYPE, HeaderValue::from_static("application/cbor"));
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
+    /// Send a MessagePack body.
+    ///
+    /// The `Content-Type` header is set to `application/msgpack`. Structs
+    /// are encoded as maps keyed by field name.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `msgpack` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `T`'s implementation of `Serialize` decides to
+    /// fail.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub fn msgpack<T: Serialize + ?Sized>(mut self, msgpack: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match rmp_serde::to_vec_named(msgpack) {
+                Ok(body) => {
+                    req.headers_mut().insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/msgpack"),
+                    );
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
     /// Sends a multipart/form-data body.
     ///
     /// ```
@@ -1,17 +1,21 @@
 use std::fmt;
+#[cfg(feature = "fs")]
+use std::fs;
 use std::io::{self, Read};
 use std::mem;
 use std::net::SocketAddr;
+#[cfg(feature = "fs")]
+use std::path::Path;
 use std::pin::Pin;
 use std::time::Duration;
 
 use bytes::Bytes;
 use http;
-use hyper::header::HeaderMap;
-#[cfg(feature = "json")]
+use hyper::header::{AsHeaderName, HeaderMap, HeaderValue};
+#[cfg(any(feature = "json", feature = "cbor", feature = "msgpack"))]
 use serde::de::DeserializeOwned;
 
-use super::client::KeepCoreThreadAlive;
+use super::client::{Client, KeepCoreThreadAlive};
 use super::wait;
 #[cfg(feature = "cookies")]
 use crate::cookie;
@@ -93,6 +97,16 @@ impl Response {
         self.inner.status()
     }
 
+    /// Get the reason phrase of this `Response`.
+    ///
+    /// For HTTP/1 responses, this is the reason phrase the server actually
+    /// sent in the status line, which may differ from the canonical one.
+    /// Otherwise, or if the sent phrase isn't valid UTF-8, this falls back
+    /// to the canonical reason for the status code, if there is one.
+    pub fn reason(&self) -> Option<&str> {
+        self.inner.reason()
+    }
+
     /// Get the `Headers` of this `Response`.
     ///
     /// # Example
@@ -128,6 +142,15 @@ impl Response {
         self.inner.headers_mut()
     }
 
+    /// Get all values of a header of this `Response`.
+    ///
+    /// Headers such as `Set-Cookie` can appear more than once; unlike
+    /// `headers().get(name)`, this returns every value, in the order they
+    /// were received. The result is empty if the header is missing.
+    pub fn header_all<K: AsHeaderName>(&self, name: K) -> Vec<&HeaderValue> {
+        self.inner.header_all(name)
+    }
+
     /// Retrieve the cookies contained in the response.
     ///
     /// Note that invalid 'Set-Cookie' headers will be ignored.
@@ -163,6 +186,126 @@ impl Response {
         self.inner.url()
     }
 
+    /// Get the value of a query parameter of the final `Url` of this `Response`.
+    ///
+    /// If the parameter appears more than once, the first value is returned.
+    /// Use `url().query_pairs()` to see every value.
+    pub fn url_query_param(&self, name: &str) -> Option<String> {
+        self.inner.url_query_param(name)
+    }
+
+    /// Get the `Content-Location` of this `Response`, if any.
+    ///
+    /// A relative value is resolved against the final `Url` of the response.
+    /// Returns `None` if the header is missing or can't be parsed as a URL.
+    pub fn content_location(&self) -> Option<Url> {
+        self.inner.content_location()
+    }
+
+    /// Returns true if this is a redirect that wasn't followed.
+    ///
+    /// That is, the status is `3xx` and there is a `Location` header. The
+    /// client returns such a response when its redirect policy stops, such
+    /// as with `redirect::Policy::none()`.
+    pub fn is_redirect(&self) -> bool {
+        self.inner.is_redirect()
+    }
+
+    /// Get the `Location` of this `Response`, if any.
+    ///
+    /// A relative value is resolved against the final `Url` of the response.
+    /// Returns `None` if the header is missing or can't be parsed as a URL.
+    pub fn location(&self) -> Option<Url> {
+        self.inner.location()
+    }
+
+    /// Follow this redirect by a single hop, and get the response to it.
+    ///
+    /// This sends a request to the redirect's
+    /// [`location`](Response::location), as a `GET` after a `301`, `302` or
+    /// `303` (a `HEAD` stays a `HEAD`), and with the same method after a
+    /// `307` or `308`. Combined with `redirect::Policy::none()`, a redirect
+    /// chain can be stepped through one hop at a time.
+    ///
+    /// The request is built with `client` like any other, so it has the
+    /// client's default headers and cookies, but none of the headers of the
+    /// original request. Unlike a redirect the client follows itself, the
+    /// redirect policy isn't consulted and no `Referer` header is added.
+    ///
+    /// The rest of this response's body is read first, so that its
+    /// connection can be reused.
+    ///
+    /// # Errors
+    ///
+    /// Fails if this response isn't a redirect with a valid `Location`, or
+    /// if it's a `307` or `308` for a request other than `GET` or `HEAD`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # fn run() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::blocking::Client::builder()
+    ///     .redirect(reqwest::redirect::Policy::none())
+    ///     .build()?;
+    /// let mut res = client.get("http://httpbin.org/redirect/2").send()?;
+    /// while res.is_redirect() {
+    ///     res = res.follow(&client)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn follow(mut self, client: &Client) -> crate::Result<Response> {
+        let (method, url) = self.inner.next_hop()?;
+        // A long body isn't worth reading to keep its connection.
+        let _ = io::copy(&mut (&mut self).take(64 * 1024), &mut io::sink());
+        drop(self);
+        client.request(method, url).send()
+    }
+
+    /// Get the number of redirects that were followed to reach this
+    /// `Response`.
+    ///
+    /// This is `0` if the request was answered directly. A redirect that
+    /// the policy declined to follow is not counted.
+    pub fn redirect_count(&self) -> usize {
+        self.inner.redirect_count()
+    }
+
+    /// Get the redirect responses that were followed to reach this
+    /// `Response`.
+    ///
+    /// Each entry is the status code of a redirect response and the `Url`
+    /// it pointed to. This is empty unless the client was built with
+    /// [`ClientBuilder::record_redirects`](crate::blocking::ClientBuilder::record_redirects).
+    pub fn redirect_responses(&self) -> &[(StatusCode, Url)] {
+        self.inner.redirect_responses()
+    }
+
+    /// Get the HTTP version of each redirect response that was followed.
+    ///
+    /// The entries match those of
+    /// [`redirect_responses`](Response::redirect_responses).
+    pub fn redirect_versions(&self) -> &[Version] {
+        self.inner.redirect_versions()
+    }
+
+    /// Get the time it took to receive this `Response`.
+    ///
+    /// This is measured from when the request was dispatched until the
+    /// response headers were received, including any redirects that were
+    /// followed. Reading the body is not included.
+    pub fn elapsed(&self) -> Duration {
+        self.inner.elapsed()
+    }
+
+    /// Get a breakdown of where the time to this `Response` went.
+    ///
+    /// The connection phases are only set on the first response over a
+    /// new connection.
+    pub fn timings(&self) -> crate::Timings {
+        self.inner.timings()
+    }
+
     /// Get the remote address used to get this `Response`.
     ///
     /// # Example
@@ -178,6 +321,101 @@ impl Response {
         self.inner.remote_addr()
     }
 
+    /// Parse the challenges in the `WWW-Authenticate` headers.
+    ///
+    /// Returns an empty `Vec` if there are none.
+    pub fn www_authenticate(&self) -> Vec<crate::AuthChallenge> {
+        self.inner.www_authenticate()
+    }
+
+    /// Returns whether the request was routed through a proxy.
+    ///
+    /// This is `true` when the connection used for this `Response` was made
+    /// through one of the client's proxies. For a redirected request, this
+    /// describes the connection used for the final hop.
+    pub fn via_proxy(&self) -> bool {
+        self.inner.via_proxy()
+    }
+
+    /// Get the URL of the proxy the request was routed through.
+    ///
+    /// Any credentials are left out. Returns `None` for direct connections.
+    pub fn proxy_url(&self) -> Option<&Url> {
+        self.inner.proxy_url()
+    }
+
+    /// Get the certificates the server presented during the TLS handshake.
+    ///
+    /// The first certificate is the server's own, followed by any
+    /// intermediates it sent. With the native-tls backend, only the
+    /// server's own certificate is available. Returns `None` if the
+    /// response wasn't received over TLS.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn peer_certificates(&self) -> Option<Vec<crate::Certificate>> {
+        self.inner.peer_certificates()
+    }
+
+    /// Get the protocol selected with ALPN during the TLS handshake, such
+    /// as `b"h2"` or `b"http/1.1"`.
+    ///
+    /// Returns `None` if the response wasn't received over TLS, or no
+    /// protocol was negotiated.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        self.inner.alpn_protocol()
+    }
+
+    /// Get whether the TLS handshake of the connection this response was
+    /// received on resumed an earlier session, instead of doing a full
+    /// handshake.
+    ///
+    /// Returns `None` if the response wasn't received over TLS, or whether
+    /// the session was resumed is unknown. It's only known for the
+    /// `rustls-tls(-...)` backend.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `default-tls`, `native-tls`, or `rustls-tls(-...)`
+    /// feature to be enabled.
+    #[cfg(feature = "__tls")]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "default-tls",
+            feature = "native-tls",
+            feature = "rustls-tls"
+        )))
+    )]
+    pub fn tls_session_reused(&self) -> Option<bool> {
+        self.inner.tls_session_reused()
+    }
+
     /// Get the content-length of the response, if it is known.
     ///
     /// Reasons it may not be known:
@@ -189,6 +427,34 @@ impl Response {
         self.inner.content_length()
     }
 
+    /// Get the parsed `Content-Type` of this response, if present.
+    ///
+    /// Returns `None` if the header is missing or isn't a valid media type.
+    pub fn content_type(&self) -> Option<mime::Mime> {
+        self.inner.content_type()
+    }
+
+    /// Check whether the `Content-Type` of this response is `essence`.
+    ///
+    /// Only the type and subtype are compared, and any parameters like
+    /// `charset` are ignored.
+    pub fn content_type_is(&self, essence: &str) -> bool {
+        self.inner.content_type_is(essence)
+    }
+
+    /// Check whether this response has a JSON `Content-Type`.
+    ///
+    /// This matches `application/json`, as well as types with a `+json`
+    /// suffix.
+    pub fn is_json(&self) -> bool {
+        self.inner.is_json()
+    }
+
+    /// Check whether this response has a `text/html` `Content-Type`.
+    pub fn is_html(&self) -> bool {
+        self.inner.is_html()
+    }
+
     /// Try and deserialize the response body as JSON using `serde`.
     ///
     /// # Optional
@@ -234,6 +500,108 @@ impl Response {
         })
     }
 
+    /// Read the rest of the response body as a dynamic `serde_json::Value`.
+    ///
+    /// This is useful when the shape of the JSON isn't known ahead of time.
+    /// The `Response` is borrowed, so its headers and status stay available
+    /// afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::blocking::get("http://httpbin.org/ip")?;
+    /// let value = res.json_value()?;
+    ///
+    /// println!("origin: {}", value["origin"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid JSON.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn json_value(&mut self) -> crate::Result<serde_json::Value> {
+        let mut full = Vec::new();
+        self.read_to_end(&mut full)
+            .map_err(crate::error::decode_io)?;
+
+        serde_json::from_slice(&full).map_err(crate::error::decode)
+    }
+
+    /// Read the rest of the response body as JSON, with a configured
+    /// deserializer.
+    ///
+    /// See the async
+    /// [`Response::json_with`](crate::Response::json_with) for details.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `json` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid JSON, or
+    /// `deserialize` returns an error.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn json_with<T, F>(&mut self, deserialize: F) -> crate::Result<T>
+    where
+        F: FnOnce(
+            &mut serde_json::Deserializer<serde_json::de::SliceRead<'_>>,
+        ) -> serde_json::Result<T>,
+    {
+        let mut full = Vec::new();
+        self.read_to_end(&mut full)
+            .map_err(crate::error::decode_io)?;
+
+        crate::util::json_with(&full, deserialize)
+    }
+
+    /// Try to deserialize the response body as CBOR.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cbor` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid CBOR
+    /// or it cannot be properly deserialized to target type `T`.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub fn cbor<T: DeserializeOwned>(self) -> crate::Result<T> {
+        wait::timeout(self.inner.cbor(), self.timeout).map_err(|e| match e {
+            wait::Waited::TimedOut(e) => crate::error::decode(e),
+            wait::Waited::Inner(e) => e,
+        })
+    }
+
+    /// Try to deserialize the response body as MessagePack.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `msgpack` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method fails whenever the response body is not valid MessagePack
+    /// or it cannot be properly deserialized to target type `T`.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub fn msgpack<T: DeserializeOwned>(self) -> crate::Result<T> {
+        wait::timeout(self.inner.msgpack(), self.timeout).map_err(|e| match e {
+            wait::Waited::TimedOut(e) => crate::error::decode(e),
+            wait::Waited::Inner(e) => e,
+        })
+    }
+
     /// Get the full response body as `Bytes`.
     ///
     /// # Example
@@ -253,6 +621,94 @@ impl Response {
         })
     }
 
+    /// Get the full response body as `Bytes`, failing if it is longer than
+    /// `max` bytes.
+    ///
+    /// Reading stops as soon as the limit is passed, so a large body is not
+    /// buffered in full just to be rejected. The error is a body error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::blocking::get("http://httpbin.org/ip")?;
+    /// let bytes = res.bytes_limited(64 * 1024)?;
+    ///
+    /// println!("bytes: {:?}", bytes);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bytes_limited(&mut self, max: usize) -> crate::Result<Bytes> {
+        let mut buf = Vec::new();
+        self.by_ref()
+            .take((max as u64).saturating_add(1))
+            .read_to_end(&mut buf)
+            .map_err(crate::error::decode_io)?;
+        if buf.len() > max {
+            return Err(crate::error::body(format!(
+                "response body is longer than {} bytes",
+                max
+            )));
+        }
+        Ok(buf.into())
+    }
+
+    /// Get at most the first `max` bytes of the response body.
+    ///
+    /// Unlike [`Response::bytes_limited`], a longer body is not an error:
+    /// it is cut off at `max` bytes, and the rest is left unread. This is
+    /// useful for reading a preview of a response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut res = reqwest::blocking::get("https://hyper.rs")?;
+    /// let preview = res.bytes_truncated(512)?;
+    ///
+    /// println!("preview: {:?}", preview);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bytes_truncated(&mut self, max: usize) -> crate::Result<Bytes> {
+        let mut buf = Vec::new();
+        self.by_ref()
+            .take(max as u64)
+            .read_to_end(&mut buf)
+            .map_err(crate::error::decode_io)?;
+        Ok(buf.into())
+    }
+
+    /// Get as much of the response body as could be read, along with the
+    /// error that stopped reading, if any.
+    ///
+    /// Unlike [`Response::bytes`], which discards everything on error, this
+    /// keeps the bytes received before a failure, such as the bytes decoded
+    /// from a truncated compressed body. If the read timeout elapses, the
+    /// bytes read so far are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (bytes, err) = reqwest::blocking::get("http://httpbin.org/gzip")?.bytes_partial();
+    ///
+    /// if let Some(err) = err {
+    ///     println!("got {} bytes before failing: {}", bytes.len(), err);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bytes_partial(self) -> (Bytes, Option<crate::Error>) {
+        let inner = self.inner;
+        let partial = async move { Ok::<_, crate::Error>(inner.bytes_partial().await) };
+        match wait::timeout(partial, self.timeout) {
+            Ok(partial) => partial,
+            Err(wait::Waited::TimedOut(e)) => (Bytes::new(), Some(crate::error::decode(e))),
+            Err(wait::Waited::Inner(e)) => (Bytes::new(), Some(e)),
+        }
+    }
+
     /// Get the response text.
     ///
     /// This method decodes the response body with BOM sniffing
@@ -329,6 +785,104 @@ impl Response {
         io::copy(self, w).map_err(crate::error::decode_io)
     }
 
+    /// Iterate over the response body in chunks.
+    ///
+    /// Each item is a chunk as it arrives from the connection, so the whole
+    /// body never has to be held in memory. This is the blocking
+    /// counterpart of the async `Response::bytes_stream`. The client's
+    /// timeout applies to waiting for each chunk.
+    ///
+    /// If part of the body was already read with the `Read` implementation,
+    /// the rest is returned in chunks of up to 8 KiB.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut resp = reqwest::blocking::get("http://httpbin.org/stream-bytes/4096")?;
+    /// let mut total = 0;
+    /// for chunk in resp.bytes_iter() {
+    ///     total += chunk?.len();
+    /// }
+    /// println!("read {} bytes", total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bytes_iter(&mut self) -> impl Iterator<Item = crate::Result<Bytes>> + '_ {
+        std::iter::from_fn(move || {
+            if self.body.is_some() {
+                let mut buf = vec![0; 8 * 1024];
+                return match self.read(&mut buf) {
+                    Ok(0) => None,
+                    Ok(n) => {
+                        buf.truncate(n);
+                        Some(Ok(Bytes::from(buf)))
+                    }
+                    Err(err) => Some(Err(crate::error::decode_io(err))),
+                };
+            }
+
+            match wait::timeout(self.inner.chunk(), self.timeout) {
+                Ok(chunk) => chunk.map(Ok),
+                Err(wait::Waited::TimedOut(e)) => Some(Err(crate::error::decode(e))),
+                Err(wait::Waited::Inner(e)) => Some(Err(e)),
+            }
+        })
+    }
+
+    /// Stream the response body into a newly created file at `path`.
+    ///
+    /// If a file already exists at `path`, it is truncated. On success, the
+    /// number of bytes written is returned. If reading the body or writing
+    /// the file fails, the partially written file is removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let written = reqwest::blocking::get("https://hyper.rs")?.save_to("hyper.html")?;
+    /// println!("saved {} bytes", written);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub fn save_to<P: AsRef<Path>>(self, path: P) -> crate::Result<u64> {
+        self.save(path.as_ref(), false)
+    }
+
+    /// Like `save_to`, but first creates any missing parent directories of
+    /// `path`.
+    ///
+    /// Directories that were created are kept even if saving fails.
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub fn save_to_creating_dirs<P: AsRef<Path>>(self, path: P) -> crate::Result<u64> {
+        self.save(path.as_ref(), true)
+    }
+
+    #[cfg(feature = "fs")]
+    fn save(mut self, path: &Path, create_dirs: bool) -> crate::Result<u64> {
+        use std::io::Write;
+
+        if create_dirs {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(crate::error::body)?;
+            }
+        }
+
+        let mut file = fs::File::create(path).map_err(crate::error::body)?;
+        let copied = io::copy(&mut self, &mut file)
+            .and_then(|written| file.flush().map(|()| written))
+            .map_err(crate::error::decode_io);
+
+        if copied.is_err() {
+            drop(file);
+            let _ = fs::remove_file(path);
+        }
+        copied
+    }
+
     /// Turn a response into an error if the server returned an error.
     ///
     /// # Example
@@ -360,6 +914,49 @@ impl Response {
         })
     }
 
+    /// Turn a response into a custom error if the server returned an error.
+    ///
+    /// For a client or server error status the body is read in full, and `f`
+    /// builds the error from the status, headers and body. An error reading
+    /// the body is converted with `From<reqwest::Error>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # extern crate reqwest;
+    /// #[derive(Debug)]
+    /// struct ApiError(String);
+    ///
+    /// impl From<reqwest::Error> for ApiError {
+    ///     fn from(err: reqwest::Error) -> ApiError {
+    ///         ApiError(err.to_string())
+    ///     }
+    /// }
+    ///
+    /// # fn run() -> Result<(), ApiError> {
+    /// let res = reqwest::blocking::get("http://httpbin.org/status/400")?
+    ///     .error_for_status_with(|status, _headers, body| {
+    ///         ApiError(format!("{}: {}", status, String::from_utf8_lossy(&body)))
+    ///     })?;
+    /// # Ok(())
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn error_for_status_with<F, E>(mut self, f: F) -> Result<Self, E>
+    where
+        F: FnOnce(StatusCode, HeaderMap, Bytes) -> E,
+        E: From<crate::Error>,
+    {
+        let status = self.status();
+        if status.is_client_error() || status.is_server_error() {
+            let headers = mem::take(self.headers_mut());
+            let body = self.bytes()?;
+            Err(f(status, headers, body))
+        } else {
+            Ok(self)
+        }
+    }
+
     /// Turn a reference to a response into an error if the server returned an error.
     ///
     /// # Example
@@ -30,25 +30,37 @@ use self::rustls_tls_conn::RustlsTlsConn;
 use crate::dns::TrustDnsResolver;
 use crate::error::BoxError;
 use crate::proxy::{Proxy, ProxyScheme};
+#[cfg(all(feature = "__tls", feature = "pinning"))]
+use crate::tls::{PeerCertificates, PinnedKeys};
+use crate::Url;
 
 #[derive(Clone)]
 pub(crate) enum HttpConnector {
-    Gai(hyper::client::HttpConnector),
-    GaiWithDnsOverrides(hyper::client::HttpConnector<DnsResolverWithOverrides<GaiResolver>>),
+    Gai(hyper::client::HttpConnector<TimedResolver<GaiResolver>>),
+    GaiWithDnsOverrides(
+        hyper::client::HttpConnector<DnsResolverWithOverrides<TimedResolver<GaiResolver>>>,
+    ),
     #[cfg(feature = "trust-dns")]
-    TrustDns(hyper::client::HttpConnector<TrustDnsResolver>),
+    TrustDns(hyper::client::HttpConnector<TimedResolver<TrustDnsResolver>>),
     #[cfg(feature = "trust-dns")]
-    TrustDnsWithOverrides(hyper::client::HttpConnector<DnsResolverWithOverrides<TrustDnsResolver>>),
+    TrustDnsWithOverrides(
+        hyper::client::HttpConnector<DnsResolverWithOverrides<TimedResolver<TrustDnsResolver>>>,
+    ),
 }
 
 impl HttpConnector {
     pub(crate) fn new_gai() -> Self {
-        Self::Gai(hyper::client::HttpConnector::new())
+        Self::Gai(hyper::client::HttpConnector::new_with_resolver(
+            TimedResolver(GaiResolver::new()),
+        ))
     }
 
-    pub(crate) fn new_gai_with_overrides(overrides: HashMap<String, SocketAddr>) -> Self {
-        let gai = hyper::client::connect::dns::GaiResolver::new();
-        let overridden_resolver = DnsResolverWithOverrides::new(gai, overrides);
+    pub(crate) fn new_gai_with_overrides(
+        overrides: HashMap<String, Vec<SocketAddr>>,
+        connect_attempts: Option<usize>,
+    ) -> Self {
+        let gai = TimedResolver(GaiResolver::new());
+        let overridden_resolver = DnsResolverWithOverrides::new(gai, overrides, connect_attempts);
         Self::GaiWithDnsOverrides(hyper::client::HttpConnector::new_with_resolver(
             overridden_resolver,
         ))
@@ -57,6 +69,7 @@ impl HttpConnector {
     #[cfg(feature = "trust-dns")]
     pub(crate) fn new_trust_dns() -> crate::Result<HttpConnector> {
         TrustDnsResolver::new()
+            .map(TimedResolver)
             .map(hyper::client::HttpConnector::new_with_resolver)
             .map(Self::TrustDns)
             .map_err(crate::error::builder)
@@ -64,10 +77,12 @@ impl HttpConnector {
 
     #[cfg(feature = "trust-dns")]
     pub(crate) fn new_trust_dns_with_overrides(
-        overrides: HashMap<String, SocketAddr>,
+        overrides: HashMap<String, Vec<SocketAddr>>,
+        connect_attempts: Option<usize>,
     ) -> crate::Result<HttpConnector> {
         TrustDnsResolver::new()
-            .map(|resolver| DnsResolverWithOverrides::new(resolver, overrides))
+            .map(TimedResolver)
+            .map(|resolver| DnsResolverWithOverrides::new(resolver, overrides, connect_attempts))
             .map(hyper::client::HttpConnector::new_with_resolver)
             .map(Self::TrustDnsWithOverrides)
             .map_err(crate::error::builder)
@@ -101,37 +116,62 @@ impl_http_connector! {
     fn set_keepalive(&mut self, dur: Option<Duration>);
 }
 
+impl HttpConnector {
+    fn set_reuse_address(&mut self, reuse_address: bool) {
+        match self {
+            Self::Gai(http) => {
+                http.set_reuse_address(reuse_address);
+            }
+            Self::GaiWithDnsOverrides(http) => {
+                http.set_reuse_address(reuse_address);
+            }
+            #[cfg(feature = "trust-dns")]
+            Self::TrustDns(http) => {
+                http.set_reuse_address(reuse_address);
+            }
+            #[cfg(feature = "trust-dns")]
+            Self::TrustDnsWithOverrides(http) => {
+                http.set_reuse_address(reuse_address);
+            }
+        }
+    }
+}
+
+type GaiConnector = hyper::client::HttpConnector<TimedResolver<GaiResolver>>;
+type GaiWithDnsOverridesConnector =
+    hyper::client::HttpConnector<DnsResolverWithOverrides<TimedResolver<GaiResolver>>>;
+
 impl Service<Uri> for HttpConnector {
     type Response = <hyper::client::HttpConnector as Service<Uri>>::Response;
     type Error = <hyper::client::HttpConnector as Service<Uri>>::Error;
     #[cfg(feature = "trust-dns")]
     type Future =
-        Either<
+        timing::Record<
             Either<
-                <hyper::client::HttpConnector as Service<Uri>>::Future,
-                <hyper::client::HttpConnector<DnsResolverWithOverrides<GaiResolver>> as Service<
-                    Uri,
-                >>::Future,
+                Either<
+                    <GaiConnector as Service<Uri>>::Future,
+                    <GaiWithDnsOverridesConnector as Service<Uri>>::Future,
+                >,
+                Either<
+                    <hyper::client::HttpConnector<TimedResolver<TrustDnsResolver>> as Service<
+                        Uri,
+                    >>::Future,
+                    <hyper::client::HttpConnector<
+                        DnsResolverWithOverrides<TimedResolver<TrustDnsResolver>>,
+                    > as Service<Uri>>::Future,
+                >,
             >,
-            Either<
-                    <hyper::client::HttpConnector<TrustDnsResolver> as Service<Uri>>::Future,
-                <hyper::client::HttpConnector<DnsResolverWithOverrides<TrustDnsResolver>> as Service<Uri>>::Future
-                 >
         >;
     #[cfg(not(feature = "trust-dns"))]
-    type Future =
+    type Future = timing::Record<
         Either<
             Either<
-                <hyper::client::HttpConnector as Service<Uri>>::Future,
-                <hyper::client::HttpConnector<DnsResolverWithOverrides<GaiResolver>> as Service<
-                    Uri,
-                >>::Future,
-            >,
-            Either<
-                <hyper::client::HttpConnector as Service<Uri>>::Future,
-                <hyper::client::HttpConnector as Service<Uri>>::Future,
+                <GaiConnector as Service<Uri>>::Future,
+                <GaiWithDnsOverridesConnector as Service<Uri>>::Future,
             >,
-        >;
+            Either<<GaiConnector as Service<Uri>>::Future, <GaiConnector as Service<Uri>>::Future>,
+        >,
+    >;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         match self {
@@ -145,7 +185,7 @@ impl Service<Uri> for HttpConnector {
     }
 
     fn call(&mut self, dst: Uri) -> Self::Future {
-        match self {
+        let connecting = match self {
             Self::Gai(resolver) => Either::Left(Either::Left(resolver.call(dst))),
             Self::GaiWithDnsOverrides(resolver) => Either::Left(Either::Right(resolver.call(dst))),
             #[cfg(feature = "trust-dns")]
@@ -154,7 +194,30 @@ impl Service<Uri> for HttpConnector {
             Self::TrustDnsWithOverrides(resolver) => {
                 Either::Right(Either::Right(resolver.call(dst)))
             }
-        }
+        };
+        timing::Record::new(connecting, timing::Phase::Connected)
+    }
+}
+
+/// Wraps a resolver to note when resolution finished, for the
+/// [`ConnectTimings`] of the connection being established.
+#[derive(Clone)]
+pub(crate) struct TimedResolver<Resolver>(Resolver);
+
+impl<Resolver> Service<Name> for TimedResolver<Resolver>
+where
+    Resolver: Service<Name>,
+{
+    type Response = Resolver::Response;
+    type Error = Resolver::Error;
+    type Future = timing::Record<Resolver::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        timing::Record::new(self.0.call(name), timing::Phase::Resolved)
     }
 }
 
@@ -164,10 +227,17 @@ pub(crate) struct Connector {
     proxies: Arc<Vec<Proxy>>,
     verbose: verbose::Wrapper,
     timeout: Option<Duration>,
+    limit: Option<limit::PerHost>,
+    provided: Option<provided::Provided>,
+    watches_continue: bool,
     #[cfg(feature = "__tls")]
     nodelay: bool,
     #[cfg(feature = "__tls")]
     user_agent: Option<HeaderValue>,
+    #[cfg(all(feature = "__tls", feature = "pinning"))]
+    pinned_keys: Option<Arc<PinnedKeys>>,
+    #[cfg(feature = "__tls")]
+    tls_handshake_timeout: Option<Duration>,
 }
 
 #[derive(Clone)]
@@ -181,9 +251,31 @@ enum Inner {
         http: HttpConnector,
         tls: Arc<rustls::ClientConfig>,
         tls_proxy: Arc<rustls::ClientConfig>,
+        hosts: Arc<HashMap<String, RustlsConfigs>>,
+        /// Whether the configs verify certificates with a
+        /// `VerificationRecorder`, so that resumed sessions can be told apart.
+        records_verification: bool,
     },
 }
 
+/// The `tls` and `tls_proxy` configs used for a destination host.
+#[cfg(feature = "__rustls")]
+type RustlsConfigs = (Arc<rustls::ClientConfig>, Arc<rustls::ClientConfig>);
+
+/// Without proxies, both configs are the same. With proxies, ALPN is
+/// disabled in the config used through them.
+#[cfg(feature = "__rustls")]
+fn rustls_configs(tls: rustls::ClientConfig, proxies: &[Proxy]) -> RustlsConfigs {
+    if proxies.is_empty() {
+        let tls = Arc::new(tls);
+        (tls.clone(), tls)
+    } else {
+        let mut tls_proxy = tls.clone();
+        tls_proxy.alpn_protocols.clear();
+        (Arc::new(tls), Arc::new(tls_proxy))
+    }
+}
+
 impl Connector {
     #[cfg(not(feature = "__tls"))]
     pub(crate) fn new<T>(
@@ -202,6 +294,9 @@ impl Connector {
             verbose: verbose::OFF,
             proxies,
             timeout: None,
+            limit: None,
+            provided: None,
+            watches_continue: false,
         }
     }
 
@@ -217,7 +312,7 @@ impl Connector {
     where
         T: Into<Option<IpAddr>>,
     {
-        let tls = tls.build().map_err(crate::error::builder)?;
+        let tls = tls.build().map_err(crate::error::tls_backend)?;
         Ok(Self::from_built_default_tls(
             http, tls, proxies, user_agent, local_addr, nodelay,
         ))
@@ -243,8 +338,14 @@ impl Connector {
             proxies,
             verbose: verbose::OFF,
             timeout: None,
+            limit: None,
+            provided: None,
+            watches_continue: false,
             nodelay,
             user_agent,
+            #[cfg(feature = "pinning")]
+            pinned_keys: None,
+            tls_handshake_timeout: None,
         }
     }
 
@@ -263,26 +364,27 @@ impl Connector {
         http.set_local_address(local_addr.into());
         http.enforce_http(false);
 
-        let (tls, tls_proxy) = if proxies.is_empty() {
-            let tls = Arc::new(tls);
-            (tls.clone(), tls)
-        } else {
-            let mut tls_proxy = tls.clone();
-            tls_proxy.alpn_protocols.clear();
-            (Arc::new(tls), Arc::new(tls_proxy))
-        };
+        let (tls, tls_proxy) = rustls_configs(tls, &proxies);
 
         Connector {
             inner: Inner::RustlsTls {
                 http,
                 tls,
                 tls_proxy,
+                hosts: Arc::new(HashMap::new()),
+                records_verification: false,
             },
             proxies,
             verbose: verbose::OFF,
             timeout: None,
+            limit: None,
+            provided: None,
+            watches_continue: false,
             nodelay,
             user_agent,
+            #[cfg(feature = "pinning")]
+            pinned_keys: None,
+            tls_handshake_timeout: None,
         }
     }
 
@@ -294,6 +396,104 @@ impl Connector {
         self.verbose.0 = enabled;
     }
 
+    pub(crate) fn set_max_connections_per_host(&mut self, max: Option<usize>) {
+        self.limit = max.map(limit::PerHost::new);
+    }
+
+    /// Gives this connector its own per-host slots, for a pool of its own,
+    /// so that connections idle in another pool don't hold them.
+    pub(crate) fn separate_limit(&mut self) {
+        self.limit = self.limit.as_ref().map(limit::PerHost::separate);
+    }
+
+    #[cfg(all(feature = "__tls", feature = "pinning"))]
+    pub(crate) fn set_pinned_keys(&mut self, pins: PinnedKeys) {
+        self.pinned_keys = if pins.is_empty() {
+            None
+        } else {
+            Some(Arc::new(pins))
+        };
+    }
+
+    #[cfg(feature = "__tls")]
+    pub(crate) fn set_tls_handshake_timeout(&mut self, timeout: Option<Duration>) {
+        self.tls_handshake_timeout = timeout;
+    }
+
+    /// Use a different rustls config when connecting to these hosts.
+    ///
+    /// The proxy hop of a tunneled connection still uses the default config.
+    #[cfg(feature = "__rustls")]
+    pub(crate) fn set_rustls_host_configs(
+        &mut self,
+        configs: HashMap<String, rustls::ClientConfig>,
+    ) {
+        let proxies = self.proxies.clone();
+        match self.inner {
+            #[cfg(feature = "default-tls")]
+            Inner::DefaultTls(..) => {}
+            Inner::RustlsTls { ref mut hosts, .. } => {
+                *hosts = Arc::new(
+                    configs
+                        .into_iter()
+                        .map(|(host, tls)| (host, rustls_configs(tls, &proxies)))
+                        .collect(),
+                );
+            }
+        }
+    }
+
+    /// Note that the rustls configs verify certificates with a
+    /// `VerificationRecorder`, so responses can tell whether the TLS session
+    /// was reused.
+    #[cfg(feature = "__rustls")]
+    pub(crate) fn set_records_tls_verification(&mut self) {
+        match self.inner {
+            #[cfg(feature = "default-tls")]
+            Inner::DefaultTls(..) => {}
+            Inner::RustlsTls {
+                ref mut records_verification,
+                ..
+            } => *records_verification = true,
+        }
+    }
+
+    /// Connect through these proxies instead of the ones given at creation.
+    pub(crate) fn set_proxies(&mut self, proxies: Arc<Vec<Proxy>>) {
+        #[cfg(feature = "__rustls")]
+        match self.inner {
+            #[cfg(feature = "default-tls")]
+            Inner::DefaultTls(..) => {}
+            Inner::RustlsTls {
+                ref mut tls,
+                ref mut tls_proxy,
+                ref mut hosts,
+                ..
+            } => {
+                let (new_tls, new_tls_proxy) = rustls_configs((**tls).clone(), &proxies);
+                *tls = new_tls;
+                *tls_proxy = new_tls_proxy;
+                *hosts = Arc::new(
+                    hosts
+                        .iter()
+                        .map(|(host, configs)| {
+                            (host.clone(), rustls_configs((*configs.0).clone(), &proxies))
+                        })
+                        .collect(),
+                );
+            }
+        }
+        self.proxies = proxies;
+    }
+
+    pub(crate) fn set_provided_stream(&mut self, provided: Option<provided::Provided>) {
+        self.provided = provided;
+    }
+
+    pub(crate) fn set_watches_continue(&mut self, enabled: bool) {
+        self.watches_continue = enabled;
+    }
+
     #[cfg(feature = "socks")]
     async fn connect_socks(&self, dst: Uri, proxy: ProxyScheme) -> Result<Conn, BoxError> {
         let dns = match proxy {
@@ -314,32 +514,51 @@ impl Connector {
                 if dst.scheme() == Some(&Scheme::HTTPS) {
                     let host = dst.host().ok_or("no host in url")?.to_string();
                     let conn = socks::connect(proxy, dst, dns).await?;
+                    timing::record(timing::Phase::Connected);
                     let tls_connector = tokio_native_tls::TlsConnector::from(tls.clone());
-                    let io = tls_connector.connect(&host, conn).await?;
+                    let io = with_handshake_timeout(
+                        tls_connector.connect(&host, conn),
+                        self.tls_handshake_timeout,
+                    )
+                    .await?;
                     return Ok(Conn {
                         inner: self.verbose.wrap(NativeTlsConn { inner: io }),
                         is_proxy: false,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     });
                 }
             }
             #[cfg(feature = "__rustls")]
-            Inner::RustlsTls { tls_proxy, .. } => {
+            Inner::RustlsTls {
+                tls_proxy, hosts, ..
+            } => {
                 if dst.scheme() == Some(&Scheme::HTTPS) {
                     use tokio_rustls::webpki::DNSNameRef;
                     use tokio_rustls::TlsConnector as RustlsConnector;
 
-                    let tls = tls_proxy.clone();
                     let host = dst.host().ok_or("no host in url")?.to_string();
+                    let tls = match hosts.get(&host) {
+                        Some((_, tls_proxy)) => tls_proxy.clone(),
+                        None => tls_proxy.clone(),
+                    };
                     let conn = socks::connect(proxy, dst, dns).await?;
+                    timing::record(timing::Phase::Connected);
                     let dnsname = DNSNameRef::try_from_ascii_str(&host)
                         .map(|dnsname| dnsname.to_owned())
                         .map_err(|_| "Invalid DNS Name")?;
-                    let io = RustlsConnector::from(tls)
-                        .connect(dnsname.as_ref(), conn)
-                        .await?;
+                    let io = with_handshake_timeout(
+                        RustlsConnector::from(tls).connect(dnsname.as_ref(), conn),
+                        self.tls_handshake_timeout,
+                    )
+                    .await?;
                     return Ok(Conn {
                         inner: self.verbose.wrap(RustlsTlsConn { inner: io }),
                         is_proxy: false,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     });
                 }
             }
@@ -347,9 +566,14 @@ impl Connector {
             Inner::Http(_) => (),
         }
 
-        socks::connect(proxy, dst, dns).await.map(|tcp| Conn {
+        let tcp = socks::connect(proxy, dst, dns).await?;
+        timing::record(timing::Phase::Connected);
+        Ok(Conn {
             inner: self.verbose.wrap(tcp),
             is_proxy: false,
+            proxy_url: None,
+            timings: None,
+            tls_session_reused: None,
         })
     }
 
@@ -361,6 +585,9 @@ impl Connector {
                 Ok(Conn {
                     inner: self.verbose.wrap(io),
                     is_proxy,
+                    proxy_url: None,
+                    timings: None,
+                    tls_session_reused: None,
                 })
             }
             #[cfg(feature = "default-tls")]
@@ -374,9 +601,15 @@ impl Connector {
                     http.set_nodelay(true);
                 }
 
+                let (http, tcp_connected) = NotifyConnected::new(http);
                 let tls_connector = tokio_native_tls::TlsConnector::from(tls.clone());
                 let mut http = hyper_tls::HttpsConnector::from((http, tls_connector));
-                let io = http.call(dst).await?;
+                let io = with_handshake_timeout_after(
+                    http.call(dst),
+                    tcp_connected,
+                    self.tls_handshake_timeout,
+                )
+                .await?;
 
                 if let hyper_tls::MaybeHttpsStream::Https(stream) = io {
                     if !self.nodelay {
@@ -385,17 +618,29 @@ impl Connector {
                     Ok(Conn {
                         inner: self.verbose.wrap(NativeTlsConn { inner: stream }),
                         is_proxy,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     })
                 } else {
                     Ok(Conn {
                         inner: self.verbose.wrap(io),
                         is_proxy,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     })
                 }
             }
             #[cfg(feature = "__rustls")]
-            Inner::RustlsTls { http, tls, .. } => {
+            Inner::RustlsTls {
+                http, tls, hosts, ..
+            } => {
                 let mut http = http.clone();
+                let tls = match dst.host().and_then(|host| hosts.get(host)) {
+                    Some((tls, _)) => tls.clone(),
+                    None => tls,
+                };
 
                 // Disable Nagle's algorithm for TLS handshake
                 //
@@ -404,8 +649,14 @@ impl Connector {
                     http.set_nodelay(true);
                 }
 
+                let (http, tcp_connected) = NotifyConnected::new(http);
                 let mut http = hyper_rustls::HttpsConnector::from((http, tls.clone()));
-                let io = http.call(dst).await?;
+                let io = with_handshake_timeout_after(
+                    http.call(dst),
+                    tcp_connected,
+                    self.tls_handshake_timeout,
+                )
+                .await?;
 
                 if let hyper_rustls::MaybeHttpsStream::Https(stream) = io {
                     if !self.nodelay {
@@ -415,11 +666,17 @@ impl Connector {
                     Ok(Conn {
                         inner: self.verbose.wrap(RustlsTlsConn { inner: stream }),
                         is_proxy,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     })
                 } else {
                     Ok(Conn {
                         inner: self.verbose.wrap(io),
                         is_proxy,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     })
                 }
             }
@@ -463,12 +720,17 @@ impl Connector {
                     )
                     .await?;
                     let tls_connector = tokio_native_tls::TlsConnector::from(tls.clone());
-                    let io = tls_connector
-                        .connect(&host.ok_or("no host in url")?, tunneled)
-                        .await?;
+                    let io = with_handshake_timeout(
+                        tls_connector.connect(&host.ok_or("no host in url")?, tunneled),
+                        self.tls_handshake_timeout,
+                    )
+                    .await?;
                     return Ok(Conn {
                         inner: self.verbose.wrap(NativeTlsConn { inner: io }),
                         is_proxy: false,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     });
                 }
             }
@@ -477,6 +739,8 @@ impl Connector {
                 http,
                 tls,
                 tls_proxy,
+                hosts,
+                ..
             } => {
                 if dst.scheme() == Some(&Scheme::HTTPS) {
                     use tokio_rustls::webpki::DNSNameRef;
@@ -486,7 +750,10 @@ impl Connector {
                     let port = dst.port().map(|r| r.as_u16()).unwrap_or(443);
                     let http = http.clone();
                     let mut http = hyper_rustls::HttpsConnector::from((http, tls_proxy.clone()));
-                    let tls = tls.clone();
+                    let tls = match hosts.get(&host) {
+                        Some((tls, _)) => tls.clone(),
+                        None => tls.clone(),
+                    };
                     let conn = http.call(proxy_dst).await?;
                     log::trace!("tunneling HTTPS over proxy");
                     let maybe_dnsname = DNSNameRef::try_from_ascii_str(&host)
@@ -494,13 +761,18 @@ impl Connector {
                         .map_err(|_| "Invalid DNS Name");
                     let tunneled = tunnel(conn, host, port, self.user_agent.clone(), auth).await?;
                     let dnsname = maybe_dnsname?;
-                    let io = RustlsConnector::from(tls)
-                        .connect(dnsname.as_ref(), tunneled)
-                        .await?;
+                    let io = with_handshake_timeout(
+                        RustlsConnector::from(tls).connect(dnsname.as_ref(), tunneled),
+                        self.tls_handshake_timeout,
+                    )
+                    .await?;
 
                     return Ok(Conn {
                         inner: self.verbose.wrap(RustlsTlsConn { inner: io }),
                         is_proxy: false,
+                        proxy_url: None,
+                        timings: None,
+                        tls_session_reused: None,
                     });
                 }
             }
@@ -521,6 +793,17 @@ impl Connector {
             Inner::Http(http) => http.set_keepalive(dur),
         }
     }
+
+    pub(crate) fn set_reuse_address(&mut self, reuse_address: bool) {
+        match &mut self.inner {
+            #[cfg(feature = "default-tls")]
+            Inner::DefaultTls(http, _tls) => http.set_reuse_address(reuse_address),
+            #[cfg(feature = "__rustls")]
+            Inner::RustlsTls { http, .. } => http.set_reuse_address(reuse_address),
+            #[cfg(not(feature = "__tls"))]
+            Inner::Http(http) => http.set_reuse_address(reuse_address),
+        }
+    }
 }
 
 fn into_uri(scheme: Scheme, host: Authority) -> Uri {
@@ -559,11 +842,111 @@ impl Service<Uri> for Connector {
 
     fn call(&mut self, dst: Uri) -> Self::Future {
         log::debug!("starting new connection: {:?}", dst);
+        let connecting = self.connect_limited(dst);
+        if !self.watches_continue {
+            return connecting;
+        }
+        Box::pin(async move {
+            let mut conn = connecting.await?;
+            conn.inner = Box::new(expect_continue::Watch::new(conn.inner));
+            Ok(conn)
+        })
+    }
+}
+
+impl Connector {
+    fn connect_limited(&mut self, dst: Uri) -> Connecting {
+        match self.limit {
+            Some(ref limit) => {
+                let permit = limit.acquire(&dst);
+                let connecting = self.connect(dst);
+                Box::pin(async move {
+                    // Only start dialing once a slot for this host is free,
+                    // so waiting for it doesn't count against the connect timeout.
+                    let permit = permit.await;
+                    let conn = connecting.await?;
+                    Ok(Conn {
+                        inner: Box::new(limit::Limited::new(conn.inner, permit)),
+                        is_proxy: conn.is_proxy,
+                        proxy_url: conn.proxy_url,
+                        timings: conn.timings,
+                        tls_session_reused: conn.tls_session_reused,
+                    })
+                })
+            }
+            None => self.connect(dst),
+        }
+    }
+
+    fn connect(&mut self, dst: Uri) -> Connecting {
+        if let Some(ref provided) = self.provided {
+            let stream = provided.connect();
+            let verbose = self.verbose;
+            return Box::pin(async move {
+                Ok(Conn {
+                    inner: verbose.wrap(stream.await?),
+                    is_proxy: false,
+                    proxy_url: None,
+                    timings: None,
+                    tls_session_reused: None,
+                })
+            });
+        }
+
+        #[cfg(all(feature = "__tls", feature = "pinning"))]
+        {
+            if let Some(ref pins) = self.pinned_keys {
+                if dst.scheme() == Some(&Scheme::HTTPS) {
+                    let pins = pins.clone();
+                    let connecting = self.connect_unpinned(dst);
+                    return Box::pin(async move {
+                        let conn = connecting.await?;
+                        verify_pinned_keys(&pins, &conn)?;
+                        Ok(conn)
+                    });
+                }
+            }
+        }
+
+        self.connect_unpinned(dst)
+    }
+
+    fn connect_unpinned(&mut self, dst: Uri) -> Connecting {
+        let is_https = dst.scheme() == Some(&Scheme::HTTPS);
+        let records_verification = is_https && self.records_tls_verification();
+        Box::pin(timing::timed(
+            self.connect_untimed(dst),
+            is_https,
+            records_verification,
+        ))
+    }
+
+    fn records_tls_verification(&self) -> bool {
+        match self.inner {
+            #[cfg(not(feature = "__tls"))]
+            Inner::Http(_) => false,
+            #[cfg(feature = "default-tls")]
+            Inner::DefaultTls(..) => false,
+            #[cfg(feature = "__rustls")]
+            Inner::RustlsTls {
+                records_verification,
+                ..
+            } => records_verification,
+        }
+    }
+
+    fn connect_untimed(&mut self, dst: Uri) -> Connecting {
         let timeout = self.timeout;
         for prox in self.proxies.iter() {
             if let Some(proxy_scheme) = prox.intercept(&dst) {
+                let proxy_url = proxy_scheme.to_url();
+                let connecting = self.clone().connect_via_proxy(dst, proxy_scheme);
                 return Box::pin(with_timeout(
-                    self.clone().connect_via_proxy(dst, proxy_scheme),
+                    async move {
+                        let mut conn = connecting.await?;
+                        conn.proxy_url = Some(proxy_url);
+                        Ok(conn)
+                    },
                     timeout,
                 ));
             }
@@ -576,6 +959,144 @@ impl Service<Uri> for Connector {
     }
 }
 
+/// Wraps the TCP connector to signal once the TCP connection is
+/// established, so the TLS handshake that follows can be timed on its own.
+#[cfg(feature = "__tls")]
+struct NotifyConnected {
+    http: HttpConnector,
+    connected: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+#[cfg(feature = "__tls")]
+impl NotifyConnected {
+    fn new(http: HttpConnector) -> (Self, tokio::sync::oneshot::Receiver<()>) {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let notify = NotifyConnected {
+            http,
+            connected: Some(tx),
+        };
+        (notify, rx)
+    }
+}
+
+#[cfg(feature = "__tls")]
+impl Service<Uri> for NotifyConnected {
+    type Response = <HttpConnector as Service<Uri>>::Response;
+    type Error = <HttpConnector as Service<Uri>>::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let connecting = self.http.call(dst);
+        let connected = self.connected.take();
+        Box::pin(async move {
+            let io = connecting.await?;
+            if let Some(connected) = connected {
+                let _ = connected.send(());
+            }
+            Ok(io)
+        })
+    }
+}
+
+/// Bounds a TLS handshake by `timeout`, if set.
+#[cfg(feature = "__tls")]
+async fn with_handshake_timeout<F, T, E>(
+    handshake: F,
+    timeout: Option<Duration>,
+) -> Result<T, BoxError>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    if let Some(to) = timeout {
+        match tokio::time::timeout(to, handshake).await {
+            Err(_elapsed) => Err(Box::new(crate::error::TlsHandshakeTimedOut) as BoxError),
+            Ok(res) => res.map_err(Into::into),
+        }
+    } else {
+        handshake.await.map_err(Into::into)
+    }
+}
+
+/// Like `with_handshake_timeout`, for a future that first connects over TCP
+/// and then does the handshake. The timeout starts once `tcp_connected`
+/// fires.
+#[cfg(feature = "__tls")]
+async fn with_handshake_timeout_after<F, T>(
+    connecting: F,
+    tcp_connected: tokio::sync::oneshot::Receiver<()>,
+    timeout: Option<Duration>,
+) -> Result<T, BoxError>
+where
+    F: Future<Output = Result<T, BoxError>>,
+{
+    if timeout.is_none() {
+        return connecting.await;
+    }
+
+    futures_util::pin_mut!(connecting);
+    match futures_util::future::select(connecting, tcp_connected).await {
+        Either::Left((res, _)) => res,
+        Either::Right((_, handshake)) => with_handshake_timeout(handshake, timeout).await,
+    }
+}
+
+#[cfg(all(feature = "__tls", feature = "pinning"))]
+fn verify_pinned_keys(pins: &PinnedKeys, conn: &Conn) -> Result<(), BoxError> {
+    let mut extensions = http::Extensions::new();
+    conn.connected().get_extras(&mut extensions);
+    let leaf = extensions
+        .get::<PeerCertificates>()
+        .and_then(|certs| certs.0.first())
+        .ok_or("no peer certificate to check against pinned public keys")?;
+    pins.verify(&leaf.to_der()?)
+}
+
+/// Added to the response extensions when the connection was routed through
+/// a proxy, holding the proxy's URL.
+#[derive(Clone, Debug)]
+pub(crate) struct ViaProxy(pub(crate) Url);
+
+/// Added to the response extensions for every response on a TLS connection
+/// whose certificate verification was recorded, holding whether the
+/// handshake resumed an earlier session instead of verifying the server's
+/// certificate again.
+#[cfg(feature = "__tls")]
+#[derive(Clone, Debug)]
+pub(crate) struct TlsSessionReused(pub(crate) bool);
+
+/// How long the phases of establishing a connection took.
+///
+/// A phase that didn't happen, such as resolving an IP address or a TLS
+/// handshake for plain HTTP, is `None`.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ConnectTimings {
+    pub(crate) dns: Option<Duration>,
+    pub(crate) connect: Option<Duration>,
+    pub(crate) tls: Option<Duration>,
+}
+
+/// Added to the response extensions for every response on a connection,
+/// holding its [`ConnectTimings`] until the first of them takes them.
+#[derive(Clone, Debug)]
+pub(crate) struct NewConnection(Arc<std::sync::Mutex<Option<ConnectTimings>>>);
+
+impl NewConnection {
+    fn new(timings: ConnectTimings) -> NewConnection {
+        NewConnection(Arc::new(std::sync::Mutex::new(Some(timings))))
+    }
+
+    /// Takes the timings, unless an earlier response on this connection
+    /// already did.
+    pub(crate) fn take(&self) -> Option<ConnectTimings> {
+        self.0.lock().unwrap().take()
+    }
+}
+
 pub(crate) trait AsyncConn:
     AsyncRead + AsyncWrite + Connection + Send + Sync + Unpin + 'static
 {
@@ -590,16 +1111,38 @@ pin_project! {
     /// This tells hyper whether the URI should be written in
     /// * origin-form (`GET /just/a/path HTTP/1.1`), when `is_proxy == false`, or
     /// * absolute-form (`GET http://foo.bar/and/a/path HTTP/1.1`), otherwise.
+    ///
+    /// The `proxy_url` member is set for any connection made through a proxy,
+    /// including tunnels, and is exposed on the response as [`ViaProxy`].
+    ///
+    /// The `timings` member is set once the connection is established, and
+    /// is handed to the first response on it as [`NewConnection`]. So is
+    /// `tls_session_reused`, which is exposed on every response on it as
+    /// [`TlsSessionReused`].
     pub(crate) struct Conn {
         #[pin]
         inner: BoxConn,
         is_proxy: bool,
+        proxy_url: Option<Url>,
+        timings: Option<NewConnection>,
+        tls_session_reused: Option<bool>,
     }
 }
 
 impl Connection for Conn {
     fn connected(&self) -> Connected {
-        self.inner.connected().proxy(self.is_proxy)
+        let mut connected = self.inner.connected().proxy(self.is_proxy);
+        if let Some(ref proxy_url) = self.proxy_url {
+            connected = connected.extra(ViaProxy(proxy_url.clone()));
+        }
+        if let Some(ref timings) = self.timings {
+            connected = connected.extra(timings.clone());
+        }
+        #[cfg(feature = "__tls")]
+        if let Some(reused) = self.tls_session_reused {
+            connected = connected.extra(TlsSessionReused(reused));
+        }
+        connected
     }
 }
 
@@ -737,6 +1280,10 @@ mod native_tls_conn {
     use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
     use tokio_native_tls::TlsStream;
 
+    #[cfg(feature = "native-tls-alpn")]
+    use crate::tls::AlpnProtocol;
+    use crate::tls::PeerCertificates;
+
     pin_project! {
         pub(super) struct NativeTlsConn<T> {
             #[pin] pub(super) inner: TlsStream<T>,
@@ -744,24 +1291,35 @@ mod native_tls_conn {
     }
 
     impl<T: Connection + AsyncRead + AsyncWrite + Unpin> Connection for NativeTlsConn<T> {
-        #[cfg(feature = "native-tls-alpn")]
         fn connected(&self) -> Connected {
-            match self.inner.get_ref().negotiated_alpn().ok() {
-                Some(Some(alpn_protocol)) if alpn_protocol == b"h2" => self
-                    .inner
-                    .get_ref()
-                    .get_ref()
-                    .get_ref()
-                    .connected()
-                    .negotiated_h2(),
-                _ => self.inner.get_ref().get_ref().get_ref().connected(),
+            let tls = self.inner.get_ref();
+            let connected = tls.get_ref().get_ref().connected();
+
+            #[cfg(feature = "native-tls-alpn")]
+            let connected = match tls.negotiated_alpn().ok().and_then(|alpn| alpn) {
+                Some(alpn_protocol) => {
+                    let connected = if alpn_protocol == b"h2" {
+                        connected.negotiated_h2()
+                    } else {
+                        connected
+                    };
+                    connected.extra(AlpnProtocol(alpn_protocol))
+                }
+                None => connected,
+            };
+
+            // native-tls only gives access to the leaf certificate.
+            let leaf = tls
+                .peer_certificate()
+                .ok()
+                .and_then(|cert| cert)
+                .and_then(|cert| cert.to_der().ok())
+                .and_then(|der| crate::Certificate::from_der(&der).ok());
+            match leaf {
+                Some(leaf) => connected.extra(PeerCertificates(vec![leaf])),
+                None => connected,
             }
         }
-
-        #[cfg(not(feature = "native-tls-alpn"))]
-        fn connected(&self) -> Connected {
-            self.inner.get_ref().get_ref().get_ref().connected()
-        }
     }
 
     impl<T: AsyncRead + AsyncWrite + Unpin> AsyncRead for NativeTlsConn<T> {
@@ -829,6 +1387,8 @@ mod rustls_tls_conn {
     use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
     use tokio_rustls::client::TlsStream;
 
+    use crate::tls::{AlpnProtocol, PeerCertificates};
+
     pin_project! {
         pub(super) struct RustlsTlsConn<T> {
             #[pin] pub(super) inner: TlsStream<T>,
@@ -837,10 +1397,25 @@ mod rustls_tls_conn {
 
     impl<T: Connection + AsyncRead + AsyncWrite + Unpin> Connection for RustlsTlsConn<T> {
         fn connected(&self) -> Connected {
-            if self.inner.get_ref().1.get_alpn_protocol() == Some(b"h2") {
-                self.inner.get_ref().0.connected().negotiated_h2()
-            } else {
-                self.inner.get_ref().0.connected()
+            let (io, session) = self.inner.get_ref();
+            let connected = match session.get_alpn_protocol() {
+                Some(b"h2") => io.connected().negotiated_h2(),
+                _ => io.connected(),
+            };
+            let connected = match session.get_alpn_protocol() {
+                Some(alpn_protocol) => connected.extra(AlpnProtocol(alpn_protocol.to_vec())),
+                None => connected,
+            };
+
+            let chain = session.get_peer_certificates().map(|certs| {
+                certs
+                    .iter()
+                    .filter_map(|cert| crate::Certificate::from_der(&cert.0).ok())
+                    .collect::<Vec<_>>()
+            });
+            match chain {
+                Some(chain) => connected.extra(PeerCertificates(chain)),
+                None => connected,
             }
         }
     }
@@ -984,10 +1559,16 @@ pub(crate) mod itertools {
     }
 }
 
+/// The addresses a `DnsResolverWithOverrides` hands to the connector, which
+/// tries them in order until one connects.
+pub(crate) type OverriddenAddrs<Iter> =
+    std::iter::Take<itertools::Either<Iter, std::vec::IntoIter<SocketAddr>>>;
+
 pin_project! {
     pub(crate) struct WrappedResolverFuture<Fut> {
         #[pin]
         fut: Fut,
+        max_addrs: usize,
     }
 }
 
@@ -996,30 +1577,39 @@ where
     Fut: std::future::Future<Output = Result<FutOutput, FutError>>,
     FutOutput: Iterator<Item = SocketAddr>,
 {
-    type Output = Result<itertools::Either<FutOutput, std::iter::Once<SocketAddr>>, FutError>;
+    type Output = Result<OverriddenAddrs<FutOutput>, FutError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
+        let max_addrs = *this.max_addrs;
         this.fut
             .poll(cx)
-            .map(|result| result.map(itertools::Either::Left))
+            .map(|result| result.map(|addrs| itertools::Either::Left(addrs).take(max_addrs)))
     }
 }
 
+/// Wraps a resolver to answer some names from a fixed set of addresses, and
+/// to bound how many resolved addresses are tried.
 #[derive(Clone)]
 pub(crate) struct DnsResolverWithOverrides<Resolver>
 where
     Resolver: Clone,
 {
     dns_resolver: Resolver,
-    overrides: Arc<HashMap<String, SocketAddr>>,
+    overrides: Arc<HashMap<String, Vec<SocketAddr>>>,
+    max_addrs: usize,
 }
 
 impl<Resolver: Clone> DnsResolverWithOverrides<Resolver> {
-    fn new(dns_resolver: Resolver, overrides: HashMap<String, SocketAddr>) -> Self {
+    fn new(
+        dns_resolver: Resolver,
+        overrides: HashMap<String, Vec<SocketAddr>>,
+        connect_attempts: Option<usize>,
+    ) -> Self {
         DnsResolverWithOverrides {
             dns_resolver,
             overrides: Arc::new(overrides),
+            max_addrs: connect_attempts.map_or(usize::MAX, |attempts| attempts.max(1)),
         }
     }
 }
@@ -1029,13 +1619,11 @@ where
     Resolver: Service<Name, Response = Iter> + Clone,
     Iter: Iterator<Item = SocketAddr>,
 {
-    type Response = itertools::Either<Iter, std::iter::Once<SocketAddr>>;
+    type Response = OverriddenAddrs<Iter>;
     type Error = <Resolver as Service<Name>>::Error;
     type Future = Either<
         WrappedResolverFuture<<Resolver as Service<Name>>::Future>,
-        futures_util::future::Ready<
-            Result<itertools::Either<Iter, std::iter::Once<SocketAddr>>, Self::Error>,
-        >,
+        futures_util::future::Ready<Result<OverriddenAddrs<Iter>, Self::Error>>,
     >;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -1045,20 +1633,626 @@ where
     fn call(&mut self, name: Name) -> Self::Future {
         match self.overrides.get(name.as_str()) {
             Some(dest) => {
-                let fut = futures_util::future::ready(Ok(itertools::Either::Right(
-                    std::iter::once(dest.to_owned()),
-                )));
+                let addrs = itertools::Either::Right(dest.clone().into_iter());
+                let fut = futures_util::future::ready(Ok(addrs.take(self.max_addrs)));
                 Either::Right(fut)
             }
             None => {
                 let resolver_fut = self.dns_resolver.call(name);
-                let y = WrappedResolverFuture { fut: resolver_fut };
+                let y = WrappedResolverFuture {
+                    fut: resolver_fut,
+                    max_addrs: self.max_addrs,
+                };
                 Either::Left(y)
             }
         }
     }
 }
 
+pub(crate) mod timing {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll};
+    use std::time::Instant;
+
+    use pin_project_lite::pin_project;
+
+    use super::{BoxError, Conn, ConnectTimings, NewConnection};
+
+    tokio::task_local! {
+        static PHASES: Arc<Mutex<Phases>>;
+    }
+
+    /// When the phases of establishing a connection ended.
+    #[derive(Default)]
+    struct Phases {
+        resolved: Option<Instant>,
+        connected: Option<Instant>,
+        verified: Option<Instant>,
+    }
+
+    #[derive(Clone, Copy)]
+    pub(crate) enum Phase {
+        Resolved,
+        Connected,
+        /// A TLS handshake verified the server's certificate, which one
+        /// that resumes a session skips.
+        #[cfg(feature = "__rustls")]
+        Verified,
+    }
+
+    /// Notes that `phase` just ended, if a connection is being timed.
+    ///
+    /// Only the first end of each phase counts, so that when connecting
+    /// through a proxy, the phases are those of reaching the proxy.
+    pub(crate) fn record(phase: Phase) {
+        let now = Instant::now();
+        let _ = PHASES.try_with(|phases| {
+            let mut phases = phases.lock().unwrap();
+            let end = match phase {
+                Phase::Resolved => &mut phases.resolved,
+                Phase::Connected => &mut phases.connected,
+                #[cfg(feature = "__rustls")]
+                Phase::Verified => &mut phases.verified,
+            };
+            end.get_or_insert(now);
+        });
+    }
+
+    /// Times the phases of `connecting`, as noted by `record` while it runs.
+    ///
+    /// Everything after the TCP connection is counted as the TLS handshake
+    /// of an `https` connection, which through a proxy includes setting up
+    /// the tunnel.
+    ///
+    /// If `records_verification` is set, whether the TLS session was reused
+    /// is also noted, from whether a certificate was verified.
+    pub(super) async fn timed<F>(
+        connecting: F,
+        is_https: bool,
+        records_verification: bool,
+    ) -> Result<Conn, BoxError>
+    where
+        F: Future<Output = Result<Conn, BoxError>>,
+    {
+        let phases = Arc::new(Mutex::new(Phases::default()));
+        let start = Instant::now();
+        let mut conn = PHASES.scope(phases.clone(), connecting).await?;
+        let end = Instant::now();
+
+        let phases = phases.lock().unwrap();
+        let connect_start = phases.resolved.unwrap_or(start);
+        conn.timings = Some(NewConnection::new(ConnectTimings {
+            dns: phases.resolved.map(|resolved| resolved - start),
+            connect: phases.connected.map(|connected| connected - connect_start),
+            tls: phases
+                .connected
+                .filter(|_| is_https)
+                .map(|connected| end - connected),
+        }));
+        if records_verification {
+            conn.tls_session_reused = Some(phases.verified.is_none());
+        }
+        Ok(conn)
+    }
+
+    pin_project! {
+        /// Records the end of `phase` once `fut` succeeds.
+        pub(crate) struct Record<F> {
+            #[pin]
+            fut: F,
+            phase: Phase,
+        }
+    }
+
+    impl<F> Record<F> {
+        pub(super) fn new(fut: F, phase: Phase) -> Record<F> {
+            Record { fut, phase }
+        }
+    }
+
+    impl<F, T, E> Future for Record<F>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        type Output = Result<T, E>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.project();
+            let res = futures_core::ready!(this.fut.poll(cx));
+            if res.is_ok() {
+                record(*this.phase);
+            }
+            Poll::Ready(res)
+        }
+    }
+}
+
+mod limit {
+    use http::Uri;
+    use hyper::client::connect::{Connected, Connection};
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::io::{self, IoSlice};
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+    /// Bounds the number of simultaneous connections to each host.
+    #[derive(Clone)]
+    pub(super) struct PerHost {
+        max: usize,
+        hosts: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+    }
+
+    impl PerHost {
+        pub(super) fn new(max: usize) -> PerHost {
+            PerHost {
+                max,
+                hosts: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+
+        /// The same limit, with slots of its own.
+        pub(super) fn separate(&self) -> PerHost {
+            PerHost::new(self.max)
+        }
+
+        pub(super) fn acquire(
+            &self,
+            dst: &Uri,
+        ) -> impl Future<Output = OwnedSemaphorePermit> + Send + 'static {
+            let key = dst
+                .authority()
+                .map(|auth| auth.as_str().to_owned())
+                .unwrap_or_default();
+            let mut hosts = self.hosts.lock().unwrap();
+            if !hosts.contains_key(&key) {
+                // Forget the hosts without connections, before adding one.
+                let max = self.max;
+                hosts.retain(|_, semaphore| {
+                    Arc::strong_count(semaphore) > 1 || semaphore.available_permits() < max
+                });
+            }
+            let semaphore = hosts
+                .entry(key)
+                .or_insert_with(|| Arc::new(Semaphore::new(self.max)))
+                .clone();
+            drop(hosts);
+            async move {
+                semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("per-host semaphore is never closed")
+            }
+        }
+    }
+
+    /// A connection holding one of its host's slots until it is dropped.
+    pub(super) struct Limited {
+        inner: super::BoxConn,
+        _permit: OwnedSemaphorePermit,
+    }
+
+    impl Limited {
+        pub(super) fn new(inner: super::BoxConn, permit: OwnedSemaphorePermit) -> Limited {
+            Limited {
+                inner,
+                _permit: permit,
+            }
+        }
+    }
+
+    impl Connection for Limited {
+        fn connected(&self) -> Connected {
+            self.inner.connected()
+        }
+    }
+
+    impl AsyncRead for Limited {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for Limited {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &[u8],
+        ) -> Poll<Result<usize, io::Error>> {
+            Pin::new(&mut self.inner).poll_write(cx, buf)
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<Result<usize, io::Error>> {
+            Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+        ) -> Poll<Result<(), io::Error>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::PerHost;
+
+        #[test]
+        fn per_host_forgets_idle_hosts() {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .expect("new rt");
+            let limit = PerHost::new(1);
+            let uri = |host: &str| format!("http://{}/", host).parse::<http::Uri>().unwrap();
+            let hosts = || limit.hosts.lock().unwrap().len();
+
+            let busy = rt.block_on(limit.acquire(&uri("busy.local")));
+            for i in 0..10 {
+                let permit = rt.block_on(limit.acquire(&uri(&format!("host{}.local", i))));
+                drop(permit);
+            }
+            // The host with a connection, and the last idle one, which is
+            // only forgotten when another host is added.
+            assert_eq!(hosts(), 2);
+
+            drop(busy);
+            let _permit = rt.block_on(limit.acquire(&uri("other.local")));
+            assert_eq!(hosts(), 1);
+        }
+    }
+}
+
+pub(crate) mod expect_continue {
+    use hyper::client::connect::{CaptureConnection, Connected, Connection};
+    use std::future::Future;
+    use std::io::{self, IoSlice};
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use tokio::time::Sleep;
+
+    /// The start of a `100 Continue` status line, up to its code.
+    const STATUS_LINE_LEN: usize = b"HTTP/1.1 100".len();
+
+    /// Added to the `Connected` of a watched connection, for a request
+    /// body to wait on until the server answers with `100 Continue`.
+    ///
+    /// hyper reads and skips interim responses without reporting them, so
+    /// the status line is looked for in what is read from the connection.
+    /// Only the start of the first response read after `expect` is looked
+    /// at, which on an HTTP/1 connection is the response to the request
+    /// whose body is waiting.
+    #[derive(Clone, Default)]
+    pub(crate) struct Signal(Arc<Shared>);
+
+    #[derive(Default)]
+    struct Shared {
+        // Checked on every read, so that connections without a request
+        // expecting `100 Continue` don't take the lock.
+        waiting: AtomicBool,
+        state: Mutex<State>,
+    }
+
+    #[derive(Default)]
+    struct State {
+        status_line: Vec<u8>,
+        continued: bool,
+        waker: Option<Waker>,
+    }
+
+    impl Signal {
+        /// Starts looking for a `100 Continue` in what is read next.
+        fn expect(&self) {
+            let mut state = self.0.state.lock().unwrap();
+            state.status_line.clear();
+            state.continued = false;
+            state.waker = None;
+            self.0.waiting.store(true, Ordering::Release);
+        }
+
+        fn poll_continued(&self, cx: &mut Context<'_>) -> Poll<()> {
+            let mut state = self.0.state.lock().unwrap();
+            if state.continued {
+                return Poll::Ready(());
+            }
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+
+        fn read(&self, bytes: &[u8]) {
+            if bytes.is_empty() || !self.0.waiting.load(Ordering::Acquire) {
+                return;
+            }
+            let mut state = self.0.state.lock().unwrap();
+            if !self.0.waiting.load(Ordering::Acquire) {
+                return;
+            }
+            let needed = STATUS_LINE_LEN - state.status_line.len();
+            state
+                .status_line
+                .extend_from_slice(&bytes[..needed.min(bytes.len())]);
+            if state.status_line.len() < STATUS_LINE_LEN {
+                return;
+            }
+            self.0.waiting.store(false, Ordering::Release);
+            let line = &state.status_line;
+            if (line.starts_with(b"HTTP/1.1 ") || line.starts_with(b"HTTP/1.0 "))
+                && line.ends_with(b" 100")
+            {
+                state.continued = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+
+    /// A connection that notes when a `100 Continue` is read from it.
+    pub(super) struct Watch {
+        inner: super::BoxConn,
+        signal: Signal,
+    }
+
+    impl Watch {
+        pub(super) fn new(inner: super::BoxConn) -> Watch {
+            Watch {
+                inner,
+                signal: Signal::default(),
+            }
+        }
+    }
+
+    impl Connection for Watch {
+        fn connected(&self) -> Connected {
+            self.inner.connected().extra(self.signal.clone())
+        }
+    }
+
+    impl AsyncRead for Watch {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let filled = buf.filled().len();
+            futures_core::ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+            self.signal.read(&buf.filled()[filled..]);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for Watch {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &[u8],
+        ) -> Poll<Result<usize, io::Error>> {
+            Pin::new(&mut self.inner).poll_write(cx, buf)
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<Result<usize, io::Error>> {
+            Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+        ) -> Poll<Result<(), io::Error>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    /// Holds back a request body until the server answers with
+    /// `100 Continue`, or `timeout` passes.
+    pub(crate) struct Wait {
+        timeout: Duration,
+        connection: CaptureConnection,
+        started: Option<Option<(Pin<Box<Sleep>>, Signal)>>,
+    }
+
+    impl Wait {
+        pub(crate) fn new(timeout: Duration, connection: CaptureConnection) -> Wait {
+            Wait {
+                timeout,
+                connection,
+                started: None,
+            }
+        }
+
+        /// Counts from the first poll, which is once the request head has
+        /// been written to the connection.
+        ///
+        /// Doesn't wait on connections that aren't watched, or that use
+        /// HTTP/2, where a `100 Continue` can't be noticed.
+        pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+            let timeout = self.timeout;
+            let connection = &self.connection;
+            let started = self.started.get_or_insert_with(|| {
+                let connected = connection.connection_metadata();
+                let connected = connected.as_ref().filter(|c| !c.is_negotiated_h2())?;
+                let mut extras = http::Extensions::new();
+                connected.get_extras(&mut extras);
+                let signal = extras.remove::<Signal>()?;
+                signal.expect();
+                Some((Box::pin(tokio::time::sleep(timeout)), signal))
+            });
+
+            let (sleep, signal) = match started {
+                Some(started) => started,
+                None => return Poll::Ready(()),
+            };
+            if signal.poll_continued(cx).is_ready() {
+                return Poll::Ready(());
+            }
+            sleep.as_mut().poll(cx)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Signal;
+
+        #[test]
+        fn continue_across_reads() {
+            let signal = Signal::default();
+            signal.expect();
+            signal.read(b"HTTP/1.1 1");
+            assert!(!signal.0.state.lock().unwrap().continued);
+            signal.read(b"00 Continue\r\n\r\n");
+            assert!(signal.0.state.lock().unwrap().continued);
+        }
+
+        #[test]
+        fn ignores_reads_until_expected() {
+            let signal = Signal::default();
+            signal.read(b"HTTP/1.1 100 Continue\r\n\r\n");
+            assert!(signal.0.state.lock().unwrap().status_line.is_empty());
+
+            signal.expect();
+            signal.read(b"HTTP/1.1 100 Continue\r\n\r\n");
+            assert!(signal.0.state.lock().unwrap().continued);
+        }
+
+        #[test]
+        fn final_response_doesnt_continue() {
+            let signal = Signal::default();
+            signal.expect();
+            signal.read(b"HTTP/1.1 417 Expectation Failed\r\n\r\n");
+            assert!(!signal.0.state.lock().unwrap().continued);
+
+            // Nor does a later one, until a body waits again.
+            signal.read(b"HTTP/1.1 100 Continue\r\n\r\n");
+            assert!(!signal.0.state.lock().unwrap().continued);
+        }
+    }
+}
+
+pub(crate) mod provided {
+    use hyper::client::connect::{Connected, Connection};
+    use std::future::Future;
+    use std::io::{self, IoSlice};
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    pub(crate) trait Io: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static {}
+
+    impl<T: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static> Io for T {}
+
+    type Connecting = Pin<Box<dyn Future<Output = io::Result<Stream>> + Send>>;
+
+    /// Makes the stream of each connection the client opens.
+    #[derive(Clone)]
+    pub(crate) struct Provided(Arc<dyn Fn() -> Connecting + Send + Sync>);
+
+    impl Provided {
+        pub(crate) fn new<F, Fut, T>(connect: F) -> Provided
+        where
+            F: Fn() -> Fut + Send + Sync + 'static,
+            Fut: Future<Output = io::Result<T>> + Send + 'static,
+            T: Io,
+        {
+            Provided(Arc::new(move || {
+                let stream = connect();
+                Box::pin(async move { Ok(Stream(Box::new(stream.await?))) })
+            }))
+        }
+
+        pub(super) fn connect(&self) -> Connecting {
+            (self.0)()
+        }
+    }
+
+    pub(super) struct Stream(Box<dyn Io>);
+
+    impl Connection for Stream {
+        fn connected(&self) -> Connected {
+            Connected::new()
+        }
+    }
+
+    impl AsyncRead for Stream {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            Pin::new(&mut *self.0).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for Stream {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &[u8],
+        ) -> Poll<Result<usize, io::Error>> {
+            Pin::new(&mut *self.0).poll_write(cx, buf)
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<Result<usize, io::Error>> {
+            Pin::new(&mut *self.0).poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.0.is_write_vectored()
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+            Pin::new(&mut *self.0).poll_flush(cx)
+        }
+
+        fn poll_shutdown(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+        ) -> Poll<Result<(), io::Error>> {
+            Pin::new(&mut *self.0).poll_shutdown(cx)
+        }
+    }
+}
+
 mod verbose {
     use hyper::client::connect::{Connected, Connection};
     use std::fmt;
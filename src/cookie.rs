@@ -14,18 +14,46 @@ pub trait CookieStore: Send + Sync {
     fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &url::Url);
     /// Get any Cookie values in the store for `url`
     fn cookies(&self, url: &url::Url) -> Option<HeaderValue>;
+    /// Store a set of Set-Cookie header values received from `url`, and call
+    /// `on_change` with each cookie that was stored or removed because of it.
+    ///
+    /// The default implementation calls `set_cookies` and reports nothing.
+    fn set_cookies_with_changes(
+        &self,
+        cookie_headers: &mut dyn Iterator<Item = &HeaderValue>,
+        url: &url::Url,
+        on_change: &mut dyn FnMut(CookieChange),
+    ) {
+        let _ = on_change;
+        self.set_cookies(cookie_headers, url);
+    }
 }
 
 /// A single HTTP cookie.
 pub struct Cookie<'a>(cookie_crate::Cookie<'a>);
 
+/// A change to the cookies in a `CookieStore`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CookieChange {
+    /// The cookie was stored, either as a new cookie or replacing one with the
+    /// same name, domain and path.
+    Stored(Cookie<'static>),
+    /// The cookie was removed, because a server expired it or because it
+    /// reached its own expiry time.
+    Removed(Cookie<'static>),
+}
+
 /// A good default `CookieStore` implementation.
 ///
 /// This is the implementation used when simply calling `cookie_store(true)`.
 /// This type is exposed to allow creating one and filling it with some
 /// existing cookies more easily, before creating a `Client`.
 #[derive(Debug, Default)]
-pub struct Jar(RwLock<cookie_store::CookieStore>);
+pub struct Jar {
+    store: RwLock<cookie_store::CookieStore>,
+    relaxed: bool,
+}
 
 // ===== impl Cookie =====
 
@@ -133,6 +161,31 @@ impl std::error::Error for CookieParseError {}
 // ===== impl Jar =====
 
 impl Jar {
+    /// Creates a jar with relaxed matching for local hosts.
+    ///
+    /// For requests to `localhost` (or a subdomain of it) and to IP
+    /// addresses, a cookie's `Domain` attribute is ignored, so the cookie is
+    /// always stored for the host that sent it, and `Secure` cookies are
+    /// sent over plain HTTP too. This makes local testing easier, and has no
+    /// effect for any other host.
+    pub(crate) fn relaxed() -> Jar {
+        Jar {
+            store: RwLock::default(),
+            relaxed: true,
+        }
+    }
+
+    fn is_relaxed_for(&self, url: &url::Url) -> bool {
+        self.relaxed
+            && match url.host() {
+                Some(url::Host::Domain(domain)) => {
+                    domain == "localhost" || domain.ends_with(".localhost")
+                }
+                Some(url::Host::Ipv4(_)) | Some(url::Host::Ipv6(_)) => true,
+                None => false,
+            }
+    }
+
     /// Add a cookie to this jar.
     ///
     /// # Example
@@ -153,24 +206,99 @@ impl Jar {
             .ok()
             .map(|c| c.into_owned())
             .into_iter();
-        self.0.write().unwrap().store_response_cookies(cookies, url);
+        self.store
+            .write()
+            .unwrap()
+            .store_response_cookies(cookies, url);
     }
 }
 
 impl CookieStore for Jar {
     fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &url::Url) {
-        let iter =
-            cookie_headers.filter_map(|val| Cookie::parse(val).map(|c| c.0.into_owned()).ok());
+        let relaxed = self.is_relaxed_for(url);
+        let iter = cookie_headers
+            .filter_map(|val| Cookie::parse(val).map(|c| c.0.into_owned()).ok())
+            .map(|mut cookie| {
+                if relaxed {
+                    cookie.unset_domain();
+                }
+                cookie
+            });
+
+        self.store
+            .write()
+            .unwrap()
+            .store_response_cookies(iter, url);
+    }
+
+    fn set_cookies_with_changes(
+        &self,
+        cookie_headers: &mut dyn Iterator<Item = &HeaderValue>,
+        url: &url::Url,
+        on_change: &mut dyn FnMut(CookieChange),
+    ) {
+        let relaxed = self.is_relaxed_for(url);
+        let mut changes = Vec::new();
+        {
+            let mut store = self.store.write().unwrap();
+            for value in cookie_headers {
+                let mut cookie = match Cookie::parse(value) {
+                    Ok(cookie) => cookie.0.into_owned(),
+                    Err(_) => continue,
+                };
+                if relaxed {
+                    cookie.unset_domain();
+                }
+                // An expired cookie that is accepted expires the stored one,
+                // which is reported when it is removed below.
+                let expired = cookie_store::Cookie::try_from_raw_cookie(&cookie, url)
+                    .map(|cookie| cookie.is_expired())
+                    .unwrap_or(false);
+                match store.insert_raw(&cookie, url) {
+                    Ok(_) if !expired => changes.push(CookieChange::Stored(Cookie(cookie))),
+                    Ok(_) => (),
+                    Err(e) => log::debug!("unable to store Set-Cookie: {:?}", e),
+                }
+            }
 
-        self.0.write().unwrap().store_response_cookies(iter, url);
+            // Expired cookies are never sent, so dropping them here reports
+            // each one exactly once.
+            let expired = store
+                .iter_any()
+                .filter(|cookie| cookie.is_expired())
+                .map(|cookie| {
+                    (
+                        String::from(&cookie.domain),
+                        String::from(&cookie.path),
+                        cookie.name().to_owned(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for (domain, path, name) in expired {
+                if let Some(cookie) = store.remove(&domain, &path, &name) {
+                    changes.push(CookieChange::Removed(Cookie((*cookie).clone())));
+                }
+            }
+        }
+
+        // Called without the lock, so the callback can use the jar.
+        for change in changes {
+            on_change(change);
+        }
     }
 
     fn cookies(&self, url: &url::Url) -> Option<HeaderValue> {
+        let mut url = std::borrow::Cow::Borrowed(url);
+        if self.is_relaxed_for(&url) && url.scheme() == "http" {
+            // Let `Secure` cookies through as well.
+            let _ = url.to_mut().set_scheme("https");
+        }
+
         let s = self
-            .0
+            .store
             .read()
             .unwrap()
-            .get_request_cookies(url)
+            .get_request_cookies(&url)
             .map(|c| format!("{}={}", c.name(), c.value()))
             .collect::<Vec<_>>()
             .join("; ");
@@ -57,8 +57,26 @@ impl Error {
     }
 
     /// Returns true if the error is from a type Builder.
+    ///
+    /// This includes TLS configuration errors, which can be told apart
+    /// further with `is_tls_backend` and `is_tls_certificate`.
     pub fn is_builder(&self) -> bool {
-        matches!(self.inner.kind, Kind::Builder)
+        matches!(
+            self.inner.kind,
+            Kind::Builder | Kind::TlsBackend | Kind::TlsCertificate
+        )
+    }
+
+    /// Returns true if the error is because the TLS backend could not be
+    /// initialized.
+    pub fn is_tls_backend(&self) -> bool {
+        matches!(self.inner.kind, Kind::TlsBackend)
+    }
+
+    /// Returns true if the error is because a provided TLS certificate or
+    /// identity is invalid.
+    pub fn is_tls_certificate(&self) -> bool {
+        matches!(self.inner.kind, Kind::TlsCertificate)
     }
 
     /// Returns true if the error is from a `RedirectPolicy`.
@@ -79,6 +97,12 @@ impl Error {
             if err.is::<TimedOut>() {
                 return true;
             }
+            #[cfg(feature = "__tls")]
+            {
+                if err.is::<TlsHandshakeTimedOut>() {
+                    return true;
+                }
+            }
             source = err.source();
         }
 
@@ -172,6 +196,8 @@ impl fmt::Display for Error {
 
         match self.inner.kind {
             Kind::Builder => f.write_str("builder error")?,
+            Kind::TlsBackend => f.write_str("error initializing TLS backend")?,
+            Kind::TlsCertificate => f.write_str("invalid TLS certificate or identity")?,
             Kind::Request => f.write_str("error sending request")?,
             Kind::Body => f.write_str("request or response body error")?,
             Kind::Decode => f.write_str("error decoding response body")?,
@@ -220,6 +246,10 @@ impl From<crate::error::Error> for js_sys::Error {
 #[derive(Debug)]
 pub(crate) enum Kind {
     Builder,
+    #[cfg_attr(not(feature = "__tls"), allow(unused))]
+    TlsBackend,
+    #[cfg_attr(not(feature = "__tls"), allow(unused))]
+    TlsCertificate,
     Request,
     Redirect,
     Status(StatusCode),
@@ -233,6 +263,16 @@ pub(crate) fn builder<E: Into<BoxError>>(e: E) -> Error {
     Error::new(Kind::Builder, Some(e))
 }
 
+#[cfg_attr(not(feature = "__tls"), allow(unused))]
+pub(crate) fn tls_backend<E: Into<BoxError>>(e: E) -> Error {
+    Error::new(Kind::TlsBackend, Some(e))
+}
+
+#[cfg_attr(not(feature = "__tls"), allow(unused))]
+pub(crate) fn tls_certificate<E: Into<BoxError>>(e: E) -> Error {
+    Error::new(Kind::TlsCertificate, Some(e))
+}
+
 pub(crate) fn body<E: Into<BoxError>>(e: E) -> Error {
     Error::new(Kind::Body, Some(e))
 }
@@ -295,6 +335,20 @@ impl fmt::Display for TimedOut {
 
 impl StdError for TimedOut {}
 
+#[cfg(feature = "__tls")]
+#[derive(Debug)]
+pub(crate) struct TlsHandshakeTimedOut;
+
+#[cfg(feature = "__tls")]
+impl fmt::Display for TlsHandshakeTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("TLS handshake timed out")
+    }
+}
+
+#[cfg(feature = "__tls")]
+impl StdError for TlsHandshakeTimedOut {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,8 +2,16 @@ use url::Url;
 
 /// A trait to try to convert some type into a `Url`.
 ///
+/// Strings are parsed following the [WHATWG URL Standard], like a browser
+/// does. Characters that aren't allowed in a URL, such as spaces, are
+/// percent-encoded in the path, query and fragment rather than rejected,
+/// so `"http://example.com/a b?q=c d"` is sent as
+/// `http://example.com/a%20b?q=c%20d`.
+///
 /// This trait is "sealed", such that only types within reqwest can
 /// implement it.
+///
+/// [WHATWG URL Standard]: https://url.spec.whatwg.org/
 pub trait IntoUrl: IntoUrlSealed {}
 
 impl IntoUrl for Url {}
@@ -35,7 +43,17 @@ impl IntoUrlSealed for Url {
 
 impl<'a> IntoUrlSealed for &'a str {
     fn into_url(self) -> crate::Result<Url> {
-        Url::parse(self).map_err(crate::error::builder)?.into_url()
+        Url::parse(self)
+            .map_err(|err| match err {
+                url::ParseError::InvalidIpv6Address if has_ipv6_zone_id(self) => {
+                    crate::error::builder(
+                        "IPv6 zone identifiers are not supported in URLs; \
+                         use `ClientBuilder::resolve_to_addrs` with a scoped address instead",
+                    )
+                }
+                err => crate::error::builder(err),
+            })?
+            .into_url()
     }
 
     fn as_str(&self) -> &str {
@@ -63,6 +81,17 @@ impl<'a> IntoUrlSealed for String {
     }
 }
 
+/// Whether the host of `url` looks like an IPv6 literal with a zone
+/// identifier, such as `[fe80::1%25eth0]`.
+fn has_ipv6_zone_id(url: &str) -> bool {
+    url.find('[')
+        .and_then(|start| {
+            let rest = &url[start..];
+            rest.find(']').map(|end| rest[..end].contains('%'))
+        })
+        .unwrap_or(false)
+}
+
 if_hyper! {
     pub(crate) fn expect_uri(url: &Url) -> http::Uri {
         url.as_str()
@@ -87,4 +116,14 @@ mod tests {
             "builder error for url (file:///etc/hosts): URL scheme is not allowed"
         );
     }
+
+    #[test]
+    fn into_url_ipv6_zone_id() {
+        let err = "http://[fe80::1%25eth0]/".into_url().unwrap_err();
+        assert!(err.is_builder());
+        assert!(err.to_string().contains("zone identifiers"));
+
+        let err = "http://[fe80::1/".into_url().unwrap_err();
+        assert!(!err.to_string().contains("zone identifiers"));
+    }
 }
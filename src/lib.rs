@@ -185,9 +185,14 @@
 //! - **brotli**: Provides response body brotli decompression.
 //! - **deflate**: Provides response body deflate decompression.
 //! - **json**: Provides serialization and deserialization for JSON bodies.
+//! - **cbor**: Provides serialization and deserialization for CBOR bodies.
+//! - **msgpack**: Provides serialization and deserialization for MessagePack bodies.
 //! - **multipart**: Provides functionality for multipart forms.
 //! - **stream**: Adds support for `futures::Stream`.
+//! - **fs**: Provides saving response bodies to files, and streaming files
+//!   into async multipart forms.
 //! - **socks**: Provides SOCKS5 proxy support.
+//! - **pinning**: Provides public key pinning for TLS connections.
 //! - **trust-dns**: Enables a trust-dns async resolver instead of default
 //!   threadpool using `getaddrinfo`.
 //!
@@ -295,15 +300,24 @@ if_hyper! {
 
     pub use self::async_impl::{
         Body, Client, ClientBuilder, Request, RequestBuilder, Response, ResponseBuilderExt,
+        Timings,
     };
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "deflate"))]
+    pub use self::async_impl::Encoding;
+    #[cfg(feature = "stream")]
+    pub use self::async_impl::SseEvent;
+    pub use self::auth::AuthChallenge;
     pub use self::proxy::Proxy;
     #[cfg(feature = "__tls")]
     pub use self::tls::{Certificate, Identity};
+    #[cfg(feature = "__rustls")]
+    pub use self::tls::CertificateChain;
     #[cfg(feature = "multipart")]
     pub use self::async_impl::multipart;
 
 
     mod async_impl;
+    mod auth;
     #[cfg(feature = "blocking")]
     pub mod blocking;
     mod connect;
@@ -311,6 +325,7 @@ if_hyper! {
     pub mod cookie;
     #[cfg(feature = "trust-dns")]
     mod dns;
+    mod nested_form;
     mod proxy;
     pub mod redirect;
     #[cfg(feature = "__tls")]
@@ -3,8 +3,12 @@ use rustls::{
     internal::msgs::handshake::DigitallySignedStruct, HandshakeSignatureValid, RootCertStore,
     ServerCertVerified, ServerCertVerifier, TLSError,
 };
+#[cfg(feature = "__rustls")]
+use std::collections::HashSet;
 use std::fmt;
 #[cfg(feature = "__rustls")]
+use std::sync::Arc;
+#[cfg(feature = "__rustls")]
 use tokio_rustls::webpki::DNSNameRef;
 
 /// Represents a server X509 certificate.
@@ -59,7 +63,8 @@ impl Certificate {
     pub fn from_der(der: &[u8]) -> crate::Result<Certificate> {
         Ok(Certificate {
             #[cfg(feature = "native-tls-crate")]
-            native: native_tls_crate::Certificate::from_der(der).map_err(crate::error::builder)?,
+            native: native_tls_crate::Certificate::from_der(der)
+                .map_err(crate::error::tls_certificate)?,
             #[cfg(feature = "__rustls")]
             original: Cert::Der(der.to_owned()),
         })
@@ -84,12 +89,41 @@ impl Certificate {
     pub fn from_pem(pem: &[u8]) -> crate::Result<Certificate> {
         Ok(Certificate {
             #[cfg(feature = "native-tls-crate")]
-            native: native_tls_crate::Certificate::from_pem(pem).map_err(crate::error::builder)?,
+            native: native_tls_crate::Certificate::from_pem(pem)
+                .map_err(crate::error::tls_certificate)?,
             #[cfg(feature = "__rustls")]
             original: Cert::Pem(pem.to_owned()),
         })
     }
 
+    /// Returns the DER encoding of this certificate.
+    ///
+    /// If the certificate was created from a PEM bundle, this is the first
+    /// certificate in it.
+    pub fn to_der(&self) -> crate::Result<Vec<u8>> {
+        #[cfg(feature = "__rustls")]
+        {
+            use rustls::internal::pemfile;
+
+            match self.original {
+                Cert::Der(ref buf) => Ok(buf.clone()),
+                Cert::Pem(ref buf) => pemfile::certs(&mut &buf[..])
+                    .ok()
+                    .and_then(|certs| certs.into_iter().next())
+                    .map(|cert| cert.0)
+                    .ok_or_else(|| {
+                        crate::error::tls_certificate(TLSError::General(String::from(
+                            "No valid certificate was found",
+                        )))
+                    }),
+            }
+        }
+        #[cfg(not(feature = "__rustls"))]
+        {
+            self.native.to_der().map_err(crate::error::tls_certificate)
+        }
+    }
+
     #[cfg(feature = "native-tls-crate")]
     pub(crate) fn add_to_native_tls(self, tls: &mut native_tls_crate::TlsConnectorBuilder) {
         tls.add_root_certificate(self.native);
@@ -104,18 +138,18 @@ impl Certificate {
             Cert::Der(buf) => tls
                 .root_store
                 .add(&::rustls::Certificate(buf))
-                .map_err(|e| crate::error::builder(TLSError::WebPKIError(e)))?,
+                .map_err(|e| crate::error::tls_certificate(TLSError::WebPKIError(e)))?,
             Cert::Pem(buf) => {
                 let mut pem = Cursor::new(buf);
                 let certs = pemfile::certs(&mut pem).map_err(|_| {
-                    crate::error::builder(TLSError::General(String::from(
+                    crate::error::tls_certificate(TLSError::General(String::from(
                         "No valid certificate was found",
                     )))
                 })?;
                 for c in certs {
                     tls.root_store
                         .add(&c)
-                        .map_err(|e| crate::error::builder(TLSError::WebPKIError(e)))?;
+                        .map_err(|e| crate::error::tls_certificate(TLSError::WebPKIError(e)))?;
                 }
             }
         }
@@ -160,7 +194,7 @@ impl Identity {
         Ok(Identity {
             inner: ClientCert::Pkcs12(
                 native_tls_crate::Identity::from_pkcs12(der, password)
-                    .map_err(crate::error::builder)?,
+                    .map_err(crate::error::tls_certificate)?,
             ),
         })
     }
@@ -197,7 +231,7 @@ impl Identity {
             let mut pem = Cursor::new(buf);
             let certs = pemfile::certs(&mut pem)
                 .map_err(|_| TLSError::General(String::from("No valid certificate was found")))
-                .map_err(crate::error::builder)?;
+                .map_err(crate::error::tls_certificate)?;
             pem.set_position(0);
             let mut sk = pemfile::pkcs8_private_keys(&mut pem)
                 .and_then(|pkcs8_keys| {
@@ -212,13 +246,13 @@ impl Identity {
                     pemfile::rsa_private_keys(&mut pem)
                 })
                 .map_err(|_| TLSError::General(String::from("No valid private key was found")))
-                .map_err(crate::error::builder)?;
+                .map_err(crate::error::tls_certificate)?;
             if let (Some(sk), false) = (sk.pop(), certs.is_empty()) {
                 (sk, certs)
             } else {
-                return Err(crate::error::builder(TLSError::General(String::from(
-                    "private key or certificate not found",
-                ))));
+                return Err(crate::error::tls_certificate(TLSError::General(
+                    String::from("private key or certificate not found"),
+                )));
             }
         };
 
@@ -227,6 +261,40 @@ impl Identity {
         })
     }
 
+    /// Creates an identity from a rustls private key and certificate chain.
+    ///
+    /// This avoids a round-trip through PEM for keys and certificates that
+    /// are already loaded, including keys that `from_pem` can't parse. The
+    /// chain starts with the end-entity certificate.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `certs` is empty, or if rustls can't sign with `key`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the `rustls-tls(-...)` Cargo feature enabled.
+    #[cfg(feature = "__rustls")]
+    pub fn from_rustls(
+        certs: Vec<rustls::Certificate>,
+        key: rustls::PrivateKey,
+    ) -> crate::Result<Identity> {
+        if certs.is_empty() {
+            return Err(crate::error::tls_certificate(TLSError::General(
+                String::from("No valid certificate was found"),
+            )));
+        }
+        rustls::sign::any_supported_type(&key).map_err(|_| {
+            crate::error::tls_certificate(TLSError::General(String::from(
+                "Unsupported private key type",
+            )))
+        })?;
+
+        Ok(Identity {
+            inner: ClientCert::Pem { key, certs },
+        })
+    }
+
     #[cfg(feature = "native-tls")]
     pub(crate) fn add_to_native_tls(
         self,
@@ -238,7 +306,9 @@ impl Identity {
                 Ok(())
             }
             #[cfg(feature = "__rustls")]
-            ClientCert::Pem { .. } => Err(crate::error::builder("incompatible TLS identity type")),
+            ClientCert::Pem { .. } => Err(crate::error::tls_certificate(
+                "incompatible TLS identity type",
+            )),
         }
     }
 
@@ -247,11 +317,13 @@ impl Identity {
         match self.inner {
             ClientCert::Pem { key, certs } => {
                 tls.set_single_client_cert(certs, key)
-                    .map_err(|e| crate::error::builder(e))?;
+                    .map_err(crate::error::tls_certificate)?;
                 Ok(())
             }
             #[cfg(feature = "native-tls")]
-            ClientCert::Pkcs12(..) => Err(crate::error::builder("incompatible TLS identity type")),
+            ClientCert::Pkcs12(..) => Err(crate::error::tls_certificate(
+                "incompatible TLS identity type",
+            )),
         }
     }
 }
@@ -268,6 +340,106 @@ impl fmt::Debug for Identity {
     }
 }
 
+/// The certificates presented by the server, stored in the extensions of
+/// every `Response` received over a TLS connection.
+#[derive(Clone)]
+pub(crate) struct PeerCertificates(pub(crate) Vec<Certificate>);
+
+/// The protocol selected with ALPN during the TLS handshake, stored in the
+/// extensions of every `Response` received over a connection that
+/// negotiated one.
+#[derive(Clone)]
+pub(crate) struct AlpnProtocol(pub(crate) Vec<u8>);
+
+/// SHA-256 hashes of the public keys a server's certificate must match.
+#[cfg(feature = "pinning")]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PinnedKeys(Vec<[u8; 32]>);
+
+#[cfg(feature = "pinning")]
+impl PinnedKeys {
+    /// Adds a pin given as a base64 encoded SHA-256 hash of a
+    /// `SubjectPublicKeyInfo`, like the ones used by `curl --pinnedpubkey`.
+    pub(crate) fn add(&mut self, sha256_base64: &str) -> crate::Result<()> {
+        let hash = base64::decode(sha256_base64.trim_start_matches("sha256//"))
+            .ok()
+            .filter(|hash| hash.len() == 32)
+            .ok_or_else(|| crate::error::builder("invalid pinned public key"))?;
+        let mut pin = [0; 32];
+        pin.copy_from_slice(&hash);
+        self.0.push(pin);
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks that the public key of the DER encoded `cert` matches one of
+    /// the pins.
+    pub(crate) fn verify(&self, cert: &[u8]) -> Result<(), crate::error::BoxError> {
+        let spki = subject_public_key_info(cert).ok_or("invalid peer certificate")?;
+        let hash = ring::digest::digest(&ring::digest::SHA256, spki);
+        if self.0.iter().any(|pin| &pin[..] == hash.as_ref()) {
+            Ok(())
+        } else {
+            Err("peer certificate does not match any pinned public key".into())
+        }
+    }
+}
+
+/// Finds the encoded `subjectPublicKeyInfo` of a DER encoded X.509
+/// certificate.
+#[cfg(feature = "pinning")]
+fn subject_public_key_info(cert: &[u8]) -> Option<&[u8]> {
+    const SEQUENCE: u8 = 0x30;
+    const VERSION: u8 = 0xa0;
+
+    // Splits the next DER element off `input`, returning its tag, its
+    // contents, and what comes after it.
+    fn next(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+        let tag = *input.first()?;
+        let first = *input.get(1)? as usize;
+        let (len, header) = if first < 0x80 {
+            (first, 2)
+        } else {
+            let octets = first & 0x7f;
+            if octets == 0 || octets > 4 {
+                return None;
+            }
+            let len = input
+                .get(2..2 + octets)?
+                .iter()
+                .fold(0usize, |len, &b| (len << 8) | b as usize);
+            (len, 2 + octets)
+        };
+        let end = header.checked_add(len)?;
+        let contents = input.get(header..end)?;
+        Some((tag, contents, &input[end..]))
+    }
+
+    let (tag, cert, _) = next(cert)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let (tag, mut tbs, _) = next(cert)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    if *tbs.first()? == VERSION {
+        tbs = next(tbs)?.2;
+    }
+    // serialNumber, signature, issuer, validity, subject
+    for _ in 0..5 {
+        tbs = next(tbs)?.2;
+    }
+    let (tag, _, rest) = next(tbs)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    Some(&tbs[..tbs.len() - rest.len()])
+}
+
 pub(crate) enum TlsBackend {
     #[cfg(feature = "default-tls")]
     Default,
@@ -346,6 +518,177 @@ impl ServerCertVerifier for NoVerifier {
     }
 }
 
+/// The certificates a server presented during the TLS handshake.
+///
+/// This is passed to a verifier set with
+/// [`ClientBuilder::custom_cert_verifier`](crate::ClientBuilder::custom_cert_verifier).
+#[cfg(feature = "__rustls")]
+pub struct CertificateChain {
+    certs: Vec<Certificate>,
+    server_name: String,
+}
+
+#[cfg(feature = "__rustls")]
+impl CertificateChain {
+    /// Get the server's own certificate.
+    pub fn end_entity(&self) -> &Certificate {
+        &self.certs[0]
+    }
+
+    /// Get the intermediate certificates the server sent, if any.
+    pub fn intermediates(&self) -> &[Certificate] {
+        &self.certs[1..]
+    }
+
+    /// Get the name of the server being connected to.
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+}
+
+#[cfg(feature = "__rustls")]
+impl fmt::Debug for CertificateChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CertificateChain")
+            .field("server_name", &self.server_name)
+            .field("len", &self.certs.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "__rustls")]
+pub(crate) type CertVerifierFn = Arc<dyn Fn(&CertificateChain) -> bool + Send + Sync>;
+
+/// Verifies server certificates with a user provided function, instead of
+/// against the root certificates.
+///
+/// The handshake signatures are still checked against the presented
+/// certificate by the default methods.
+#[cfg(feature = "__rustls")]
+pub(crate) struct CustomVerifier(pub(crate) CertVerifierFn);
+
+#[cfg(feature = "__rustls")]
+impl ServerCertVerifier for CustomVerifier {
+    fn verify_server_cert(
+        &self,
+        _roots: &RootCertStore,
+        presented_certs: &[rustls::Certificate],
+        dns_name: DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        let certs = presented_certs
+            .iter()
+            .map(|cert| Certificate::from_der(&cert.0))
+            .collect::<crate::Result<Vec<_>>>()
+            .map_err(|e| TLSError::General(e.to_string()))?;
+        if certs.is_empty() {
+            return Err(TLSError::NoCertificatesPresented);
+        }
+        let server_name: &str = dns_name.into();
+        let chain = CertificateChain {
+            certs,
+            server_name: server_name.to_owned(),
+        };
+        if (self.0)(&chain) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(TLSError::General(String::from(
+                "certificate rejected by custom verifier",
+            )))
+        }
+    }
+}
+
+/// Accepts any certificate from some hosts, and verifies the others with
+/// `inner`.
+#[cfg(feature = "__rustls")]
+pub(crate) struct PerHostVerifier {
+    pub(crate) insecure_hosts: HashSet<String>,
+    pub(crate) inner: Arc<dyn ServerCertVerifier>,
+}
+
+#[cfg(feature = "__rustls")]
+impl ServerCertVerifier for PerHostVerifier {
+    fn verify_server_cert(
+        &self,
+        roots: &RootCertStore,
+        presented_certs: &[rustls::Certificate],
+        dns_name: DNSNameRef,
+        ocsp_response: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        let server_name: &str = dns_name.into();
+        if self
+            .insecure_hosts
+            .contains(&server_name.to_ascii_lowercase())
+        {
+            return Ok(ServerCertVerified::assertion());
+        }
+        self.inner
+            .verify_server_cert(roots, presented_certs, dns_name, ocsp_response)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::Certificate,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TLSError> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::Certificate,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TLSError> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+}
+
+/// Verifies server certificates with `inner`, noting each verification so
+/// that a handshake that resumed a session, and skipped it, can be told
+/// apart from a full one.
+#[cfg(feature = "__rustls")]
+pub(crate) struct VerificationRecorder(pub(crate) Arc<dyn ServerCertVerifier>);
+
+#[cfg(feature = "__rustls")]
+impl ServerCertVerifier for VerificationRecorder {
+    fn verify_server_cert(
+        &self,
+        roots: &RootCertStore,
+        presented_certs: &[rustls::Certificate],
+        dns_name: DNSNameRef,
+        ocsp_response: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        use crate::connect::timing::{record, Phase};
+
+        let verified =
+            self.0
+                .verify_server_cert(roots, presented_certs, dns_name, ocsp_response)?;
+        record(Phase::Verified);
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::Certificate,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TLSError> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::Certificate,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TLSError> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,25 +696,29 @@ mod tests {
     #[cfg(feature = "default-tls")]
     #[test]
     fn certificate_from_der_invalid() {
-        Certificate::from_der(b"not der").unwrap_err();
+        let err = Certificate::from_der(b"not der").unwrap_err();
+        assert!(err.is_tls_certificate());
     }
 
     #[cfg(feature = "default-tls")]
     #[test]
     fn certificate_from_pem_invalid() {
-        Certificate::from_pem(b"not pem").unwrap_err();
+        let err = Certificate::from_pem(b"not pem").unwrap_err();
+        assert!(err.is_tls_certificate());
     }
 
     #[cfg(feature = "native-tls")]
     #[test]
     fn identity_from_pkcs12_der_invalid() {
-        Identity::from_pkcs12_der(b"not der", "nope").unwrap_err();
+        let err = Identity::from_pkcs12_der(b"not der", "nope").unwrap_err();
+        assert!(err.is_tls_certificate());
     }
 
     #[cfg(feature = "__rustls")]
     #[test]
     fn identity_from_pem_invalid() {
-        Identity::from_pem(b"not pem").unwrap_err();
+        let err = Identity::from_pem(b"not pem").unwrap_err();
+        assert!(err.is_tls_certificate());
     }
 
     #[cfg(feature = "__rustls")]
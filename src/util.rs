@@ -1,4 +1,8 @@
 use crate::header::HeaderMap;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::header::{HeaderName, HeaderValue, FORWARDED};
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::IpAddr;
 
 // xor-shift
 #[cfg(not(target_arch = "wasm32"))]
@@ -63,3 +67,90 @@ pub(crate) fn replace_headers(dst: &mut HeaderMap, src: HeaderMap) {
         }
     }
 }
+
+/// Records `ip` as the next hop in the `X-Forwarded-For` and `Forwarded`
+/// headers, keeping any hops that are already listed.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn append_forwarded_for(headers: &mut HeaderMap, ip: IpAddr) {
+    // Both headers are lists, and a request may carry several lines of
+    // them, so fold any existing values into one before appending.
+    fn append(headers: &mut HeaderMap, name: HeaderName, elem: &str) {
+        let mut value = Vec::new();
+        for prev in headers.get_all(&name) {
+            value.extend_from_slice(prev.as_bytes());
+            value.extend_from_slice(b", ");
+        }
+        value.extend_from_slice(elem.as_bytes());
+        let value = HeaderValue::from_bytes(&value).expect("joined header values are valid");
+        headers.insert(name, value);
+    }
+
+    append(
+        headers,
+        HeaderName::from_static("x-forwarded-for"),
+        &ip.to_string(),
+    );
+
+    // RFC 7239 requires IPv6 nodes to be bracketed and quoted.
+    let node = match ip {
+        IpAddr::V4(ip) => format!("for={}", ip),
+        IpAddr::V6(ip) => format!("for=\"[{}]\"", ip),
+    };
+    append(headers, FORWARDED, &node);
+}
+
+/// Checks that `tags` is a valid `Accept-Language` value, such as
+/// `"fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5"`.
+pub(crate) fn is_valid_accept_language(tags: &str) -> bool {
+    fn is_valid_range(range: &str) -> bool {
+        if range == "*" {
+            return true;
+        }
+        range.split('-').enumerate().all(|(i, subtag)| {
+            let valid_chars = if i == 0 {
+                subtag.bytes().all(|b| b.is_ascii_alphabetic())
+            } else {
+                subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+            };
+            !subtag.is_empty() && subtag.len() <= 8 && valid_chars
+        })
+    }
+
+    fn is_valid_weight(param: &str) -> bool {
+        let qvalue = match param.trim().strip_prefix("q=") {
+            Some(qvalue) => qvalue,
+            None => return false,
+        };
+        let (int, frac) = match qvalue.find('.') {
+            Some(dot) => (&qvalue[..dot], &qvalue[dot + 1..]),
+            None => (qvalue, ""),
+        };
+        frac.len() <= 3
+            && match int {
+                "0" => frac.bytes().all(|b| b.is_ascii_digit()),
+                "1" => frac.bytes().all(|b| b == b'0'),
+                _ => false,
+            }
+    }
+
+    tags.split(',').all(|item| {
+        let mut parts = item.split(';');
+        let range = parts.next().unwrap_or("").trim();
+        is_valid_range(range) && parts.all(is_valid_weight)
+    })
+}
+
+/// Deserializes `slice` with `deserialize`, checking that nothing but
+/// whitespace follows the value.
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+pub(crate) fn json_with<T, F>(slice: &[u8], deserialize: F) -> crate::Result<T>
+where
+    F: FnOnce(
+        &mut serde_json::Deserializer<serde_json::de::SliceRead<'_>>,
+    ) -> serde_json::Result<T>,
+{
+    let mut de = serde_json::Deserializer::from_slice(slice);
+    let value = deserialize(&mut de).map_err(crate::error::decode)?;
+    de.end().map_err(crate::error::decode)?;
+    Ok(value)
+}
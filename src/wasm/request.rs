@@ -10,7 +10,7 @@ use url::Url;
 use web_sys::RequestCredentials;
 
 use super::{Body, Client, Response};
-use crate::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use crate::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT_LANGUAGE, CONTENT_TYPE};
 
 /// A request which can be executed with `Client::execute()`.
 pub struct Request {
@@ -155,6 +155,27 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the query string of the URL as-is, without re-encoding it.
+    ///
+    /// This replaces any existing query string, including parameters added
+    /// with [`RequestBuilder::query`]. A leading `?` is ignored, and an empty
+    /// string removes the query.
+    ///
+    /// # Note
+    /// The caller is responsible for the encoding: percent escapes and
+    /// separators like `&` and `=` are sent exactly as given.
+    pub fn query_raw(mut self, query: &str) -> RequestBuilder {
+        if let Ok(ref mut req) = self.request {
+            let query = query.strip_prefix('?').unwrap_or(query);
+            if query.is_empty() {
+                req.url_mut().set_query(None);
+            } else {
+                req.url_mut().set_query(Some(query));
+            }
+        }
+        self
+    }
+
     /// Send a form body.
     pub fn form<T: Serialize + ?Sized>(mut self, form: &T) -> RequestBuilder {
         let mut error = None;
@@ -197,6 +218,74 @@ impl RequestBuilder {
         self
     }
 
+    /// Send a CBOR body.
+    ///
+    /// The `Content-Type` header is set to `application/cbor`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cbor` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `T`'s implementation of `Serialize` decides to
+    /// fail.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub fn cbor<T: Serialize>(mut self, cbor: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            let mut body = Vec::new();
+            match ciborium::ser::into_writer(cbor, &mut body) {
+                Ok(()) => {
+                    req.headers_mut()
+                        .insert(CONTENT_TYPE, HeaderValue::from_static("application/cbor"));
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
+    /// Send a MessagePack body.
+    ///
+    /// The `Content-Type` header is set to `application/msgpack`. Structs
+    /// are encoded as maps keyed by field name.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `msgpack` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `T`'s implementation of `Serialize` decides to
+    /// fail.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub fn msgpack<T: Serialize + ?Sized>(mut self, msgpack: &T) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match rmp_serde::to_vec_named(msgpack) {
+                Ok(body) => {
+                    req.headers_mut().insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/msgpack"),
+                    );
+                    *req.body_mut() = Some(body.into());
+                }
+                Err(err) => error = Some(crate::error::builder(err)),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
     /// Enable HTTP bearer authentication.
     pub fn bearer_auth<T>(self, token: T) -> RequestBuilder
     where
@@ -206,6 +295,26 @@ impl RequestBuilder {
         self.header(crate::header::AUTHORIZATION, header_value)
     }
 
+    /// Set the `Accept-Language` header of this request.
+    ///
+    /// The value is a comma-separated list of language tags with optional
+    /// weights, such as `"fr-CH, fr;q=0.9, en;q=0.8"`.
+    pub fn accept_language(mut self, tags: &str) -> RequestBuilder {
+        let mut error = None;
+        if let Ok(ref mut req) = self.request {
+            match HeaderValue::from_str(tags) {
+                Ok(value) if crate::util::is_valid_accept_language(tags) => {
+                    req.headers_mut().insert(ACCEPT_LANGUAGE, value);
+                }
+                _ => error = Some(crate::error::builder("invalid Accept-Language value")),
+            }
+        }
+        if let Some(err) = error {
+            self.request = Err(err);
+        }
+        self
+    }
+
     /// Set the request body.
     pub fn body<T: Into<Body>>(mut self, body: T) -> RequestBuilder {
         if let Ok(ref mut req) = self.request {
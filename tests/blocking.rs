@@ -64,6 +64,58 @@ fn test_response_copy_to() {
     assert_eq!(dst, b"Hello");
 }
 
+#[test]
+fn test_response_bytes_iter() {
+    let chunk = vec![b'x'; 16 * 1024];
+    let expected_len = chunk.len() * 64;
+    let server = server::http(move |_req| {
+        let chunks = vec![Ok::<_, std::convert::Infallible>(chunk.clone()); 64];
+        async move { http::Response::new(hyper::Body::wrap_stream(futures_util::stream::iter(chunks))) }
+    });
+
+    let url = format!("http://{}/large", server.addr());
+    let mut res = reqwest::blocking::get(&url).unwrap();
+
+    let mut total = 0;
+    let mut count = 0;
+    for chunk in res.bytes_iter() {
+        let chunk = chunk.unwrap();
+        assert!(chunk.iter().all(|&b| b == b'x'));
+        total += chunk.len();
+        count += 1;
+    }
+    assert_eq!(total, expected_len);
+    assert!(count > 1);
+
+    // Continuing after a partial read with `Read`.
+    let mut res = reqwest::blocking::get(&url).unwrap();
+    let mut first = [0; 10];
+    std::io::Read::read_exact(&mut res, &mut first).unwrap();
+    let rest: usize = res.bytes_iter().map(|chunk| chunk.unwrap().len()).sum();
+    assert_eq!(first.len() + rest, expected_len);
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn test_response_save_to() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let path = std::env::temp_dir().join(format!(
+        "reqwest-blocking-save-to-{}.txt",
+        std::process::id()
+    ));
+
+    let written = reqwest::blocking::get(format!("http://{}/save", server.addr()))
+        .unwrap()
+        .save_to(&path)
+        .unwrap();
+
+    assert_eq!(written, 5);
+    assert_eq!(std::fs::read(&path).unwrap(), b"Hello");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
 #[test]
 fn test_get() {
     let server = server::http(move |_req| async { http::Response::default() });
@@ -291,6 +343,51 @@ fn test_blocking_inside_a_runtime() {
     });
 }
 
+#[test]
+fn test_blocking_on_provided_runtime() {
+    let server = server::http(move |_req| async { http::Response::new("Hello".into()) });
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .expect("new rt");
+
+    let client = reqwest::blocking::Client::builder()
+        .runtime(rt.handle().clone())
+        .build()
+        .expect("client builder");
+
+    for _ in 0..3 {
+        let url = format!("http://{}/runtime", server.addr());
+        let res = client.get(&url).send().unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        assert_eq!(res.text().unwrap(), "Hello");
+    }
+}
+
+#[test]
+fn test_blocking_on_shut_down_runtime() {
+    let server = server::http(move |_req| async { http::Response::default() });
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("new rt");
+
+    let client = reqwest::blocking::Client::builder()
+        .runtime(rt.handle().clone())
+        .build()
+        .expect("client builder");
+    drop(rt);
+
+    let url = format!("http://{}/runtime", server.addr());
+    let err = client.get(&url).send().unwrap_err();
+    assert!(err.is_request());
+    assert!(err.to_string().contains("has shut down"), "{}", err);
+}
+
 #[cfg(feature = "default-tls")]
 #[test]
 fn test_allowed_methods_blocking() {
@@ -328,3 +425,156 @@ fn test_body_from_bytes() {
 
     assert_eq!(request.body().unwrap().as_bytes(), Some(body.as_bytes()));
 }
+
+#[test]
+fn test_post_from_reader() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.method(), "POST");
+        assert_eq!(req.headers()["transfer-encoding"], "chunked");
+        assert_eq!(req.headers().get("content-length"), None);
+
+        let data = hyper::body::to_bytes(req.into_body()).await.unwrap();
+        http::Response::new(data.into())
+    });
+
+    let reader = std::io::Cursor::new(b"streamed from a reader".to_vec());
+
+    let url = format!("http://{}/reader", server.addr());
+    let res = reqwest::blocking::Client::new()
+        .post(&url)
+        .body(reqwest::blocking::Body::new(reader))
+        .send()
+        .expect("request send");
+
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.text().unwrap(), "streamed from a reader");
+}
+
+#[test]
+fn test_from_async_shares_pool() {
+    use std::sync::{Arc, Mutex};
+
+    let _ = env_logger::try_init();
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("runtime");
+
+    let peers = Arc::new(Mutex::new(Vec::new()));
+    let server = {
+        let peers = peers.clone();
+        server::http_with_peers(move |peer, _req| {
+            peers.lock().unwrap().push(peer);
+            async { http::Response::new("Hello".into()) }
+        })
+    };
+    let url = format!("http://{}/shared", server.addr());
+
+    let async_client = reqwest::Client::new();
+    let text = rt.block_on(async {
+        let res = async_client.get(&url).send().await.expect("async request");
+        res.text().await.expect("async text")
+    });
+    assert_eq!(text, "Hello");
+
+    let client = reqwest::blocking::Client::from_async(async_client).expect("from_async");
+    let text = client.get(&url).send().unwrap().text().unwrap();
+    assert_eq!(text, "Hello");
+
+    let peers = peers.lock().unwrap();
+    assert_eq!(peers.len(), 2);
+    assert_eq!(peers[0], peers[1]);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack_named_fields_and_decode_error() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        label: String,
+    }
+
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["content-type"], "application/msgpack");
+        // Structs are sent as maps keyed by field name, not as arrays.
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+        assert_eq!(body[0], 0x82, "a map of 2 entries");
+        assert!(body.windows(5).any(|w| w == b"label"));
+        // 0xc1 is never used by MessagePack.
+        http::Response::builder()
+            .header("content-type", "application/msgpack")
+            .body(vec![0xc1].into())
+            .unwrap()
+    });
+
+    let point = Point {
+        x: 1,
+        label: "origin-ish".to_owned(),
+    };
+
+    let url = format!("http://{}/msgpack", server.addr());
+    let err = reqwest::blocking::Client::new()
+        .post(&url)
+        .msgpack(&point)
+        .send()
+        .unwrap()
+        .msgpack::<Point>()
+        .unwrap_err();
+    assert!(err.is_decode());
+}
+
+#[test]
+fn test_bytes_limited() {
+    let server =
+        server::http(move |_req| async { http::Response::new("0123456789abcdefghij".into()) });
+    let url = format!("http://{}/limited", server.addr());
+    let client = reqwest::blocking::Client::new();
+
+    let mut res = client.get(&url).send().unwrap();
+    assert_eq!(res.bytes_truncated(10).unwrap(), "0123456789");
+
+    let mut res = client.get(&url).send().unwrap();
+    assert!(res.bytes_limited(10).unwrap_err().is_body());
+
+    let mut res = client.get(&url).send().unwrap();
+    assert_eq!(res.bytes_limited(20).unwrap(), "0123456789abcdefghij");
+
+    let mut res = client.get(&url).send().unwrap();
+    assert_eq!(
+        res.bytes_limited(usize::MAX).unwrap(),
+        "0123456789abcdefghij"
+    );
+}
+
+#[test]
+fn test_core_thread_panic_is_propagated() {
+    let server = server::http(move |_req| async { http::Response::default() });
+    let url = format!("http://{}/panic", server.addr());
+
+    let client = reqwest::blocking::Client::builder()
+        .request_interceptor(|req| {
+            if req.url().path() == "/panic" {
+                panic!("interceptor exploded");
+            }
+        })
+        .build()
+        .unwrap();
+
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _ = client.get(&url).send();
+    }))
+    .expect_err("send should panic");
+    assert_eq!(panic.downcast_ref::<&str>(), Some(&"interceptor exploded"));
+
+    // The core thread survives, and keeps serving requests.
+    let res = client
+        .get(format!("http://{}/ok", server.addr()))
+        .send()
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
@@ -146,3 +146,40 @@ async fn brotli_case(response_size: usize, chunk_size: usize) {
     let body = res.text().await.expect("text");
     assert_eq!(body, content);
 }
+
+#[tokio::test]
+async fn brotli_request_body() {
+    let content = "compress me ".repeat(1_000);
+    let expected = content.clone();
+
+    let server = server::http(move |req| {
+        let expected = expected.clone();
+        async move {
+            assert_eq!(req.headers()["content-encoding"], "br");
+            assert_eq!(req.headers()["content-type"], "text/plain");
+            assert!(req.headers().get("content-length").is_none());
+
+            let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            assert!(body.len() < expected.len());
+
+            let mut inflated = String::new();
+            brotli_crate::Decompressor::new(&body[..], 4096)
+                .read_to_string(&mut inflated)
+                .unwrap();
+            assert_eq!(inflated, expected);
+
+            http::Response::default()
+        }
+    });
+
+    let res = reqwest::Client::new()
+        .post(format!("http://{}/upload", server.addr()))
+        .header("content-type", "text/plain")
+        .body(content)
+        .brotli()
+        .send()
+        .await
+        .expect("response");
+
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
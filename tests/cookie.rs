@@ -201,3 +201,123 @@ async fn cookie_store_path() {
     let url = format!("http://{}/subpath", server.addr());
     client.get(&url).send().await.unwrap();
 }
+
+#[tokio::test]
+async fn cookie_store_relaxed_ip_host() {
+    let server = server::http(move |req| async move {
+        if req.uri() == "/" {
+            http::Response::builder()
+                .header("Set-Cookie", "session=abc; Domain=localhost; Secure")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            let cookie = req
+                .headers()
+                .get("cookie")
+                .map(|v| v.to_str().unwrap().to_owned())
+                .unwrap_or_default();
+            http::Response::new(cookie.into())
+        }
+    });
+
+    let url = format!("http://{}/", server.addr());
+    let check = format!("http://{}/check", server.addr());
+
+    let strict = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    strict.get(&url).send().await.unwrap();
+    let body = strict
+        .get(&check)
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(body, "");
+
+    let relaxed = reqwest::Client::builder()
+        .cookie_store_relaxed(true)
+        .build()
+        .unwrap();
+    relaxed.get(&url).send().await.unwrap();
+    let body = relaxed
+        .get(&check)
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(body, "session=abc");
+}
+
+#[tokio::test]
+async fn cookie_store_on_change() {
+    use reqwest::cookie::CookieChange;
+    use std::sync::{Arc, Mutex};
+
+    let server = server::http(move |req| async move {
+        let mut res = http::Response::builder();
+        match req.uri().path() {
+            "/login" => {
+                res = res
+                    .header("Set-Cookie", "session=abc; HttpOnly")
+                    .header("Set-Cookie", "short=lived; Max-Age=1")
+                    // for another domain, so the store rejects it
+                    .header("Set-Cookie", "other=x; Domain=example.com");
+            }
+            "/logout" => {
+                res = res.header("Set-Cookie", "session=; Max-Age=0");
+            }
+            _ => (),
+        }
+        res.body(Default::default()).unwrap()
+    });
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let seen = changes.clone();
+    let client = reqwest::Client::builder()
+        .cookie_store(true)
+        .on_cookie_change(move |change| {
+            let change = match change {
+                CookieChange::Stored(cookie) => ("stored", cookie.name().to_owned()),
+                CookieChange::Removed(cookie) => ("removed", cookie.name().to_owned()),
+                _ => unreachable!(),
+            };
+            seen.lock().unwrap().push(change);
+        })
+        .build()
+        .unwrap();
+
+    let get = |path: &str| {
+        client
+            .get(format!("http://{}{}", server.addr(), path))
+            .send()
+    };
+
+    get("/login").await.unwrap();
+    assert_eq!(
+        changes.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![
+            ("stored", "session".to_owned()),
+            ("stored", "short".to_owned()),
+        ]
+    );
+
+    // `short` expires on its own, and is reported with the next response.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    get("/noop").await.unwrap();
+    assert_eq!(
+        changes.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![("removed", "short".to_owned())]
+    );
+
+    get("/logout").await.unwrap();
+    assert_eq!(
+        changes.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![("removed", "session".to_owned())]
+    );
+}
@@ -37,6 +37,32 @@ async fn test_gzip_empty_body() {
     assert_eq!(body, "");
 }
 
+#[tokio::test]
+async fn head_skips_gzip_decoding() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.method(), "HEAD");
+
+        http::Response::builder()
+            .header("content-encoding", "gzip")
+            .header("content-length", 100)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let res = reqwest::Client::new()
+        .head(format!("http://{}/gzip", server.addr()))
+        .force_decode(reqwest::Encoding::Gzip)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.headers()["content-encoding"], "gzip");
+    assert_eq!(res.headers()["content-length"], "100");
+
+    let body = res.text().await.expect("no decode error");
+    assert_eq!(body, "");
+}
+
 #[tokio::test]
 async fn test_accept_header_is_not_changed_if_set() {
     let server = server::http(move |req| async move {
@@ -86,6 +112,104 @@ async fn test_accept_encoding_header_is_not_changed_if_set() {
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
 
+#[tokio::test]
+async fn gzip_content_type() {
+    let content = "application/x-gzip body";
+    let gzipped = gzip_encode(content);
+
+    let server = server::http(move |_req| {
+        let gzipped = gzipped.clone();
+        async move {
+            http::Response::builder()
+                .header("content-type", "application/x-gzip")
+                .header("content-length", gzipped.len())
+                .body(gzipped.into())
+                .unwrap()
+        }
+    });
+    let url = format!("http://{}/x-gzip", server.addr());
+
+    let res = reqwest::Client::new().get(&url).send().await.unwrap();
+    assert_ne!(res.bytes().await.unwrap(), content.as_bytes());
+
+    let client = reqwest::Client::builder()
+        .decode_gzip_content_type(true)
+        .build()
+        .unwrap();
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.headers()["content-type"], "application/x-gzip");
+    assert_eq!(res.content_length(), None);
+    assert_eq!(res.text().await.unwrap(), content);
+}
+
+fn gzip_encode(content: &str) -> Vec<u8> {
+    let mut encoder = libflate::gzip::Encoder::new(Vec::new()).unwrap();
+    encoder.write_all(content.as_bytes()).unwrap();
+    encoder.finish().into_result().unwrap()
+}
+
+async fn accept_encoding_case(advertise: bool, decompress: bool) {
+    let content = "advertise and decompress separately";
+    let gzipped = gzip_encode(content);
+    let expected = gzipped.clone();
+
+    let server = server::http(move |req| {
+        let gzipped = gzipped.clone();
+        async move {
+            let accept_encoding = req
+                .headers()
+                .get("accept-encoding")
+                .map(|value| value.to_str().unwrap())
+                .unwrap_or("");
+            assert_eq!(accept_encoding.contains("gzip"), advertise);
+            http::Response::builder()
+                .header("content-encoding", "gzip")
+                .body(gzipped.into())
+                .unwrap()
+        }
+    });
+
+    let client = reqwest::Client::builder()
+        .accept_encoding(advertise)
+        .auto_decompress(decompress)
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://{}/gzip", server.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.headers().contains_key("content-encoding"), !decompress);
+    let body = res.bytes().await.unwrap();
+    if decompress {
+        assert_eq!(body, content.as_bytes());
+    } else {
+        assert_eq!(body, expected);
+    }
+}
+
+#[tokio::test]
+async fn accept_encoding_and_auto_decompress() {
+    accept_encoding_case(true, true).await;
+}
+
+#[tokio::test]
+async fn accept_encoding_without_auto_decompress() {
+    accept_encoding_case(true, false).await;
+}
+
+#[tokio::test]
+async fn auto_decompress_without_accept_encoding() {
+    accept_encoding_case(false, true).await;
+}
+
+#[tokio::test]
+async fn no_accept_encoding_no_auto_decompress() {
+    accept_encoding_case(false, false).await;
+}
+
 async fn gzip_case(response_size: usize, chunk_size: usize) {
     use futures_util::stream::StreamExt;
 
@@ -150,3 +274,135 @@ async fn gzip_case(response_size: usize, chunk_size: usize) {
     let body = res.text().await.expect("text");
     assert_eq!(body, content);
 }
+
+#[tokio::test]
+async fn truncated_gzip_keeps_decoded_prefix() {
+    let content: String = (0..200_000u64)
+        .map(|i| (b'a' + (i.wrapping_mul(i).wrapping_mul(2_654_435_761) >> 7) as u8 % 26) as char)
+        .collect();
+    let mut gzipped = gzip_encode(&content);
+    gzipped.truncate(gzipped.len() * 2 / 3);
+
+    let server = server::http(move |_req| {
+        let chunks = gzipped
+            .chunks(100)
+            .map(|chunk| Ok::<_, std::convert::Infallible>(chunk.to_vec()))
+            .collect::<Vec<_>>();
+        async move {
+            http::Response::builder()
+                .header("content-encoding", "gzip")
+                .body(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)))
+                .unwrap()
+        }
+    });
+
+    let url = format!("http://{}/truncated", server.addr());
+
+    let err = reqwest::get(&url).await.unwrap().bytes().await.unwrap_err();
+    assert!(err.is_decode());
+
+    let (bytes, err) = reqwest::get(&url).await.unwrap().bytes_partial().await;
+    assert!(err.unwrap().is_decode());
+    assert!(!bytes.is_empty());
+    assert!(bytes.len() < content.len());
+    assert_eq!(&bytes[..], &content.as_bytes()[..bytes.len()]);
+}
+
+#[tokio::test]
+async fn gzip_skip_content_types() {
+    let gzipped = gzip_encode("not really a png");
+    let body = gzipped.clone();
+    let server = server::http(move |req| {
+        let body = body.clone();
+        async move {
+            let accept_encoding = req
+                .headers()
+                .get("accept-encoding")
+                .map(|v| v.to_str().unwrap().to_owned())
+                .unwrap_or_default();
+            let content_type = if req.uri() == "/image" {
+                assert!(!accept_encoding.contains("gzip"), "{}", accept_encoding);
+                "image/png"
+            } else {
+                assert!(accept_encoding.contains("gzip"), "{}", accept_encoding);
+                "text/plain"
+            };
+            http::Response::builder()
+                .header("content-type", content_type)
+                .header("content-encoding", "gzip")
+                .body(body.into())
+                .unwrap()
+        }
+    });
+
+    let client = reqwest::Client::builder()
+        .gzip(true)
+        .gzip_skip_content_types(&["image/*"])
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://{}/image", server.addr()))
+        .header("accept", "image/png, image/webp;q=0.9")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["content-encoding"], "gzip");
+    assert_eq!(res.bytes().await.unwrap(), gzipped);
+
+    let res = client
+        .get(format!("http://{}/text", server.addr()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("content-encoding"), None);
+    assert_eq!(res.text().await.unwrap(), "not really a png");
+}
+
+#[tokio::test]
+async fn force_decode_without_content_encoding() {
+    let content = "gzipped without saying so";
+    let gzipped = gzip_encode(content);
+
+    let server = server::http(move |_req| {
+        let gzipped = gzipped.clone();
+        async move {
+            http::Response::builder()
+                .header("content-length", gzipped.len())
+                .body(gzipped.into())
+                .unwrap()
+        }
+    });
+
+    let client = reqwest::Client::builder().no_gzip().build().unwrap();
+    let res = client
+        .get(format!("http://{}/gzip", server.addr()))
+        .force_decode(reqwest::Encoding::Gzip)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.content_length(), None);
+    assert_eq!(res.text().await.unwrap(), content);
+}
+
+#[tokio::test]
+async fn gzip_multiple_members() {
+    let mut body = gzip_encode("first member, ");
+    body.extend(gzip_encode("second member"));
+
+    let server = server::http(move |_req| {
+        let body = body.clone();
+        async move {
+            http::Response::builder()
+                .header("content-encoding", "gzip")
+                .body(body.into())
+                .unwrap()
+        }
+    });
+
+    let res = reqwest::get(format!("http://{}/members", server.addr()))
+        .await
+        .unwrap();
+    assert_eq!(res.text().await.unwrap(), "first member, second member");
+}
@@ -56,6 +56,88 @@ async fn text_part() {
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
 
+#[test]
+fn seeded_boundary_is_reproducible() {
+    fn xorshift(seed: u64) -> impl FnMut() -> u64 {
+        let mut state = seed;
+        move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        }
+    }
+
+    let a = reqwest::multipart::Form::new_with_rng(xorshift(7));
+    let b = reqwest::multipart::Form::new_with_rng(xorshift(7));
+    let c = reqwest::multipart::Form::new_with_rng(xorshift(8));
+    assert_eq!(a.boundary(), b.boundary());
+    assert_ne!(a.boundary(), c.boundary());
+
+    let mut rng = xorshift(7);
+    let expected = format!(
+        "{:016x}-{:016x}-{:016x}-{:016x}",
+        rng(),
+        rng(),
+        rng(),
+        rng()
+    );
+    assert_eq!(a.boundary(), expected);
+
+    // The default source is still random.
+    assert_ne!(
+        reqwest::multipart::Form::new().boundary(),
+        reqwest::multipart::Form::new().boundary()
+    );
+}
+
+#[tokio::test]
+async fn try_clone_text_form() {
+    let _ = env_logger::try_init();
+
+    let form = reqwest::multipart::Form::new().text("foo", "bar").part(
+        "data",
+        reqwest::multipart::Part::bytes(&b"\x00\x01"[..]).file_name("data.bin"),
+    );
+
+    let expected_body = format!(
+        "\
+         --{0}\r\n\
+         Content-Disposition: form-data; name=\"foo\"\r\n\r\n\
+         bar\r\n\
+         --{0}\r\n\
+         Content-Disposition: form-data; name=\"data\"; filename=\"data.bin\"\r\n\r\n\
+         \x00\x01\r\n\
+         --{0}--\r\n\
+         ",
+        form.boundary()
+    );
+
+    let server = server::http(move |req| {
+        let expected_body = expected_body.clone();
+        async move {
+            let full = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            assert_eq!(full, expected_body.as_bytes());
+            http::Response::default()
+        }
+    });
+
+    let url = format!("http://{}/multipart/clone", server.addr());
+    let client = reqwest::Client::new();
+
+    let copy = form.try_clone().expect("in-memory form is cloneable");
+    let res = client.post(&url).multipart(form).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    let res = client.post(&url).multipart(copy).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let stream = reqwest::multipart::Part::stream(reqwest::Body::from(hyper::Body::empty()));
+    let form = reqwest::multipart::Form::new()
+        .text("foo", "bar")
+        .part("stream", stream);
+    assert!(form.try_clone().is_none());
+}
+
 #[cfg(feature = "stream")]
 #[tokio::test]
 async fn stream_part() {
@@ -30,6 +30,49 @@ async fn http_proxy() {
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
 
+#[tokio::test]
+async fn http_proxy_basic_auth_on_redirect() {
+    let server = server::http(move |req| {
+        assert_eq!(
+            req.headers()["proxy-authorization"],
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+        assert_eq!(req.headers()["user-agent"], "redirect-ua");
+
+        async move {
+            if req.uri() == "http://hyper.rs/start" {
+                http::Response::builder()
+                    .status(302)
+                    .header("location", "http://rust-lang.org/end")
+                    .body(Default::default())
+                    .unwrap()
+            } else {
+                assert_eq!(req.uri(), "http://rust-lang.org/end");
+                http::Response::default()
+            }
+        }
+    });
+
+    let proxy = format!("http://{}", server.addr());
+
+    let res = reqwest::Client::builder()
+        .proxy(
+            reqwest::Proxy::http(&proxy)
+                .unwrap()
+                .basic_auth("Aladdin", "open sesame"),
+        )
+        .user_agent("redirect-ua")
+        .build()
+        .unwrap()
+        .get("http://hyper.rs/start")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().as_str(), "http://rust-lang.org/end");
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
+
 #[tokio::test]
 async fn http_proxy_basic_auth() {
     let url = "http://hyper.rs/prox";
@@ -162,6 +205,108 @@ async fn test_no_proxy() {
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_no_proxy_then_proxy() {
+    let url = "http://hyper.rs/prox";
+    let server = server::http(move |req| {
+        assert_eq!(req.uri(), url);
+
+        async { http::Response::default() }
+    });
+    let proxy = format!("http://{}", server.addr());
+
+    // The first proxy is cleared, so only the second one is used.
+    let res = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::http("http://127.0.0.1:1").unwrap())
+        .no_proxy()
+        .proxy(reqwest::Proxy::http(&proxy).unwrap())
+        .build()
+        .unwrap()
+        .get(url)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.url().as_str(), url);
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn per_request_proxy_override() {
+    let url = "http://hyper.rs/prox";
+    let proxy_server = server::http(move |req| {
+        assert_eq!(req.uri(), url);
+
+        async { http::Response::default() }
+    });
+    let direct_server = server::http(move |req| {
+        assert_eq!(req.uri(), "/direct");
+
+        async { http::Response::default() }
+    });
+    let proxy = format!("http://{}", proxy_server.addr());
+    let direct = format!("http://{}/direct", direct_server.addr());
+
+    let client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::http("http://127.0.0.1:1").unwrap())
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(url)
+        .proxy(Some(reqwest::Proxy::http(&proxy).unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.url().as_str(), url);
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert!(res.timings().connect().is_some());
+    res.bytes().await.unwrap();
+
+    // An equal override reuses the connection made for the first one.
+    let res = client
+        .get(url)
+        .proxy(Some(reqwest::Proxy::http(&proxy).unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.timings().connect(), None);
+
+    let res = client.get(&direct).proxy(None).send().await.unwrap();
+    assert_eq!(res.url().as_str(), direct);
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    // Requests without an override still use the client's proxy.
+    let err = client.get(&direct).send().await.unwrap_err();
+    assert!(err.is_connect());
+}
+
+#[tokio::test]
+async fn per_request_proxy_pools_evict_least_recently_used() {
+    let url = "http://hyper.rs/prox";
+    let proxy_server = server::http(move |_req| async { http::Response::default() });
+    let proxy = format!("http://{}", proxy_server.addr());
+
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    let send = |user: String| {
+        let proxy = reqwest::Proxy::http(&proxy).unwrap().basic_auth(&user, "");
+        client.get(url).proxy(Some(proxy)).send()
+    };
+
+    let res = send("warm".into()).await.unwrap();
+    assert!(res.timings().connect().is_some());
+    res.bytes().await.unwrap();
+
+    // Many other proxies come and go, but the pool in steady use stays.
+    for i in 0..20 {
+        send(format!("user{}", i)).await.unwrap();
+        let res = send("warm".into()).await.unwrap();
+        assert_eq!(res.timings().connect(), None);
+        res.bytes().await.unwrap();
+    }
+}
+
 #[cfg_attr(not(feature = "__internal_proxy_sys_no_cache"), ignore)]
 #[tokio::test]
 async fn test_using_system_proxy() {
@@ -221,3 +366,90 @@ async fn http_over_http() {
     assert_eq!(res.url().as_str(), url);
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
+
+#[tokio::test]
+async fn response_via_proxy() {
+    let server = server::http(move |_req| async { http::Response::default() });
+
+    let proxy = format!("http://{}", server.addr());
+    let res = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::http(&proxy).unwrap())
+        .build()
+        .unwrap()
+        .get("http://hyper.rs/prox")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.via_proxy());
+
+    let direct = format!("http://{}/direct", server.addr());
+    let res = reqwest::Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .get(&direct)
+        .send()
+        .await
+        .unwrap();
+    assert!(!res.via_proxy());
+}
+
+#[tokio::test]
+async fn response_proxy_url_custom_selector() {
+    let server = server::http(move |_req| async { http::Response::default() });
+
+    let proxy = format!("http://user:secret@{}", server.addr());
+    let client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::custom(move |url| {
+            if url.host_str() == Some("hyper.rs") {
+                Some(proxy.clone())
+            } else {
+                None
+            }
+        }))
+        .build()
+        .unwrap();
+
+    let res = client.get("http://hyper.rs/prox").send().await.unwrap();
+    let expected = format!("http://{}/", server.addr());
+    assert_eq!(res.proxy_url().map(|url| url.as_str()), Some(&*expected));
+
+    let direct = format!("http://{}/direct", server.addr());
+    let res = client.get(&direct).send().await.unwrap();
+    assert_eq!(res.proxy_url(), None);
+}
+
+#[tokio::test]
+async fn asterisk_form_through_http_proxy() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.uri(), "*");
+        http::Response::default()
+    });
+
+    let client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::http(format!("http://{}", server.addr())).unwrap())
+        .build()
+        .unwrap();
+
+    // The proxy would need the absolute-form to forward it.
+    let err = client
+        .request(reqwest::Method::OPTIONS, "http://hyper.rs")
+        .asterisk_form()
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.is_builder());
+
+    // Sent directly, it doesn't go through the proxy.
+    let res = client
+        .request(
+            reqwest::Method::OPTIONS,
+            format!("http://{}", server.addr()),
+        )
+        .asterisk_form()
+        .proxy(None)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
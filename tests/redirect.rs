@@ -240,6 +240,119 @@ async fn test_redirect_policy_can_stop_redirects_without_an_error() {
     assert_eq!(res.status(), reqwest::StatusCode::FOUND);
 }
 
+#[tokio::test]
+async fn test_unfollowed_redirect_location() {
+    let server = server::http(move |req| async move {
+        if req.uri() == "/docs/start" {
+            http::Response::builder()
+                .status(302)
+                .header("location", "../guide/intro?lang=en")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            http::Response::default()
+        }
+    });
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let res = client
+        .get(format!("http://{}/docs/start", server.addr()))
+        .send()
+        .await
+        .unwrap();
+    assert!(res.is_redirect());
+    assert_eq!(
+        res.location().unwrap().as_str(),
+        format!("http://{}/guide/intro?lang=en", server.addr())
+    );
+
+    let res = client
+        .get(format!("http://{}/other", server.addr()))
+        .send()
+        .await
+        .unwrap();
+    assert!(!res.is_redirect());
+    assert_eq!(res.location(), None);
+}
+
+#[tokio::test]
+async fn test_follow_redirects_one_hop_at_a_time() {
+    let server = server::http(move |req| async move {
+        let (status, location) = match (req.method().as_str(), req.uri().path()) {
+            ("POST", "/submit") => (303, "/step"),
+            ("GET", "/step") => (307, "/done"),
+            ("POST", "/temporary") => (307, "/done"),
+            ("GET", "/done") => return http::Response::new("done".into()),
+            (method, path) => panic!("unexpected request: {} {}", method, path),
+        };
+        http::Response::builder()
+            .status(status)
+            .header("location", location)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let res = client
+        .post(format!("http://{}/submit", server.addr()))
+        .body("form")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::SEE_OTHER);
+
+    let res = res.follow(&client).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(res.url().path(), "/step");
+
+    let res = res.follow(&client).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.url().path(), "/done");
+
+    let err = res.follow(&client).await.unwrap_err();
+    assert!(err.is_redirect());
+
+    // The body of the POST would have to be sent again.
+    let res = client
+        .post(format!("http://{}/temporary", server.addr()))
+        .body("form")
+        .send()
+        .await
+        .unwrap();
+    let err = res.follow(&client).await.unwrap_err();
+    assert!(err.is_redirect());
+
+    // Converting to an `http::Response` and back keeps the method.
+    let res = client
+        .post(format!("http://{}/temporary", server.addr()))
+        .body("form")
+        .send()
+        .await
+        .unwrap();
+    let res = reqwest::Response::from(http::Response::<reqwest::Body>::from(res));
+    let err = res.follow(&client).await.unwrap_err();
+    assert!(err.is_redirect());
+
+    // Without a request, the method to follow with isn't known.
+    let res = reqwest::Response::from(
+        http::Response::builder()
+            .status(307)
+            .header("location", format!("http://{}/done", server.addr()))
+            .body("")
+            .unwrap(),
+    );
+    let err = res.follow(&client).await.unwrap_err();
+    assert!(err.is_builder());
+}
+
 #[tokio::test]
 async fn test_referer_is_not_set_if_disabled() {
     let server = server::http(move |req| async move {
@@ -267,6 +380,278 @@ async fn test_referer_is_not_set_if_disabled() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_referer_query_stripped_cross_origin() {
+    let dst = server::http(move |req| async move {
+        assert_eq!(req.uri(), "/dst");
+        let referer = req.headers()["referer"].to_str().unwrap().to_owned();
+        assert!(referer.ends_with("/src"), "referer = {:?}", referer);
+
+        http::Response::default()
+    });
+
+    let dst_url = format!("http://{}/dst", dst.addr());
+    let src = server::http(move |req| {
+        let dst_url = dst_url.clone();
+        async move {
+            assert_eq!(req.uri(), "/src?token=secret");
+            http::Response::builder()
+                .status(302)
+                .header("location", dst_url)
+                .body(Default::default())
+                .unwrap()
+        }
+    });
+
+    let res = reqwest::Client::builder()
+        .referer_policy(reqwest::redirect::RefererPolicy::StripQueryWhenCrossOrigin)
+        .build()
+        .unwrap()
+        .get(format!("http://{}/src?token=secret", src.addr()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_redirect_loop_detected() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let hits = Arc::new(AtomicUsize::new(0));
+    let server = {
+        let hits = hits.clone();
+        server::http(move |req| {
+            hits.fetch_add(1, Ordering::SeqCst);
+            let location = if req.uri() == "/ping" {
+                "/pong"
+            } else {
+                "/ping"
+            };
+            async move {
+                http::Response::builder()
+                    .status(302)
+                    .header("location", location)
+                    .body(Default::default())
+                    .unwrap()
+            }
+        })
+    };
+
+    let url = format!("http://{}/ping", server.addr());
+    let err = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::detect_loops(
+            10,
+            reqwest::redirect::LoopDetection::Exact,
+        ))
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .await
+        .unwrap_err();
+
+    assert!(err.is_redirect());
+    assert_eq!(err.url().unwrap().path(), "/pong");
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn test_default_headers_kept_on_cross_host_redirect() {
+    let server = server::http(move |req| async move {
+        assert_eq!(req.headers()["user-agent"], "redirect-ua");
+        assert_eq!(req.headers()["x-default"], "kept");
+        assert!(req.headers()["accept-encoding"]
+            .to_str()
+            .unwrap()
+            .contains("gzip"));
+
+        if req.uri().path() == "/start" {
+            let port = req.headers()["host"]
+                .to_str()
+                .unwrap()
+                .rsplit(':')
+                .next()
+                .unwrap()
+                .to_owned();
+            http::Response::builder()
+                .status(302)
+                .header("location", format!("http://localhost:{}/end", port))
+                .body(Default::default())
+                .unwrap()
+        } else {
+            assert!(req.headers()["host"]
+                .to_str()
+                .unwrap()
+                .starts_with("localhost:"));
+            http::Response::default()
+        }
+    });
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-default", "kept".parse().unwrap());
+
+    let url = format!("http://{}/start", server.addr());
+    let res = reqwest::Client::builder()
+        .user_agent("redirect-ua")
+        .default_headers(headers)
+        .resolve("localhost", server.addr())
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.url().path(), "/end");
+    assert_eq!(res.redirect_count(), 1);
+}
+
+#[tokio::test]
+async fn test_redirect_count() {
+    let server = server::http(move |req| async move {
+        let location = match req.uri().path() {
+            "/first" => "/second",
+            "/second" => "/dst",
+            _ => return http::Response::default(),
+        };
+        http::Response::builder()
+            .status(302)
+            .header("location", location)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let url = format!("http://{}/first", server.addr());
+    let res = reqwest::Client::new().get(&url).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.url().path(), "/dst");
+    assert_eq!(res.redirect_count(), 2);
+
+    let res = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FOUND);
+    assert_eq!(res.redirect_count(), 0);
+}
+
+#[tokio::test]
+async fn test_redirect_responses() {
+    let server = server::http(move |req| async move {
+        let (status, location) = match req.uri().path() {
+            "/old" => (301, "/moved"),
+            "/moved" => (302, "/dst"),
+            _ => return http::Response::default(),
+        };
+        http::Response::builder()
+            .status(status)
+            .header("location", location)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let base = format!("http://{}", server.addr());
+    let url = format!("{}/old", base);
+    let res = reqwest::Client::builder()
+        .record_redirects(true)
+        .build()
+        .unwrap()
+        .get(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let hops = res
+        .redirect_responses()
+        .iter()
+        .map(|(status, url)| (*status, url.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hops,
+        vec![
+            (
+                reqwest::StatusCode::MOVED_PERMANENTLY,
+                &*format!("{}/moved", base)
+            ),
+            (reqwest::StatusCode::FOUND, &*format!("{}/dst", base)),
+        ]
+    );
+
+    // Not recorded by default.
+    let res = reqwest::get(&url).await.unwrap();
+    assert_eq!(res.redirect_count(), 2);
+    assert!(res.redirect_responses().is_empty());
+}
+
+// Redirected requests are sent with the default version, so a chain
+// started with HTTP/1.0 switches to HTTP/1.1 after the first hop.
+#[tokio::test]
+async fn test_redirect_versions() {
+    let server = server::http(move |req| async move {
+        let (status, location) = match req.uri().path() {
+            "/old" => {
+                assert_eq!(req.version(), http::Version::HTTP_10);
+                (301, "/moved")
+            }
+            "/moved" => (302, "/dst"),
+            _ => return http::Response::default(),
+        };
+        http::Response::builder()
+            .status(status)
+            .header("location", location)
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let url = format!("http://{}/old", server.addr());
+    let res = reqwest::Client::builder()
+        .record_redirects(true)
+        .build()
+        .unwrap()
+        .get(&url)
+        .version(reqwest::Version::HTTP_10)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.version(), reqwest::Version::HTTP_11);
+    assert_eq!(
+        res.redirect_versions(),
+        &[reqwest::Version::HTTP_10, reqwest::Version::HTTP_11][..]
+    );
+}
+
+#[tokio::test]
+async fn test_redirect_url_query_param() {
+    let server = server::http(move |req| async move {
+        if req.uri().path() == "/authorize" {
+            http::Response::builder()
+                .status(302)
+                .header("location", "/callback?code=abc&state=xyz&code=def")
+                .body(Default::default())
+                .unwrap()
+        } else {
+            http::Response::default()
+        }
+    });
+
+    let url = format!("http://{}/authorize", server.addr());
+    let res = reqwest::get(&url).await.unwrap();
+    assert_eq!(res.url().path(), "/callback");
+    assert_eq!(res.url_query_param("code").as_deref(), Some("abc"));
+    assert_eq!(res.url_query_param("state").as_deref(), Some("xyz"));
+    assert_eq!(res.url_query_param("missing"), None);
+}
+
 #[tokio::test]
 async fn test_invalid_location_stops_redirect_gh484() {
     let server = server::http(move |_req| async move {
@@ -316,3 +701,38 @@ async fn test_redirect_302_with_set_cookies() {
     assert_eq!(res.url().as_str(), dst);
     assert_eq!(res.status(), reqwest::StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_redirect_non_utf8_location() {
+    let server = server::http(move |req| async move {
+        if req.uri() == "/legacy" {
+            http::Response::builder()
+                .status(302)
+                .header(
+                    "location",
+                    http::HeaderValue::from_bytes(b"/caf\xe9?q=\xe9").unwrap(),
+                )
+                .body(Default::default())
+                .unwrap()
+        } else {
+            assert_eq!(req.uri(), "/caf%E9?q=%E9");
+            http::Response::new("found".into())
+        }
+    });
+
+    let url = format!("http://{}/legacy", server.addr());
+
+    // By default, the redirect isn't followed.
+    let res = reqwest::get(&url).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FOUND);
+    assert_eq!(res.url().as_str(), url);
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::default().percent_encode_location(true))
+        .build()
+        .unwrap();
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.url().path(), "/caf%E9");
+    assert_eq!(res.text().await.unwrap(), "found");
+}
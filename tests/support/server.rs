@@ -41,6 +41,16 @@ pub fn http<F, Fut>(func: F) -> Server
 where
     F: Fn(http::Request<hyper::Body>) -> Fut + Clone + Send + 'static,
     Fut: Future<Output = http::Response<hyper::Body>> + Send + 'static,
+{
+    http_with_peers(move |_peer, req| func(req))
+}
+
+/// Like `http`, but also passes `func` the client's address of the
+/// connection each request came on, to tell connections apart.
+pub fn http_with_peers<F, Fut>(func: F) -> Server
+where
+    F: Fn(net::SocketAddr, http::Request<hyper::Body>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = http::Response<hyper::Body>> + Send + 'static,
 {
     //Spawn new runtime in thread to prevent reactor execution context conflict
     thread::spawn(move || {
@@ -50,11 +60,12 @@ where
             .expect("new rt");
         let srv = rt.block_on(async move {
             hyper::Server::bind(&([127, 0, 0, 1], 0).into()).serve(hyper::service::make_service_fn(
-                move |_| {
+                move |conn: &hyper::server::conn::AddrStream| {
+                    let peer = conn.remote_addr();
                     let func = func.clone();
                     async move {
                         Ok::<_, Infallible>(hyper::service::service_fn(move |req| {
-                            let fut = func(req);
+                            let fut = func(peer, req);
                             async move { Ok::<_, Infallible>(fut.await) }
                         }))
                     }
@@ -90,3 +101,169 @@ where
     .join()
     .unwrap()
 }
+
+/// The CA that signed the certificate used by `https` servers.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub const CA_CERT: &[u8] = include_bytes!("certs/ca.pem");
+
+/// The leaf certificate presented by `https` servers, valid for `localhost`.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub const SERVER_CERT: &[u8] = include_bytes!("certs/server.pem");
+
+/// Like `http`, but serves over TLS using the test certificate in `certs/`.
+///
+/// Clients need to trust `CA_CERT`, and connect to `localhost`.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub fn https<F, Fut>(func: F) -> Server
+where
+    F: Fn(http::Request<hyper::Body>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = http::Response<hyper::Body>> + Send + 'static,
+{
+    use tokio_rustls::rustls::NoClientAuth;
+
+    serve_tls(NoClientAuth::new(), func)
+}
+
+/// The CA that signed the client certificates in `CLIENT_A` and `CLIENT_B`.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub const CLIENT_CA_CERT: &[u8] = include_bytes!("certs/client-ca.pem");
+
+/// A client identity (key and certificate) signed by `CLIENT_CA_CERT`.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub const CLIENT_A: &[u8] = include_bytes!("certs/client-a.pem");
+
+/// Another client identity signed by `CLIENT_CA_CERT`.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub const CLIENT_B: &[u8] = include_bytes!("certs/client-b.pem");
+
+/// The DER certificates a client presented during the TLS handshake.
+///
+/// `https_client_auth` servers add this to the extensions of every request.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+#[derive(Clone, Debug)]
+pub struct PeerCertificates(pub Vec<Vec<u8>>);
+
+/// Like `https`, but asks clients for a certificate signed by
+/// `CLIENT_CA_CERT`.
+///
+/// Clients without a certificate are still accepted. The certificates a
+/// client presented are available as the `PeerCertificates` extension.
+#[cfg(feature = "__rustls")]
+#[allow(unused)]
+pub fn https_client_auth<F, Fut>(func: F) -> Server
+where
+    F: Fn(http::Request<hyper::Body>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = http::Response<hyper::Body>> + Send + 'static,
+{
+    use tokio_rustls::rustls::internal::pemfile;
+    use tokio_rustls::rustls::{AllowAnyAnonymousOrAuthenticatedClient, RootCertStore};
+
+    let mut roots = RootCertStore::empty();
+    for cert in pemfile::certs(&mut &CLIENT_CA_CERT[..]).expect("client ca cert") {
+        roots.add(&cert).expect("add client ca cert");
+    }
+    serve_tls(AllowAnyAnonymousOrAuthenticatedClient::new(roots), func)
+}
+
+#[cfg(feature = "__rustls")]
+fn serve_tls<F, Fut>(
+    client_auth: std::sync::Arc<dyn tokio_rustls::rustls::ClientCertVerifier>,
+    func: F,
+) -> Server
+where
+    F: Fn(http::Request<hyper::Body>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = http::Response<hyper::Body>> + Send + 'static,
+{
+    use std::sync::Arc;
+    use tokio_rustls::rustls::internal::pemfile;
+    use tokio_rustls::rustls::{ServerConfig, Session};
+
+    let certs = pemfile::certs(&mut &SERVER_CERT[..]).expect("server cert");
+    let key = pemfile::pkcs8_private_keys(&mut &include_bytes!("certs/server.key")[..])
+        .expect("server key")
+        .remove(0);
+    let mut tls = ServerConfig::new(client_auth);
+    tls.set_single_cert(certs, key).expect("set_single_cert");
+    tls.set_protocols(&[b"h2".to_vec(), b"http/1.1".to_vec()]);
+    let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(tls));
+
+    //Spawn new runtime in thread to prevent reactor execution context conflict
+    thread::spawn(move || {
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("new rt");
+        let listener = rt.block_on(async {
+            tokio::net::TcpListener::bind(&net::SocketAddr::from(([127, 0, 0, 1], 0)))
+                .await
+                .expect("bind")
+        });
+
+        let addr = listener.local_addr().expect("local_addr");
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+        let srv = async move {
+            loop {
+                let tcp = tokio::select! {
+                    res = listener.accept() => res.expect("accept").0,
+                    _ = &mut shutdown_rx => break,
+                };
+                let acceptor = acceptor.clone();
+                let func = func.clone();
+                tokio::spawn(async move {
+                    // The client may hang up during the handshake, e.g. when
+                    // it rejects the certificate.
+                    let tls = match acceptor.accept(tcp).await {
+                        Ok(tls) => tls,
+                        Err(_) => return,
+                    };
+                    let peer_certs = PeerCertificates(
+                        tls.get_ref()
+                            .1
+                            .get_peer_certificates()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|cert| cert.0)
+                            .collect(),
+                    );
+                    let svc = hyper::service::service_fn(move |mut req| {
+                        req.extensions_mut().insert(peer_certs.clone());
+                        let fut = func(req);
+                        async move { Ok::<_, Infallible>(fut.await) }
+                    });
+                    let _ = hyper::server::conn::Http::new()
+                        .serve_connection(tls, svc)
+                        .await;
+                });
+            }
+        };
+
+        let (panic_tx, panic_rx) = std_mpsc::channel();
+        let tname = format!(
+            "test({})-support-server",
+            thread::current().name().unwrap_or("<unknown>")
+        );
+        thread::Builder::new()
+            .name(tname)
+            .spawn(move || {
+                rt.block_on(srv);
+                let _ = panic_tx.send(());
+            })
+            .expect("thread spawn");
+
+        Server {
+            addr,
+            panic_rx,
+            shutdown_tx: Some(shutdown_tx),
+        }
+    })
+    .join()
+    .unwrap()
+}
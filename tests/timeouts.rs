@@ -31,6 +31,72 @@ async fn client_timeout() {
     assert_eq!(err.url().map(|u| u.as_str()), Some(url.as_str()));
 }
 
+#[tokio::test]
+async fn client_timeout_spans_redirects() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        // each hop is well within the timeout, but not all of them together
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let hop: u32 = req.uri().path()[1..].parse().unwrap();
+        http::Response::builder()
+            .status(302)
+            .header("location", format!("/{}", hop + 1))
+            .body(Default::default())
+            .unwrap()
+    });
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(500))
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/0", server.addr());
+    let err = client.get(&url).send().await.unwrap_err();
+
+    assert!(err.is_timeout());
+    // the deadline is hit on the third hop
+    assert_eq!(
+        err.url().map(|u| u.path()),
+        Some("/2"),
+        "timeout should be shared across hops"
+    );
+}
+
+#[tokio::test]
+async fn total_timeout_fires_during_retry_backoff() {
+    let _ = env_logger::try_init();
+
+    // Nothing listens on this port once the listener is dropped, so every
+    // attempt fails to connect.
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    // Retries are sent after 200ms, then 400ms, so the second delay is
+    // still running when the total timeout passes.
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .total_timeout(Duration::from_millis(500))
+        .max_retries(5)
+        .retry_backoff(Duration::from_millis(200), Duration::from_secs(5), false)
+        .build()
+        .unwrap();
+
+    let url = format!("http://{}/busy", addr);
+    let start = std::time::Instant::now();
+    let err = client.get(&url).send().await.unwrap_err();
+
+    assert!(err.is_timeout());
+    let elapsed = start.elapsed();
+    assert!(
+        elapsed >= Duration::from_millis(500) && elapsed < Duration::from_secs(2),
+        "elapsed: {:?}",
+        elapsed
+    );
+}
+
 #[tokio::test]
 async fn request_timeout() {
     let _ = env_logger::try_init();
@@ -117,6 +183,69 @@ async fn response_timeout() {
     assert!(err.is_timeout());
 }
 
+#[tokio::test]
+async fn response_bytes_timeout() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        // immediate headers, the body is delayed on `/slow`
+        let delay = if req.uri() == "/slow" { 2000 } else { 0 };
+        let body = hyper::Body::wrap_stream(futures_util::stream::once(async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Ok::<_, std::convert::Infallible>("Hello")
+        }));
+        http::Response::new(body)
+    });
+
+    let client = reqwest::Client::new();
+
+    let url = format!("http://{}/slow", server.addr());
+    let mut res = client.get(&url).send().await.expect("headers arrive");
+    let err = res
+        .bytes_timeout(Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(err.is_timeout());
+    assert!(err.is_body());
+
+    let url = format!("http://{}/fast", server.addr());
+    let mut res = client.get(&url).send().await.expect("headers arrive");
+    let body = res.bytes_timeout(Duration::from_secs(5)).await.unwrap();
+    assert_eq!(body, "Hello");
+}
+
+#[test]
+fn default_timeouts() {
+    // The async client has no timeout unless one is set.
+    let client = reqwest::Client::new();
+    assert!(!format!("{:?}", client).contains("timeout"));
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .timeout_disabled()
+        .build()
+        .unwrap();
+    assert!(!format!("{:?}", client).contains("timeout"));
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn default_timeouts_blocking() {
+    assert_eq!(reqwest::blocking::DEFAULT_TIMEOUT, Duration::from_secs(30));
+
+    let client = reqwest::blocking::Client::new();
+    assert_eq!(
+        format!("{:?}", client),
+        format!("Client {{ timeout: {:?} }}", Some(Duration::from_secs(30)))
+    );
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout_disabled()
+        .build()
+        .unwrap();
+    assert_eq!(format!("{:?}", client), "Client { timeout: None }");
+}
+
 /// Tests that internal client future cancels when the oneshot channel
 /// is canceled.
 #[cfg(feature = "blocking")]
@@ -207,3 +336,223 @@ fn write_timeout_large_body() {
     assert!(err.is_timeout());
     assert_eq!(err.url().map(|u| u.as_str()), Some(url.as_str()));
 }
+
+#[cfg(feature = "__tls")]
+#[tokio::test]
+async fn tls_handshake_timeout() {
+    let _ = env_logger::try_init();
+
+    // Accepts TCP connections, but never answers the TLS handshake.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+
+    let client = reqwest::Client::builder()
+        .tls_handshake_timeout(Duration::from_millis(200))
+        .connect_timeout(Duration::from_secs(5))
+        .resolve("localhost", addr)
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let url = format!("https://localhost:{}/stall", addr.port());
+    let start = std::time::Instant::now();
+    let err = client.get(&url).send().await.unwrap_err();
+
+    assert!(err.is_timeout());
+    let mut source = std::error::Error::source(&err);
+    let mut handshake_timed_out = false;
+    while let Some(cause) = source {
+        handshake_timed_out |= cause.to_string() == "TLS handshake timed out";
+        source = cause.source();
+    }
+    assert!(handshake_timed_out, "{:?}", err);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+/// A server that sends `100 Continue` if `sends_continue` is set, and notes
+/// how long the body of the request took to arrive after its head.
+async fn expect_continue_server(
+    sends_continue: bool,
+) -> (
+    std::net::SocketAddr,
+    tokio::task::JoinHandle<(Vec<u8>, Duration)>,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0; 1024];
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        let head_end = buf.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        let head = String::from_utf8_lossy(&buf[..head_end]).to_lowercase();
+        assert!(head.contains("expect: 100-continue"));
+
+        let start = std::time::Instant::now();
+        if sends_continue {
+            stream
+                .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+                .await
+                .unwrap();
+        }
+        let mut body = buf[head_end..].to_vec();
+        while body.len() < 5 {
+            let n = stream.read(&mut chunk).await.unwrap();
+            body.extend_from_slice(&chunk[..n]);
+        }
+        let waited = start.elapsed();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        (body, waited)
+    });
+    (addr, server)
+}
+
+#[tokio::test]
+async fn expect_continue_timeout_sends_body() {
+    let _ = env_logger::try_init();
+
+    let (addr, server) = expect_continue_server(false).await;
+
+    let client = reqwest::Client::builder()
+        .expect_continue_timeout(Duration::from_millis(300))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let res = client
+        .post(format!("http://{}/upload", addr))
+        .header("expect", "100-continue")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let (body, waited) = server.await.unwrap();
+    assert_eq!(body, b"hello");
+    assert!(waited >= Duration::from_millis(250), "waited {:?}", waited);
+    assert!(waited < Duration::from_secs(1), "waited {:?}", waited);
+}
+
+#[tokio::test]
+async fn expect_continue_waits_a_second_by_default() {
+    let _ = env_logger::try_init();
+
+    let (addr, server) = expect_continue_server(false).await;
+
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+    let res = client
+        .post(format!("http://{}/upload", addr))
+        .header("expect", "100-continue")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let (body, waited) = server.await.unwrap();
+    assert_eq!(body, b"hello");
+    assert!(waited >= Duration::from_millis(900), "waited {:?}", waited);
+    assert!(waited < Duration::from_secs(2), "waited {:?}", waited);
+}
+
+#[tokio::test]
+async fn expect_continue_sends_body_once_continued() {
+    let _ = env_logger::try_init();
+
+    let (addr, server) = expect_continue_server(true).await;
+
+    let client = reqwest::Client::builder()
+        .expect_continue_timeout(Duration::from_secs(5))
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let res = client
+        .post(format!("http://{}/upload", addr))
+        .header("expect", "100-continue")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let (body, waited) = server.await.unwrap();
+    assert_eq!(body, b"hello");
+    assert!(waited < Duration::from_secs(1), "waited {:?}", waited);
+}
+
+#[tokio::test]
+async fn expect_continue_timeout_none_sends_body_right_away() {
+    let _ = env_logger::try_init();
+
+    let (addr, server) = expect_continue_server(false).await;
+
+    let client = reqwest::Client::builder()
+        .expect_continue_timeout(None)
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let res = client
+        .post(format!("http://{}/upload", addr))
+        .header("expect", "100-continue")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let (body, waited) = server.await.unwrap();
+    assert_eq!(body, b"hello");
+    assert!(waited < Duration::from_millis(250), "waited {:?}", waited);
+}
+
+// Needs another worker thread for the HTTP/2 connection, see tests/client.rs.
+#[tokio::test(flavor = "multi_thread")]
+async fn expect_continue_doesnt_wait_over_http2() {
+    let _ = env_logger::try_init();
+
+    let server = server::http(move |req| async move {
+        assert_eq!(req.version(), http::Version::HTTP_2);
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+        assert_eq!(body, "hello");
+        http::Response::default()
+    });
+
+    let client = reqwest::Client::builder()
+        .http2_prior_knowledge()
+        .no_proxy()
+        .build()
+        .unwrap();
+
+    let start = std::time::Instant::now();
+    let res = client
+        .post(format!("http://{}/upload", server.addr()))
+        .header("expect", "100-continue")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert!(
+        start.elapsed() < Duration::from_millis(500),
+        "waited {:?}",
+        start.elapsed()
+    );
+}